@@ -0,0 +1,97 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::{Alert, NotificationSink, SinkError, SinkFuture};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs a local command with the alert as JSON on stdin.
+///
+/// A non-zero exit status counts as a failed delivery and is retried.
+pub struct CommandSink {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandSink {
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+        }
+    }
+}
+
+impl NotificationSink for CommandSink {
+    fn describe(&self) -> String {
+        format!("command {}", self.program)
+    }
+
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let mut payload = serde_json::to_vec(alert)?;
+            payload.push(b'\n');
+
+            let mut child = Command::new(&self.program)
+                .args(&self.args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::inherit())
+                .kill_on_drop(true)
+                .spawn()?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(&payload).await?;
+                // Dropping stdin closes the pipe so the command sees EOF.
+            }
+
+            let status = tokio::time::timeout(TIMEOUT, child.wait())
+                .await
+                .map_err(|_| SinkError::Timeout)??;
+            if !status.success() {
+                return Err(SinkError::Exit(status));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::super::tests::{alert, sighting};
+    use super::*;
+
+    #[tokio::test]
+    async fn writes_alert_to_stdin() {
+        let path = std::env::temp_dir().join(format!("rubl-alert-{}.json", std::process::id()));
+        let sink = CommandSink::new("sh", vec!["-c".into(), format!("cat > {}", path.display())]);
+        sink.deliver(&alert(vec![sighting("S1")])).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(payload["sightings"][0]["subId"], "S1");
+    }
+
+    #[tokio::test]
+    async fn non_zero_exit_is_an_error() {
+        let sink = CommandSink::new("sh", vec!["-c".into(), "cat > /dev/null; exit 3".into()]);
+        let err = sink
+            .deliver(&alert(vec![sighting("S1")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SinkError::Exit(_)));
+    }
+
+    #[tokio::test]
+    async fn missing_program_is_an_error() {
+        let sink = CommandSink::new("/nonexistent/rubl-notify", vec![]);
+        let err = sink
+            .deliver(&alert(vec![sighting("S1")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SinkError::Io(_)));
+    }
+}
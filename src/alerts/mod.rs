@@ -0,0 +1,497 @@
+//! Notification sinks for rarity alerts.
+//!
+//! When a watch finds new notable observations, every configured sink whose
+//! regions match receives an [`Alert`]. Deliveries are retried with
+//! exponential backoff, and each sink remembers which sightings it has
+//! already delivered so overlapping watches do not alert twice.
+//!
+//! Sinks are configured in `alerts.json` in the data directory:
+//!
+//! ```json
+//! {
+//!   "maxAttempts": 3,
+//!   "sinks": [
+//!     { "type": "webhook", "url": "https://example.com/hook", "secret": "s3cret", "regions": ["US-NC"] },
+//!     { "type": "command", "program": "/usr/local/bin/notify", "args": ["--birds"] }
+//!   ]
+//! }
+//! ```
+
+mod command;
+mod webhook;
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub use command::CommandSink;
+pub use webhook::WebhookSink;
+
+use crate::storage;
+use crate::sync::lock;
+use crate::tools::observations::RareBird;
+use crate::watch::sighting_key;
+
+const CONFIG_FILE: &str = "alerts.json";
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(2);
+/// Upper bound on `maxAttempts`, however large the config asks for.
+const MAX_ATTEMPTS_CEILING: u32 = 10;
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+/// Delivered sighting keys remembered per sink.
+const DEDUP_CAPACITY: usize = 10_000;
+
+/// Payload delivered to sinks when a watch finds new rarities.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub watch_id: String,
+    /// Region code or point/radius the watch covers.
+    pub target: String,
+    pub detected_at: DateTime<Utc>,
+    pub sightings: Vec<RareBird>,
+}
+
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Webhook returned {0}")]
+    Status(reqwest::StatusCode),
+
+    #[error("Command failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Command exited with {0}")]
+    Exit(std::process::ExitStatus),
+
+    #[error("Command timed out")]
+    Timeout,
+
+    #[error("Failed to serialize alert: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<(), SinkError>> + Send + 'a>>;
+
+/// Destination for rarity alerts.
+pub trait NotificationSink: Send + Sync {
+    /// Short description used in logs.
+    fn describe(&self) -> String;
+
+    /// Delivers one alert. Errors are retried by the dispatcher.
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> SinkFuture<'a>;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertsConfig {
+    #[serde(default)]
+    max_attempts: Option<u32>,
+    #[serde(default)]
+    sinks: Vec<SinkConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum SinkConfig {
+    Webhook {
+        url: String,
+        secret: Option<String>,
+        #[serde(default)]
+        regions: Vec<String>,
+    },
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        regions: Vec<String>,
+    },
+}
+
+struct Route {
+    sink: Box<dyn NotificationSink>,
+    /// Region codes this sink wants; empty means every watch.
+    regions: Vec<String>,
+    delivered: Mutex<RecentKeys>,
+}
+
+impl Route {
+    /// A region watch matches a configured region if it is that region or
+    /// lies within it (US-NC-063 is within US-NC). Radius watches only match
+    /// sinks without a region filter.
+    fn matches(&self, region: Option<&str>) -> bool {
+        if self.regions.is_empty() {
+            return true;
+        }
+        let Some(region) = region else {
+            return false;
+        };
+        self.regions.iter().any(|configured| {
+            region.eq_ignore_ascii_case(configured)
+                || region
+                    .to_ascii_uppercase()
+                    .starts_with(&format!("{}-", configured.to_ascii_uppercase()))
+        })
+    }
+}
+
+/// Bounded set of recently delivered sighting keys.
+#[derive(Default)]
+struct RecentKeys {
+    order: VecDeque<String>,
+    keys: HashSet<String>,
+}
+
+impl RecentKeys {
+    fn contains(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+
+    fn insert(&mut self, key: String) {
+        if self.keys.insert(key.clone()) {
+            self.order.push_back(key);
+        }
+        while self.order.len() > DEDUP_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Fans alerts out to the configured sinks.
+#[derive(Clone)]
+pub struct AlertDispatcher {
+    routes: Arc<Vec<Route>>,
+    max_attempts: u32,
+    base_delay: Duration,
+}
+
+impl AlertDispatcher {
+    /// Loads sinks from `alerts.json`; no file means no sinks.
+    pub fn load() -> Self {
+        let config = storage::data_file(CONFIG_FILE)
+            .as_deref()
+            .map(storage::load_json::<AlertsConfig>)
+            .transpose()
+            .unwrap_or_else(|e| {
+                tracing::error!(error = %e, "Failed to load alert sinks");
+                None
+            })
+            .flatten();
+        let Some(config) = config else {
+            return Self::new(Vec::new());
+        };
+
+        let routes = config
+            .sinks
+            .into_iter()
+            .map(|sink| match sink {
+                SinkConfig::Webhook {
+                    url,
+                    secret,
+                    regions,
+                } => (
+                    Box::new(WebhookSink::new(url, secret)) as Box<dyn NotificationSink>,
+                    regions,
+                ),
+                SinkConfig::Command {
+                    program,
+                    args,
+                    regions,
+                } => (
+                    Box::new(CommandSink::new(program, args)) as Box<dyn NotificationSink>,
+                    regions,
+                ),
+            })
+            .collect();
+        let mut dispatcher = Self::new(routes);
+        if let Some(max_attempts) = config.max_attempts {
+            dispatcher = dispatcher.with_retry(max_attempts, DEFAULT_BASE_DELAY);
+        }
+        tracing::info!(sinks = dispatcher.routes.len(), "Loaded alert sinks");
+        dispatcher
+    }
+
+    pub fn new(sinks: Vec<(Box<dyn NotificationSink>, Vec<String>)>) -> Self {
+        let routes = sinks
+            .into_iter()
+            .map(|(sink, regions)| Route {
+                sink,
+                regions,
+                delivered: Mutex::new(RecentKeys::default()),
+            })
+            .collect();
+        Self {
+            routes: Arc::new(routes),
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+        }
+    }
+
+    pub fn with_retry(mut self, max_attempts: u32, base_delay: Duration) -> Self {
+        self.max_attempts = max_attempts.clamp(1, MAX_ATTEMPTS_CEILING);
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Delivers `alert` to every matching sink, skipping sightings a sink
+    /// has already received.
+    pub async fn dispatch(&self, region: Option<&str>, alert: &Alert) {
+        for route in self.routes.iter().filter(|r| r.matches(region)) {
+            let sightings: Vec<RareBird> = {
+                let delivered = lock(&route.delivered);
+                alert
+                    .sightings
+                    .iter()
+                    .filter(|s| !delivered.contains(&sighting_key(s)))
+                    .cloned()
+                    .collect()
+            };
+            if sightings.is_empty() {
+                continue;
+            }
+            let alert = Alert {
+                sightings,
+                ..alert.clone()
+            };
+
+            if self.deliver_with_retry(route.sink.as_ref(), &alert).await {
+                let mut delivered = lock(&route.delivered);
+                for sighting in &alert.sightings {
+                    delivered.insert(sighting_key(sighting));
+                }
+            }
+        }
+    }
+
+    async fn deliver_with_retry(&self, sink: &dyn NotificationSink, alert: &Alert) -> bool {
+        for attempt in 1..=self.max_attempts {
+            match sink.deliver(alert).await {
+                Ok(()) => return true,
+                Err(e) => {
+                    tracing::warn!(
+                        sink = %sink.describe(),
+                        attempt,
+                        max_attempts = self.max_attempts,
+                        error = %e,
+                        "Alert delivery failed"
+                    );
+                    if attempt < self.max_attempts {
+                        tokio::time::sleep(backoff(self.base_delay, attempt)).await;
+                    }
+                }
+            }
+        }
+        tracing::error!(sink = %sink.describe(), "Giving up on alert");
+        false
+    }
+}
+
+/// The delay after failed attempt `attempt`: doubling from `base`, capped
+/// at [`MAX_BACKOFF`].
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    2u32.checked_pow(attempt.saturating_sub(1))
+        .and_then(|factor| base.checked_mul(factor))
+        .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    pub(super) fn sighting(sub_id: &str) -> RareBird {
+        RareBird {
+            scientific_name: "Numenius phaeopus".into(),
+            count: Some(2),
+            location_id: Some("L123456".into()),
+            latitude: Some(35.84),
+            longitude: Some(-78.79),
+            species_code: Some("whimbr".into()),
+            submission_id: Some(sub_id.into()),
+            ..RareBird::test("Whimbrel", "Lake Crabtree", "2023-05-15 10:30")
+        }
+    }
+
+    pub(super) fn alert(sightings: Vec<RareBird>) -> Alert {
+        Alert {
+            watch_id: "w1".into(),
+            target: "US-NC".into(),
+            detected_at: Utc::now(),
+            sightings,
+        }
+    }
+
+    /// Fails the first `failures` deliveries, then records successes.
+    struct FlakySink {
+        failures: u32,
+        attempts: Arc<AtomicU32>,
+        delivered: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl NotificationSink for FlakySink {
+        fn describe(&self) -> String {
+            "flaky".into()
+        }
+
+        fn deliver<'a>(&'a self, alert: &'a Alert) -> SinkFuture<'a> {
+            Box::pin(async move {
+                let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+                if attempt <= self.failures {
+                    return Err(SinkError::Timeout);
+                }
+                lock(&self.delivered).push(alert.sightings.len());
+                Ok(())
+            })
+        }
+    }
+
+    fn flaky(failures: u32) -> (FlakySink, Arc<AtomicU32>, Arc<Mutex<Vec<usize>>>) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let sink = FlakySink {
+            failures,
+            attempts: attempts.clone(),
+            delivered: delivered.clone(),
+        };
+        (sink, attempts, delivered)
+    }
+
+    mod dispatch {
+        use super::*;
+
+        #[tokio::test]
+        async fn retries_until_success() {
+            let (sink, attempts, delivered) = flaky(2);
+            let dispatcher = AlertDispatcher::new(vec![(Box::new(sink), vec![])])
+                .with_retry(3, Duration::from_millis(1));
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S1")]))
+                .await;
+            assert_eq!(attempts.load(Ordering::SeqCst), 3);
+            assert_eq!(*lock(&delivered), vec![1]);
+        }
+
+        #[tokio::test]
+        async fn skips_sightings_already_delivered() {
+            let (sink, _, delivered) = flaky(0);
+            let dispatcher = AlertDispatcher::new(vec![(Box::new(sink), vec![])])
+                .with_retry(1, Duration::from_millis(1));
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S1")]))
+                .await;
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S1"), sighting("S2")]))
+                .await;
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S2")]))
+                .await;
+            assert_eq!(*lock(&delivered), vec![1, 1]);
+        }
+
+        #[tokio::test]
+        async fn failed_deliveries_are_not_marked_delivered() {
+            let (sink, attempts, delivered) = flaky(1);
+            let dispatcher = AlertDispatcher::new(vec![(Box::new(sink), vec![])])
+                .with_retry(1, Duration::from_millis(1));
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S1")]))
+                .await;
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S1")]))
+                .await;
+            assert_eq!(attempts.load(Ordering::SeqCst), 2);
+            assert_eq!(*lock(&delivered), vec![1]);
+        }
+
+        #[tokio::test]
+        async fn attempts_are_capped() {
+            let (sink, attempts, delivered) = flaky(u32::MAX);
+            let dispatcher = AlertDispatcher::new(vec![(Box::new(sink), vec![])])
+                .with_retry(u32::MAX, Duration::ZERO);
+            dispatcher
+                .dispatch(Some("US-NC"), &alert(vec![sighting("S1")]))
+                .await;
+            assert_eq!(attempts.load(Ordering::SeqCst), MAX_ATTEMPTS_CEILING);
+            assert!(lock(&delivered).is_empty());
+        }
+
+        #[test]
+        fn backoff_doubles_up_to_the_cap() {
+            let base = Duration::from_secs(2);
+            assert_eq!(backoff(base, 1), base);
+            assert_eq!(backoff(base, 3), base * 4);
+            assert_eq!(backoff(base, 9), MAX_BACKOFF);
+            assert_eq!(backoff(base, 40), MAX_BACKOFF);
+            assert_eq!(backoff(Duration::MAX, 2), MAX_BACKOFF);
+        }
+    }
+
+    mod regions {
+        use super::*;
+
+        fn route(regions: &[&str]) -> Route {
+            let (sink, _, _) = flaky(0);
+            Route {
+                sink: Box::new(sink),
+                regions: regions.iter().map(|r| r.to_string()).collect(),
+                delivered: Mutex::new(RecentKeys::default()),
+            }
+        }
+
+        #[test]
+        fn empty_filter_matches_everything() {
+            assert!(route(&[]).matches(Some("US-NC")));
+            assert!(route(&[]).matches(None));
+        }
+
+        #[test]
+        fn matches_region_and_its_subregions() {
+            let route = route(&["US-NC"]);
+            assert!(route.matches(Some("US-NC")));
+            assert!(route.matches(Some("us-nc-063")));
+            assert!(!route.matches(Some("US-NCX")));
+            assert!(!route.matches(Some("US-SC")));
+            assert!(!route.matches(None));
+        }
+    }
+
+    mod config {
+        use super::*;
+
+        #[test]
+        fn parses_both_sink_types() {
+            let config: AlertsConfig = serde_json::from_str(
+                r#"{
+                    "maxAttempts": 5,
+                    "sinks": [
+                        { "type": "webhook", "url": "http://localhost/hook", "secret": "s", "regions": ["US-NC"] },
+                        { "type": "command", "program": "notify-send" }
+                    ]
+                }"#,
+            )
+            .unwrap();
+            assert_eq!(config.max_attempts, Some(5));
+            assert!(
+                matches!(&config.sinks[0], SinkConfig::Webhook { regions, .. } if regions.len() == 1)
+            );
+            assert!(
+                matches!(&config.sinks[1], SinkConfig::Command { args, .. } if args.is_empty())
+            );
+        }
+    }
+}
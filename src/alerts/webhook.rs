@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use sha2::Sha256;
+
+use super::{Alert, NotificationSink, SinkError, SinkFuture};
+
+pub const SIGNATURE_HEADER: &str = "X-Rubl-Signature";
+
+/// Same limit as the command sink, so a silent endpoint can't stall dispatch.
+const TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts alerts as JSON to an HTTP endpoint.
+///
+/// When a secret is configured, the body is signed with HMAC-SHA256 and the
+/// hex digest is sent as `X-Rubl-Signature: sha256=<digest>`.
+pub struct WebhookSink {
+    url: String,
+    secret: Option<String>,
+    http: Client,
+}
+
+impl WebhookSink {
+    pub fn new(url: impl Into<String>, secret: Option<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret,
+            http: Client::builder()
+                .timeout(TIMEOUT)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .build()
+                .expect("HTTP client builds with the default TLS backend"),
+        }
+    }
+}
+
+/// Computes the `sha256=<hex>` signature for a payload.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl NotificationSink for WebhookSink {
+    fn describe(&self) -> String {
+        format!("webhook {}", self.url)
+    }
+
+    fn deliver<'a>(&'a self, alert: &'a Alert) -> SinkFuture<'a> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(alert)?;
+            let mut request = self
+                .http
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json");
+            if let Some(secret) = &self.secret {
+                request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+            }
+            let response = request.body(body).send().await?;
+            if !response.status().is_success() {
+                return Err(SinkError::Status(response.status()));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::super::tests::{alert, sighting};
+    use super::super::{AlertDispatcher, lock};
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct Received {
+        signature: Option<String>,
+        body: Vec<u8>,
+    }
+
+    /// Minimal HTTP server that answers with the given statuses in order
+    /// (repeating the last one) and records each request.
+    async fn stand_in(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<Received>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let log = received.clone();
+        tokio::spawn(async move {
+            let mut index = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let request = read_request(&mut socket).await;
+                lock(&log).push(request);
+                let status = statuses[index.min(statuses.len() - 1)];
+                index += 1;
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        (url, received)
+    }
+
+    async fn read_request(socket: &mut tokio::net::TcpStream) -> Received {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        let header_end = loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+        let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+        let header = |name: &str| {
+            head.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.eq_ignore_ascii_case(name)
+                    .then(|| value.trim().to_string())
+            })
+        };
+        let length: usize = header("content-length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        while buf.len() < header_end + length {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        Received {
+            signature: header(SIGNATURE_HEADER),
+            body: buf[header_end..header_end + length].to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_signed_json_payload() {
+        let (url, received) = stand_in(vec![200]).await;
+        let sink = WebhookSink::new(url, Some("s3cret".into()));
+        sink.deliver(&alert(vec![sighting("S1")])).await.unwrap();
+
+        let requests = lock(&received).clone();
+        assert_eq!(requests.len(), 1);
+        let payload: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(payload["watchId"], "w1");
+        assert_eq!(payload["sightings"][0]["comName"], "Whimbrel");
+        assert_eq!(
+            requests[0].signature.as_deref(),
+            Some(sign("s3cret", &requests[0].body).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn omits_signature_without_secret() {
+        let (url, received) = stand_in(vec![204]).await;
+        let sink = WebhookSink::new(url, None);
+        sink.deliver(&alert(vec![sighting("S1")])).await.unwrap();
+        assert!(lock(&received)[0].signature.is_none());
+    }
+
+    #[tokio::test]
+    async fn error_status_is_reported() {
+        let (url, _) = stand_in(vec![500]).await;
+        let sink = WebhookSink::new(url, None);
+        let err = sink
+            .deliver(&alert(vec![sighting("S1")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SinkError::Status(status) if status.as_u16() == 500));
+    }
+
+    #[tokio::test]
+    async fn dispatcher_retries_and_dedups_against_stand_in() {
+        let (url, received) = stand_in(vec![503, 200]).await;
+        let sink = WebhookSink::new(url, Some("s3cret".into()));
+        let dispatcher = AlertDispatcher::new(vec![(Box::new(sink), vec!["US-NC".into()])])
+            .with_retry(3, Duration::from_millis(1));
+
+        dispatcher
+            .dispatch(Some("US-NC-183"), &alert(vec![sighting("S1")]))
+            .await;
+        dispatcher
+            .dispatch(Some("US-NC"), &alert(vec![sighting("S1")]))
+            .await;
+        dispatcher
+            .dispatch(Some("US-VA"), &alert(vec![sighting("S2")]))
+            .await;
+
+        // One failed attempt, one success, then nothing: S1 was already
+        // delivered and US-VA is outside the sink's regions.
+        assert_eq!(lock(&received).len(), 2);
+    }
+
+    #[test]
+    fn signature_matches_known_vector() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Recording and replaying eBird HTTP interactions.
+//!
+//! A cassette is a directory of fixture files, one per distinct request.
+//! In record mode every response eBird sends is also written to the
+//! cassette; in replay mode responses come from the cassette and nothing
+//! goes over the network, so demos and tests are deterministic and a bug
+//! report can carry the exact responses that triggered it.
+//!
+//! Files are named after the request path plus a hash of the method, path,
+//! and query (`data_obs_US-NC_recent_notable-1a2b3c4d5e6f.json`) and hold
+//! the request and response as readable JSON. The API key travels in a
+//! header, which is never written; should it appear in a URL or a body, it
+//! is replaced with `REDACTED` before saving.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::api::client::RawResponse;
+use crate::api::error::ApiError;
+use crate::storage;
+
+const REDACTED: &str = "REDACTED";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Call eBird and save each response.
+    Record,
+    /// Serve saved responses only.
+    Replay,
+}
+
+/// A fixture directory and what to do with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cassette {
+    mode: Mode,
+    dir: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    /// Path and query relative to the API base URL.
+    url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retry_after_secs: Option<u64>,
+    /// The body when it is JSON, kept structured so cassettes diff well.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<Value>,
+    /// The body otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+}
+
+fn redact(text: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(api_key, REDACTED)
+    }
+}
+
+impl Cassette {
+    pub fn record(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: Mode::Record,
+            dir: dir.into(),
+        }
+    }
+
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode: Mode::Replay,
+            dir: dir.into(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, method: &Method, url: &str) -> PathBuf {
+        let digest = Sha256::digest(format!("{} {}", method, url).as_bytes());
+        let route = url.split('?').next().unwrap_or_default();
+        let slug: String = route
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(80)
+            .collect();
+        self.dir
+            .join(format!("{}-{}.json", slug, &hex::encode(digest)[..12]))
+    }
+
+    /// The saved response for `url` (relative to the base URL).
+    pub(crate) fn load(
+        &self,
+        method: &Method,
+        url: &str,
+        api_key: &str,
+    ) -> Result<RawResponse, ApiError> {
+        let url = redact(url, api_key);
+        let not_recorded = || ApiError::NotRecorded {
+            request: format!("{} {}", method, url),
+            dir: self.dir.clone(),
+        };
+        let interaction: Interaction = match storage::load_json(&self.path(method, &url)) {
+            Ok(Some(interaction)) => interaction,
+            Ok(None) => return Err(not_recorded()),
+            Err(error) => {
+                tracing::warn!(%error, "Unreadable cassette entry");
+                return Err(not_recorded());
+            }
+        };
+        let response = interaction.response;
+        let body = match (response.json, response.text) {
+            (Some(json), _) => serde_json::to_vec(&json).map_err(ApiError::Serialization)?,
+            (None, Some(text)) => text.into_bytes(),
+            (None, None) => Vec::new(),
+        };
+        Ok(RawResponse {
+            status: StatusCode::from_u16(response.status).map_err(|_| not_recorded())?,
+            retry_after: response.retry_after_secs.map(Duration::from_secs),
+            body,
+        })
+    }
+
+    /// Saves a response, redacting `api_key`. Failures are logged; the
+    /// request itself has succeeded.
+    pub(crate) fn save(&self, method: &Method, url: &str, response: &RawResponse, api_key: &str) {
+        let url = redact(url, api_key);
+        let body = redact(&String::from_utf8_lossy(&response.body), api_key);
+        let (json, text) = match serde_json::from_str(&body) {
+            Ok(json) => (Some(json), None),
+            Err(_) => (None, Some(body)),
+        };
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: method.to_string(),
+                url: url.clone(),
+            },
+            response: RecordedResponse {
+                status: response.status.as_u16(),
+                retry_after_secs: response.retry_after.map(|d| d.as_secs()),
+                json,
+                text,
+            },
+        };
+        let path = self.path(method, &url);
+        match storage::save_json(&path, &interaction) {
+            Ok(()) => tracing::debug!(path = %path.display(), "Recorded eBird response"),
+            Err(error) => {
+                tracing::warn!(%error, path = %path.display(), "Failed to record eBird response")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, body: &str) -> RawResponse {
+        RawResponse {
+            status: StatusCode::from_u16(status).unwrap(),
+            retry_after: None,
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn saved_responses_replay_and_redact_the_key() {
+        let dir = std::env::temp_dir().join(format!("rubl-cassette-{}", std::process::id()));
+        let recorder = Cassette::record(&dir);
+        let url = "ref/hotspot/info/L123?key=secret-key";
+        recorder.save(
+            &Method::GET,
+            url,
+            &response(200, r#"{"locId":"L123","note":"secret-key"}"#),
+            "secret-key",
+        );
+        recorder.save(
+            &Method::GET,
+            "data/obs/XX/recent",
+            &response(400, "not json"),
+            "",
+        );
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 2);
+        for file in files {
+            let text = std::fs::read_to_string(file.unwrap().path()).unwrap();
+            assert!(!text.contains("secret-key"));
+        }
+
+        let player = Cassette::replay(&dir);
+        let replayed = player.load(&Method::GET, url, "secret-key").unwrap();
+        assert_eq!(replayed.status, StatusCode::OK);
+        let body: Value = serde_json::from_slice(&replayed.body).unwrap();
+        assert_eq!(body["note"], REDACTED);
+        let replayed = player.load(&Method::GET, "data/obs/XX/recent", "").unwrap();
+        assert_eq!(replayed.status, StatusCode::BAD_REQUEST);
+        assert_eq!(replayed.body, b"not json");
+
+        assert!(matches!(
+            player.load(&Method::GET, "data/obs/US/recent", ""),
+            Err(ApiError::NotRecorded { .. })
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn file_names_follow_the_route() {
+        let cassette = Cassette::replay("/tmp/c");
+        let path = cassette.path(&Method::GET, "data/obs/US-NC/recent/notable?back=3");
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("data_obs_US-NC_recent_notable-"));
+        assert_ne!(
+            path,
+            cassette.path(&Method::GET, "data/obs/US-NC/recent/notable?back=4")
+        );
+    }
+}
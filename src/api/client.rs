@@ -1,12 +1,50 @@
-use reqwest::Client;
+//! The eBird HTTP client.
+//!
+//! Identical requests that are in flight at the same moment (same method,
+//! path, query, and format) are coalesced: the first caller fetches and
+//! the others wait for its response and deserialize their own copy. A
+//! semaphore shared by all clones of a client caps how many requests are
+//! outstanding at once; callers beyond the cap queue for a slot.
+//!
+//! With a [`Cassette`] attached, responses are also recorded to fixture
+//! files, or served from them without touching the network.
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::{Client, StatusCode};
+use tokio::sync::{OnceCell, Semaphore};
+use tracing::Instrument;
+
+use crate::api::cassette::{Cassette, Mode};
 use crate::api::endpoint::Endpoint;
 use crate::api::error::ApiError;
+use crate::telemetry::{self, InFlight};
 
 const BASE_URL: &str = "https://api.ebird.org/v2";
 
+/// Default cap on concurrent requests per client.
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// What eBird sent back, shared by every caller of a coalesced request.
+#[derive(Debug)]
+pub(crate) struct RawResponse {
+    pub(crate) status: StatusCode,
+    pub(crate) retry_after: Option<Duration>,
+    pub(crate) body: Vec<u8>,
+}
+
+type Shared = Result<Arc<RawResponse>, Arc<reqwest::Error>>;
+
+/// Requests in flight, by method and URL.
+type InFlightRequests = Mutex<HashMap<String, Arc<OnceCell<Shared>>>>;
+
 /// HTTP client for authenticated eBird API requests.
 ///
+/// Clones share the connection pool, the concurrency limit, and the set of
+/// in-flight requests.
+///
 /// # Examples
 ///
 /// ```no_run
@@ -18,12 +56,16 @@ const BASE_URL: &str = "https://api.ebird.org/v2";
 pub struct ApiClient {
     api_key: String,
     http: Client,
+    base_url: Arc<str>,
+    limit: Arc<Semaphore>,
+    in_flight: Arc<InFlightRequests>,
+    cassette: Option<Cassette>,
 }
 
 impl ApiClient {
     /// Creates a new API client.
     ///
-    /// Get your API key from: https://ebird.org/api/keygen
+    /// Get your API key from: <https://ebird.org/api/keygen>
     ///
     /// # Examples
     ///
@@ -34,12 +76,71 @@ impl ApiClient {
     /// let client = ApiClient::new("your-api-key".to_string());
     /// ```
     pub fn new(api_key: impl Into<String>) -> Self {
+        Self::with_http(api_key, Client::new())
+    }
+
+    /// Creates a client whose requests give up after `timeout`, or after
+    /// `connect_timeout` if eBird can't be reached at all.
+    pub fn with_timeouts(
+        api_key: impl Into<String>,
+        timeout: Duration,
+        connect_timeout: Duration,
+    ) -> Result<Self, reqwest::Error> {
+        let http = Client::builder()
+            .timeout(timeout)
+            .connect_timeout(connect_timeout)
+            .build()?;
+        Ok(Self::with_http(api_key, http))
+    }
+
+    fn with_http(api_key: impl Into<String>, http: Client) -> Self {
         Self {
-            http: Client::new(),
             api_key: api_key.into(),
+            http,
+            base_url: BASE_URL.into(),
+            limit: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_REQUESTS)),
+            in_flight: Arc::default(),
+            cassette: None,
         }
     }
 
+    /// Allows at most `limit` requests to eBird at once (at least one).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rubl::api::client::ApiClient;
+    ///
+    /// let client = ApiClient::new("your-api-key").with_max_concurrent_requests(4);
+    /// ```
+    pub fn with_max_concurrent_requests(mut self, limit: usize) -> Self {
+        self.limit = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Sends requests to `base_url` instead of `https://api.ebird.org/v2`,
+    /// for a proxy or a local stand-in.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').into();
+        self
+    }
+
+    /// Records responses to, or replays them from, `cassette`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rubl::api::cassette::Cassette;
+    /// use rubl::api::client::ApiClient;
+    ///
+    /// // Offline: no key needed, every response comes from the fixtures.
+    /// let client = ApiClient::new("").with_cassette(Cassette::replay("fixtures/cassettes/demo"));
+    /// ```
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
     /// Sends an API request and deserializes the response.
     ///
     /// # Examples
@@ -53,31 +154,318 @@ impl ApiClient {
     /// # }
     /// ```
     pub async fn send<E: Endpoint>(&self, endpoint: &E) -> Result<E::Response, ApiError> {
-        let url = format!("{}/{}", BASE_URL, endpoint.path());
+        let span = tracing::debug_span!(
+            "ebird_request",
+            path = %endpoint.path(),
+            status = tracing::field::Empty,
+        );
+        let in_flight = InFlight::start("rubl_ebird_requests_in_flight");
+        let started = Instant::now();
+        let result = self.execute(endpoint).instrument(span.clone()).await;
+        let elapsed = started.elapsed();
+        drop(in_flight);
+        let latency_ms = elapsed.as_millis() as u64;
+        span.in_scope(|| match &result {
+            Ok(_) => tracing::debug!(latency_ms, "eBird request finished"),
+            Err(error) => {
+                tracing::debug!(latency_ms, kind = ?error.kind(), %error, "eBird request failed")
+            }
+        });
+        telemetry::ebird_request(
+            telemetry::endpoint_name::<E>(),
+            result.as_ref().map(|(status, _)| status.as_u16()),
+            elapsed,
+        );
+        result.map(|(_, response)| response)
+    }
+
+    async fn execute<E: Endpoint>(
+        &self,
+        endpoint: &E,
+    ) -> Result<(StatusCode, E::Response), ApiError> {
+        endpoint.validate().map_err(ApiError::InvalidRequest)?;
+
+        let url = format!("{}/{}", self.base_url, endpoint.path());
         let mut request = self.http.request(E::METHOD, url);
         if let Some(fmt) = endpoint.format() {
             request = request.query(&[("fmt", fmt)]);
         }
         let request = request
             .query(endpoint.query())
-            .header("X-eBirdApiToken", &self.api_key);
+            .header("X-eBirdApiToken", &self.api_key)
+            .build()
+            .map_err(|e| ApiError::Network(Arc::new(e)))?;
 
-        let response = request.send().await.map_err(ApiError::Network)?;
+        let raw = match &self.cassette {
+            Some(cassette) if cassette.mode() == Mode::Replay => {
+                let url = self.relative(request.url());
+                Arc::new(cassette.load(request.method(), &url, &self.api_key)?)
+            }
+            _ => self
+                .coalesced::<E>(request)
+                .await
+                .map_err(ApiError::Network)?,
+        };
 
-        // Check status code before attempting deserialization
-        let status = response.status();
+        let status = raw.status;
+        tracing::Span::current().record("status", status.as_u16());
         if !status.is_success() {
-            // Capture the error response body for better error messages
-            let body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| String::from("(unable to read response body)"));
-            return Err(ApiError::HttpError { status, body });
+            let body = String::from_utf8_lossy(&raw.body).into_owned();
+            return Err(ApiError::from_response(status, body, raw.retry_after));
+        }
+
+        let response = serde_json::from_slice(&raw.body).map_err(ApiError::Deserialization)?;
+        Ok((status, response))
+    }
+
+    /// Sends `request`, or waits for an identical one already in flight.
+    async fn coalesced<E: Endpoint>(&self, request: reqwest::Request) -> Shared {
+        let key = format!("{} {}", request.method(), request.url());
+        let cell = self.requests().entry(key.clone()).or_default().clone();
+        let mut led = false;
+        let shared = cell
+            .get_or_init(|| {
+                led = true;
+                self.fetch(request)
+            })
+            .await
+            .clone();
+        {
+            let mut requests = self.requests();
+            if requests
+                .get(&key)
+                .is_some_and(|current| Arc::ptr_eq(current, &cell))
+            {
+                requests.remove(&key);
+            }
+        }
+        if !led {
+            tracing::debug!("Coalesced with an identical request in flight");
+            telemetry::coalesced(telemetry::endpoint_name::<E>());
+        }
+        shared
+    }
+
+    /// The request's path and query relative to the base URL.
+    fn relative(&self, url: &reqwest::Url) -> String {
+        let url = url.as_str();
+        url.strip_prefix(&*self.base_url)
+            .map(|rest| rest.trim_start_matches('/'))
+            .unwrap_or(url)
+            .to_string()
+    }
+
+    fn requests(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<OnceCell<Shared>>>> {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Makes one upstream request once a slot is free.
+    async fn fetch(&self, request: reqwest::Request) -> Shared {
+        let _permit = self
+            .limit
+            .acquire()
+            .await
+            .expect("the request semaphore is never closed");
+        let method = request.method().clone();
+        let url = self.relative(request.url());
+        let response = self.http.execute(request).await.map_err(Arc::new)?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        let body = match response.bytes().await {
+            Ok(body) => body.to_vec(),
+            Err(error) if status.is_success() => return Err(Arc::new(error)),
+            // Capture what we can of the error response for the message.
+            Err(_) => b"(unable to read response body)".to_vec(),
+        };
+        let raw = RawResponse {
+            status,
+            retry_after,
+            body,
+        };
+        if let Some(cassette) = self.cassette.as_ref().filter(|c| c.mode() == Mode::Record) {
+            cassette.save(&method, &url, &raw, &self.api_key);
         }
+        Ok(Arc::new(raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use reqwest::Method;
+    use serde::Serialize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinSet;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct TestRequest {
+        #[serde(skip)]
+        path: String,
+        back: u32,
+    }
+
+    impl Endpoint for TestRequest {
+        type Query = Self;
+        type Response = Vec<String>;
+
+        const METHOD: Method = Method::GET;
+
+        fn path(&self) -> String {
+            self.path.clone()
+        }
+
+        fn query(&self) -> &Self {
+            self
+        }
+    }
+
+    fn request(path: &str, back: u32) -> TestRequest {
+        TestRequest {
+            path: path.into(),
+            back,
+        }
+    }
+
+    #[derive(Default)]
+    struct Counts {
+        requests: AtomicUsize,
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    /// Minimal HTTP server that answers every request with `status` and a
+    /// JSON body after `delay`, counting requests and peak concurrency.
+    async fn stand_in(status: u16, delay: Duration) -> (ApiClient, Arc<Counts>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v2", listener.local_addr().unwrap());
+        let counts = Arc::new(Counts::default());
+        let shared = counts.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let counts = shared.clone();
+                tokio::spawn(async move {
+                    let mut head = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = socket.read(&mut chunk).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        head.extend_from_slice(&chunk[..n]);
+                    }
+                    counts.requests.fetch_add(1, Ordering::SeqCst);
+                    let active = counts.active.fetch_add(1, Ordering::SeqCst) + 1;
+                    counts.peak.fetch_max(active, Ordering::SeqCst);
+                    tokio::time::sleep(delay).await;
+                    counts.active.fetch_sub(1, Ordering::SeqCst);
+                    let body = r#"["ok"]"#;
+                    let response = format!(
+                        "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (ApiClient::new("key").with_base_url(url), counts)
+    }
+
+    async fn send_all(
+        client: &ApiClient,
+        requests: Vec<TestRequest>,
+    ) -> Vec<Result<Vec<String>, ApiError>> {
+        let mut tasks = JoinSet::new();
+        for request in requests {
+            let client = client.clone();
+            tasks.spawn(async move { client.send(&request).await });
+        }
+        tasks.join_all().await
+    }
+
+    #[tokio::test]
+    async fn concurrent_identical_requests_share_one_upstream_call() {
+        let (client, counts) = stand_in(200, Duration::from_millis(200)).await;
+        let requests = (0..10)
+            .map(|_| request("ref/hotspot/info/L123", 3))
+            .collect();
+        let results = send_all(&client, requests).await;
+        assert_eq!(results.len(), 10);
+        for result in results {
+            assert_eq!(result.unwrap(), vec!["ok".to_string()]);
+        }
+        assert_eq!(counts.requests.load(Ordering::SeqCst), 1);
+        assert!(client.requests().is_empty());
+
+        // Once answered, the same request goes upstream again.
+        client
+            .send(&request("ref/hotspot/info/L123", 3))
+            .await
+            .unwrap();
+        assert_eq!(counts.requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn coalesced_failures_reach_every_caller() {
+        let (client, counts) = stand_in(503, Duration::from_millis(200)).await;
+        let requests = (0..4)
+            .map(|_| request("data/obs/US-NC/recent", 3))
+            .collect();
+        for result in send_all(&client, requests).await {
+            assert!(matches!(result, Err(ApiError::Unavailable { .. })));
+        }
+        assert_eq!(counts.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn different_queries_are_sent_separately_within_the_limit() {
+        let (client, counts) = stand_in(200, Duration::from_millis(100)).await;
+        let client = client.with_max_concurrent_requests(2);
+        let requests = (1..=6)
+            .map(|back| request("data/obs/US-NC/recent", back))
+            .collect();
+        for result in send_all(&client, requests).await {
+            result.unwrap();
+        }
+        assert_eq!(counts.requests.load(Ordering::SeqCst), 6);
+        assert_eq!(counts.peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_replay_offline() {
+        let dir = std::env::temp_dir().join(format!("rubl-vcr-{}", std::process::id()));
+        let (client, counts) = stand_in(200, Duration::ZERO).await;
+        let recorder = client.with_cassette(Cassette::record(&dir));
+        let recorded = recorder
+            .send(&request("ref/hotspot/info/L123", 3))
+            .await
+            .unwrap();
+        assert_eq!(counts.requests.load(Ordering::SeqCst), 1);
 
-        response
-            .json::<E::Response>()
+        // Nothing listens on the discard port; replay must not need it.
+        let player = ApiClient::new("other-key")
+            .with_base_url("http://127.0.0.1:9/v2")
+            .with_cassette(Cassette::replay(&dir));
+        let replayed = player
+            .send(&request("ref/hotspot/info/L123", 3))
             .await
-            .map_err(ApiError::Deserialization)
+            .unwrap();
+        assert_eq!(replayed, recorded);
+        assert!(matches!(
+            player.send(&request("ref/hotspot/info/L123", 4)).await,
+            Err(ApiError::NotRecorded { .. })
+        ));
+        assert_eq!(counts.requests.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
@@ -1,12 +1,19 @@
 use reqwest::Method;
 use serde::{Serialize, de::DeserializeOwned};
 
+/// One eBird API call.
+///
+/// Implementors are usually the request struct itself, serialized as the
+/// query string with path parameters marked `#[serde(skip_serializing)]`.
 pub trait Endpoint {
+    /// Serialized into the query string.
     type Query: Serialize;
+    /// Deserialized from the JSON response body.
     type Response: DeserializeOwned;
 
     const METHOD: Method;
 
+    /// Path under `https://api.ebird.org/v2/`, such as `data/obs/US-NC/recent`.
     fn path(&self) -> String;
 
     fn query(&self) -> &Self::Query;
@@ -15,4 +22,10 @@ pub trait Endpoint {
     fn format(&self) -> Option<&'static str> {
         None
     }
+
+    /// Checks parameters before any network call; the message is shown to
+    /// the caller as-is.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
 }
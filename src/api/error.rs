@@ -1,28 +1,202 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum ApiError {
-    /// HTTP error with response body (often contains helpful API error messages).
-    #[error("eBird API returned {status}: {body}")]
-    HttpError {
-        status: reqwest::StatusCode,
-        body: String,
+    /// 401/403: the API key is missing, invalid, or revoked.
+    #[error("eBird rejected the API key ({status}): {message}")]
+    Unauthorized { status: StatusCode, message: String },
+
+    /// A 400 or 404 that names a region or location eBird doesn't know.
+    #[error("eBird does not recognize the region or location ({status}): {message}")]
+    UnknownRegion { status: StatusCode, message: String },
+
+    /// Any other 404, e.g. an unknown checklist or species code.
+    #[error("eBird has nothing at that address ({status}): {message}")]
+    NotFound { status: StatusCode, message: String },
+
+    /// 429: the request quota is exhausted for now.
+    #[error("eBird rate limit exceeded: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
     },
 
+    /// 5xx: eBird is down or failing.
+    #[error("eBird API is unavailable ({status}): {message}")]
+    Unavailable { status: StatusCode, message: String },
+
+    /// Any other HTTP error, with the response body (often contains helpful API error messages).
+    #[error("eBird API returned {status}: {body}")]
+    HttpError { status: StatusCode, body: String },
+
+    /// Shared because coalesced requests all report the one failure.
     #[error("Network error: {0}")]
-    Network(reqwest::Error),
+    Network(Arc<reqwest::Error>),
 
     #[error("Failed to deserialize API response: {0}")]
-    Deserialization(reqwest::Error),
+    Deserialization(serde_json::Error),
 
     #[error("Failed to serialize request parameters: {0}")]
     Serialization(#[from] serde_json::Error),
+
+    /// Rejected before sending because the parameters can never succeed.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// Replaying a cassette that has no response for this request.
+    #[error("No recorded response for {request} in {dir}")]
+    NotRecorded { request: String, dir: PathBuf },
+}
+
+/// Machine-readable error category, reported to MCP clients as `data.kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    InvalidRequest,
+    Unauthorized,
+    UnknownRegion,
+    NotFound,
+    RateLimited,
+    Unavailable,
+    Http,
+    Network,
+    Deserialization,
+    NotRecorded,
+}
+
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidRequest => "invalid_request",
+            ErrorKind::Unauthorized => "unauthorized",
+            ErrorKind::UnknownRegion => "unknown_region",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::RateLimited => "rate_limited",
+            ErrorKind::Unavailable => "unavailable",
+            ErrorKind::Http => "http",
+            ErrorKind::Network => "network",
+            ErrorKind::Deserialization => "deserialization",
+            ErrorKind::NotRecorded => "not_recorded",
+        }
+    }
+}
+
+impl ApiError {
+    /// Classifies a non-success response, pulling the message out of
+    /// eBird's `{"errors": [{"title": ...}]}` body when there is one.
+    pub fn from_response(status: StatusCode, body: String, retry_after: Option<Duration>) -> Self {
+        let message = error_message(&body).unwrap_or_else(|| body.trim().to_string());
+        match status.as_u16() {
+            401 | 403 => ApiError::Unauthorized { status, message },
+            400 | 404 if names_region(&message) => ApiError::UnknownRegion { status, message },
+            404 => ApiError::NotFound { status, message },
+            429 => ApiError::RateLimited {
+                retry_after,
+                message,
+            },
+            _ if status.is_server_error() => ApiError::Unavailable { status, message },
+            _ => ApiError::HttpError { status, body },
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ApiError::Unauthorized { .. } => ErrorKind::Unauthorized,
+            ApiError::UnknownRegion { .. } => ErrorKind::UnknownRegion,
+            ApiError::NotFound { .. } => ErrorKind::NotFound,
+            ApiError::RateLimited { .. } => ErrorKind::RateLimited,
+            ApiError::Unavailable { .. } => ErrorKind::Unavailable,
+            ApiError::HttpError { .. } => ErrorKind::Http,
+            ApiError::Network(_) => ErrorKind::Network,
+            ApiError::Deserialization(_) => ErrorKind::Deserialization,
+            ApiError::Serialization(_) | ApiError::InvalidRequest(_) => ErrorKind::InvalidRequest,
+            ApiError::NotRecorded { .. } => ErrorKind::NotRecorded,
+        }
+    }
+
+    /// The HTTP status eBird answered with, if it answered.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ApiError::Unauthorized { status, .. }
+            | ApiError::UnknownRegion { status, .. }
+            | ApiError::NotFound { status, .. }
+            | ApiError::Unavailable { status, .. }
+            | ApiError::HttpError { status, .. } => Some(*status),
+            ApiError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            _ => None,
+        }
+    }
+
+    /// Whether the same request may succeed if sent again later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ApiError::RateLimited { .. } | ApiError::Unavailable { .. } | ApiError::Network(_)
+        )
+    }
+
+    /// What the caller can do about it, phrased for an agent.
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            ApiError::Unauthorized { .. } => Some(
+                "Check that EBIRD_API_KEY holds a valid key; request one at https://ebird.org/api/keygen.",
+            ),
+            ApiError::UnknownRegion { .. } => Some(
+                "Check the code with get_region_info, or browse valid codes with get_subregions. Regions look like US, US-NC, or US-NC-067; hotspots like L123456.",
+            ),
+            ApiError::NotFound { .. } => Some(
+                "Check the IDs and codes passed to the tool; eBird found nothing matching them.",
+            ),
+            ApiError::RateLimited { .. } => {
+                Some("Wait before retrying, and prefer fewer or narrower requests.")
+            }
+            ApiError::Unavailable { .. } | ApiError::Network(_) => {
+                Some("eBird may be briefly unavailable; retry in a minute.")
+            }
+            ApiError::InvalidRequest(_) | ApiError::Serialization(_) => {
+                Some("Fix the parameters named in the message and call the tool again.")
+            }
+            ApiError::NotRecorded { .. } => Some(
+                "Record this request against the live API with RUBL_RECORD, or replay a cassette that includes it.",
+            ),
+            ApiError::HttpError { .. } | ApiError::Deserialization(_) => None,
+        }
+    }
+}
+
+/// Joins the `title` (or `detail`) of each entry in an eBird error body.
+fn error_message(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    let messages: Vec<&str> = value
+        .get("errors")?
+        .as_array()?
+        .iter()
+        .filter_map(|error| {
+            error
+                .get("title")
+                .or_else(|| error.get("detail"))
+                .and_then(|m| m.as_str())
+        })
+        .collect();
+    (!messages.is_empty()).then(|| messages.join("; "))
+}
+
+fn names_region(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    ["region", "location", "locid", "hotspot"]
+        .iter()
+        .any(|word| message.contains(word))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reqwest::StatusCode;
 
     mod display {
         use super::*;
@@ -58,7 +232,7 @@ mod tests {
                 .await
                 .unwrap_err();
 
-            let error = ApiError::Network(reqwest_error);
+            let error = ApiError::Network(Arc::new(reqwest_error));
             let display = format!("{}", error);
             assert!(display.contains("Network error"));
         }
@@ -72,6 +246,15 @@ mod tests {
             assert!(display.contains("Failed to serialize request parameters"));
         }
 
+        #[test]
+        fn invalid_request_includes_reason() {
+            let error = ApiError::InvalidRequest("2023-02-30 is not a valid date".into());
+            assert_eq!(
+                format!("{}", error),
+                "Invalid request: 2023-02-30 is not a valid date"
+            );
+        }
+
         #[test]
         fn http_error_with_500() {
             let error = ApiError::HttpError {
@@ -83,4 +266,71 @@ mod tests {
             assert!(display.contains("Server error occurred"));
         }
     }
+
+    mod from_response {
+        use super::*;
+
+        fn classify(status: u16, body: &str) -> ApiError {
+            ApiError::from_response(StatusCode::from_u16(status).unwrap(), body.into(), None)
+        }
+
+        #[test]
+        fn parses_ebird_error_body() {
+            let error = classify(
+                400,
+                r#"{"errors":[{"status":"400 BAD_REQUEST","code":"error.data.invalid","title":"Field regionCode of recentObsQuery: regionCode is invalid"}]}"#,
+            );
+            assert_eq!(error.kind(), ErrorKind::UnknownRegion);
+            assert!(
+                error
+                    .to_string()
+                    .ends_with("Field regionCode of recentObsQuery: regionCode is invalid")
+            );
+            assert!(!error.is_retryable());
+            assert!(error.suggestion().unwrap().contains("get_region_info"));
+        }
+
+        #[test]
+        fn classifies_by_status() {
+            assert_eq!(classify(401, "").kind(), ErrorKind::Unauthorized);
+            assert_eq!(classify(403, "Forbidden").kind(), ErrorKind::Unauthorized);
+            assert_eq!(classify(404, "").kind(), ErrorKind::NotFound);
+            assert_eq!(
+                classify(404, "Unknown region code").kind(),
+                ErrorKind::UnknownRegion
+            );
+            assert_eq!(classify(429, "").kind(), ErrorKind::RateLimited);
+            assert_eq!(classify(503, "").kind(), ErrorKind::Unavailable);
+            assert_eq!(
+                classify(400, "maxResults too large").kind(),
+                ErrorKind::Http
+            );
+            assert_eq!(
+                classify(429, "").status(),
+                Some(StatusCode::TOO_MANY_REQUESTS)
+            );
+        }
+
+        #[test]
+        fn other_not_found_errors_stay_neutral() {
+            let error = classify(404, r#"{"errors":[{"title":"Checklist S123 not found"}]}"#);
+            assert_eq!(error.kind(), ErrorKind::NotFound);
+            assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+            assert!(!error.suggestion().unwrap().contains("get_region_info"));
+        }
+
+        #[test]
+        fn only_transient_errors_are_retryable() {
+            assert!(classify(429, "").is_retryable());
+            assert!(classify(502, "").is_retryable());
+            assert!(!classify(401, "").is_retryable());
+            assert!(!ApiError::InvalidRequest("bad".into()).is_retryable());
+        }
+
+        #[test]
+        fn keeps_unparseable_bodies() {
+            let error = classify(500, "  <html>oops</html>\n");
+            assert!(error.to_string().ends_with("<html>oops</html>"));
+        }
+    }
 }
@@ -1,3 +1,10 @@
+//! Talking to the eBird API: [`client::ApiClient`] sends any
+//! [`endpoint::Endpoint`] and reports failures as [`error::ApiError`];
+//! [`throttle`] paces and bounds calls that fan out; [`cassette`] records
+//! and replays responses.
+
+pub mod cassette;
 pub mod client;
 pub mod endpoint;
 pub mod error;
+pub mod throttle;
@@ -0,0 +1,135 @@
+//! Concurrency and rate limiting for fanned-out API calls.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// Spaces request starts at least `interval` apart across all holders.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    interval: Duration,
+    next: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn per_second(requests: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests.max(1),
+            next: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Waits for the next free slot.
+    pub async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Runs `task` over every item with at most `concurrency` in flight,
+/// returning results in input order. Stops at the first error.
+///
+/// `on_progress` is called with (completed, total) after each item.
+pub async fn fan_out<T, R, E, F, Fut>(
+    items: Vec<T>,
+    concurrency: usize,
+    mut task: F,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<R>, E>
+where
+    F: FnMut(T) -> Fut,
+    Fut: Future<Output = Result<R, E>> + Send + 'static,
+    R: Send + 'static,
+    E: Send + 'static,
+{
+    let total = items.len();
+    let mut results: Vec<Option<R>> = (0..total).map(|_| None).collect();
+    let mut pending = items.into_iter().enumerate();
+    let mut running = JoinSet::new();
+    let mut completed = 0;
+
+    loop {
+        while running.len() < concurrency.max(1) {
+            let Some((index, item)) = pending.next() else {
+                break;
+            };
+            let future = task(item);
+            running.spawn(async move { (index, future.await) });
+        }
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (index, result) = joined.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+        results[index] = Some(result?);
+        completed += 1;
+        on_progress(completed, total);
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn keeps_order_and_bounds_concurrency() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let mut progress = Vec::new();
+
+        let results = fan_out(
+            (0..10u64).collect(),
+            3,
+            |n| {
+                let (active, peak) = (active.clone(), peak.clone());
+                async move {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10 * (10 - n))).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    Ok::<_, ()>(n * 2)
+                }
+            },
+            |done, total| progress.push((done, total)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(results, (0..10).map(|n| n * 2).collect::<Vec<_>>());
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        assert_eq!(progress.last(), Some(&(10, 10)));
+    }
+
+    #[tokio::test]
+    async fn stops_at_first_error() {
+        let result = fan_out(
+            vec![1, 2, 3],
+            1,
+            |n| async move { if n == 2 { Err("boom") } else { Ok(n) } },
+            |_, _| {},
+        )
+        .await;
+        assert_eq!(result, Err("boom"));
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::per_second(50);
+        let start = Instant::now();
+        for _ in 0..4 {
+            limiter.wait().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(60));
+    }
+}
@@ -0,0 +1,222 @@
+//! First-of-season arrivals and departures.
+//!
+//! A recent window of historic days is compared against the weeks before
+//! it. Species reported in the window but not the baseline are arrivals;
+//! species reported regularly in the baseline but not the window are
+//! departures. Days come through the historic fetcher, so repeated runs
+//! mostly hit the cache.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::historic::HistoricDay;
+use crate::ids::RegionCode;
+use crate::tools::observations::Observation;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Arrival {
+    pub common_name: String,
+    pub scientific_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_code: Option<String>,
+    pub first_date: NaiveDate,
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Days in the recent window the species was reported on.
+    pub days_reported: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Departure {
+    pub common_name: String,
+    pub scientific_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_code: Option<String>,
+    pub last_date: NaiveDate,
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_id: Option<String>,
+    /// Days in the baseline the species was reported on.
+    pub baseline_days: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArrivalReport {
+    pub region_code: RegionCode,
+    pub window: String,
+    pub baseline: String,
+    pub arrivals: Vec<Arrival>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub departures: Vec<Departure>,
+}
+
+fn key(obs: &Observation) -> String {
+    obs.species_code
+        .clone()
+        .unwrap_or_else(|| obs.scientific_name.to_lowercase())
+}
+
+/// Earliest (or latest) report of each species and how many days it was on.
+fn sightings(
+    days: &[HistoricDay],
+    latest: bool,
+) -> HashMap<String, (NaiveDate, &Observation, u32)> {
+    let mut seen: HashMap<String, (NaiveDate, &Observation, u32)> = HashMap::new();
+    for day in days {
+        let mut today = Vec::new();
+        for obs in &day.observations {
+            let key = key(obs);
+            if today.contains(&key) {
+                continue;
+            }
+            today.push(key.clone());
+            seen.entry(key)
+                .and_modify(|(date, kept, n)| {
+                    *n += 1;
+                    if (latest && day.date > *date) || (!latest && day.date < *date) {
+                        *date = day.date;
+                        *kept = obs;
+                    }
+                })
+                .or_insert((day.date, obs, 1));
+        }
+    }
+    seen
+}
+
+/// Compares the recent window against the baseline.
+///
+/// Departures require at least `min_baseline_days` baseline reports so a
+/// single stray record does not count as a species leaving.
+pub fn detect(
+    recent: &[HistoricDay],
+    baseline: &[HistoricDay],
+    min_baseline_days: u32,
+) -> (Vec<Arrival>, Vec<Departure>) {
+    let recent_seen = sightings(recent, false);
+    let baseline_seen = sightings(baseline, true);
+
+    let mut arrivals: BTreeMap<(NaiveDate, String), Arrival> = BTreeMap::new();
+    for (key, (first_date, obs, days)) in &recent_seen {
+        if baseline_seen.contains_key(key) {
+            continue;
+        }
+        arrivals.insert(
+            (*first_date, obs.common_name.clone()),
+            Arrival {
+                common_name: obs.common_name.clone(),
+                scientific_name: obs.scientific_name.clone(),
+                species_code: obs.species_code.clone(),
+                first_date: *first_date,
+                location: obs.location.clone(),
+                location_id: obs.location_id.clone(),
+                count: obs.count,
+                days_reported: *days,
+            },
+        );
+    }
+
+    let mut departures: Vec<Departure> = baseline_seen
+        .iter()
+        .filter(|(key, (_, _, days))| *days >= min_baseline_days && !recent_seen.contains_key(*key))
+        .map(|(_, (last_date, obs, days))| Departure {
+            common_name: obs.common_name.clone(),
+            scientific_name: obs.scientific_name.clone(),
+            species_code: obs.species_code.clone(),
+            last_date: *last_date,
+            location: obs.location.clone(),
+            location_id: obs.location_id.clone(),
+            baseline_days: *days,
+        })
+        .collect();
+    departures.sort_by(|a, b| {
+        b.last_date
+            .cmp(&a.last_date)
+            .then_with(|| a.common_name.cmp(&b.common_name))
+    });
+
+    (arrivals.into_values().collect(), departures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn obs(name: &str, location: &str, count: Option<u32>) -> Observation {
+        Observation {
+            scientific_name: format!("{} sp.", name),
+            count,
+            ..Observation::test(name, location, "")
+        }
+    }
+
+    fn day(value: &str, observations: Vec<Observation>) -> HistoricDay {
+        HistoricDay {
+            date: date(value),
+            observations,
+        }
+    }
+
+    #[test]
+    fn flags_arrivals_with_first_report() {
+        let baseline = vec![
+            day("2023-04-20", vec![obs("Carolina Wren", "Yard", None)]),
+            day("2023-04-21", vec![obs("Carolina Wren", "Yard", None)]),
+        ];
+        let recent = vec![
+            day(
+                "2023-05-01",
+                vec![
+                    obs("Carolina Wren", "Yard", None),
+                    obs("Whimbrel", "Marsh", Some(2)),
+                ],
+            ),
+            day("2023-05-02", vec![obs("Whimbrel", "Beach", Some(9))]),
+        ];
+
+        let (arrivals, departures) = detect(&recent, &baseline, 1);
+        assert_eq!(arrivals.len(), 1);
+        let whimbrel = &arrivals[0];
+        assert_eq!(whimbrel.common_name, "Whimbrel");
+        assert_eq!(whimbrel.first_date, date("2023-05-01"));
+        assert_eq!(whimbrel.location, "Marsh");
+        assert_eq!(whimbrel.count, Some(2));
+        assert_eq!(whimbrel.days_reported, 2);
+        assert!(departures.is_empty());
+    }
+
+    #[test]
+    fn flags_departures_seen_regularly_in_baseline() {
+        let baseline = vec![
+            day(
+                "2023-04-20",
+                vec![
+                    obs("Dark-eyed Junco", "Park", None),
+                    obs("Snow Goose", "Field", None),
+                ],
+            ),
+            day("2023-04-22", vec![obs("Dark-eyed Junco", "Feeder", None)]),
+        ];
+        let recent = vec![day("2023-05-01", vec![])];
+
+        let (arrivals, departures) = detect(&recent, &baseline, 2);
+        assert!(arrivals.is_empty());
+        assert_eq!(departures.len(), 1);
+        assert_eq!(departures[0].common_name, "Dark-eyed Junco");
+        assert_eq!(departures[0].last_date, date("2023-04-22"));
+        assert_eq!(departures[0].location, "Feeder");
+        assert_eq!(departures[0].baseline_days, 2);
+    }
+}
@@ -0,0 +1,366 @@
+//! On-disk cache for API responses and derived results.
+//!
+//! Entries are JSON files under `cache/` in the data directory, named by a
+//! hash of their key. Each entry records when it was stored so callers can
+//! decide how stale is too stale; past-dated eBird data rarely changes, so
+//! most entries are kept for days or weeks. Stale entries are deleted when
+//! read, and a periodic sweep trims the directory back under `max_bytes`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+
+use crate::api::client::ApiClient;
+use crate::api::endpoint::Endpoint;
+use crate::api::error::ApiError;
+use crate::config::CacheConfig;
+use crate::storage;
+use crate::telemetry;
+
+const CACHE_DIR: &str = "cache";
+/// Writes between sweeps; the first write of a run also sweeps.
+const SWEEP_EVERY: usize = 500;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry<T> {
+    stored_at: u64,
+    value: T,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStatus {
+    /// Absent when there is no data directory and nothing is cached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    pub entries: usize,
+    pub bytes: u64,
+    pub max_bytes: u64,
+    /// Entries deleted since startup, for being stale or over the size cap.
+    pub evicted: usize,
+}
+
+/// How long each kind of entry stays fresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ttls {
+    /// The last week is still filling in; keep it fresh.
+    pub recent: Duration,
+    /// Late submissions still trickle in for past days, so refresh monthly.
+    pub historic: Duration,
+    /// Tables built from many responses; the underlying samples are in the past.
+    pub derived: Duration,
+}
+
+impl Default for Ttls {
+    fn default() -> Self {
+        Self::from(&CacheConfig::default())
+    }
+}
+
+impl From<&CacheConfig> for Ttls {
+    fn from(config: &CacheConfig) -> Self {
+        Self {
+            recent: Duration::from_secs(config.recent_ttl_secs),
+            historic: Duration::from_secs(config.historic_ttl_secs),
+            derived: Duration::from_secs(config.derived_ttl_secs),
+        }
+    }
+}
+
+/// Handle to the cache directory. Without a data directory every lookup
+/// misses and writes are dropped.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: Option<PathBuf>,
+    pub ttls: Ttls,
+    max_bytes: u64,
+    writes: Arc<AtomicUsize>,
+    evicted: Arc<AtomicUsize>,
+}
+
+impl Cache {
+    /// The configured directory, or `cache/` in the data directory.
+    pub fn open(config: &CacheConfig) -> Self {
+        Self {
+            dir: config.dir.clone().or_else(|| storage::data_file(CACHE_DIR)),
+            ttls: config.into(),
+            max_bytes: config.max_bytes,
+            writes: Arc::default(),
+            evicted: Arc::default(),
+        }
+    }
+
+    /// A cache that never hits and stores nothing.
+    pub fn disabled() -> Self {
+        Self {
+            dir: None,
+            ttls: Ttls::default(),
+            max_bytes: CacheConfig::default().max_bytes,
+            writes: Arc::default(),
+            evicted: Arc::default(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Self::disabled()
+        }
+    }
+
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        let digest = Sha256::digest(key.as_bytes());
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", hex::encode(digest))))
+    }
+
+    /// Returns the cached value for `key` if present and younger than `ttl`.
+    /// A stale entry is deleted, since the caller is about to replace it.
+    pub fn get<T: DeserializeOwned>(&self, key: &str, ttl: Duration) -> Option<T> {
+        let path = self.path(key)?;
+        let entry: Entry<T> = storage::load_json(&path).ok().flatten()?;
+        if now().saturating_sub(entry.stored_at) < ttl.as_secs() {
+            return Some(entry.value);
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            self.evicted.fetch_add(1, Ordering::Relaxed);
+        }
+        None
+    }
+
+    /// Stores a value. Failures are logged and otherwise ignored; the cache
+    /// is an optimization, not a source of truth.
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) {
+        let Some(path) = self.path(key) else {
+            return;
+        };
+        let entry = Entry {
+            stored_at: now(),
+            value,
+        };
+        if let Err(e) = storage::save_json(&path, &entry) {
+            tracing::warn!(error = %e, "Failed to write cache entry");
+        }
+        if self
+            .writes
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(SWEEP_EVERY)
+        {
+            self.sweep();
+        }
+    }
+
+    /// Deletes entries older than the longest TTL, then the oldest of the
+    /// rest until the cache fits in `max_bytes`. Returns how many went.
+    pub fn sweep(&self) -> usize {
+        let Some(entries) = self
+            .dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return 0;
+        };
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+            })
+            .collect();
+        files.sort();
+
+        let longest = self
+            .ttls
+            .recent
+            .max(self.ttls.historic)
+            .max(self.ttls.derived);
+        let cutoff = SystemTime::now().checked_sub(longest).unwrap_or(UNIX_EPOCH);
+        let mut bytes: u64 = files.iter().map(|(_, len, _)| len).sum();
+        let mut removed = 0;
+        for (modified, len, path) in files {
+            if modified >= cutoff && bytes <= self.max_bytes {
+                break;
+            }
+            if std::fs::remove_file(&path).is_ok() {
+                bytes -= len;
+                removed += 1;
+            }
+        }
+        if removed > 0 {
+            tracing::debug!(removed, bytes, "Swept cache");
+            self.evicted.fetch_add(removed, Ordering::Relaxed);
+        }
+        removed
+    }
+
+    /// Returns a cached response for an endpoint, keyed by its path and query.
+    pub fn cached<E>(&self, endpoint: &E, ttl: Duration) -> Option<E::Response>
+    where
+        E: Endpoint,
+    {
+        let value = self.get(&endpoint_key(endpoint)?, ttl);
+        telemetry::cache_lookup(value.is_some());
+        tracing::debug!(
+            path = %endpoint.path(),
+            cache_hit = value.is_some(),
+            "Cache lookup"
+        );
+        value
+    }
+
+    pub fn store<E>(&self, endpoint: &E, value: &E::Response)
+    where
+        E: Endpoint,
+        E::Response: Serialize,
+    {
+        if let Some(key) = endpoint_key(endpoint) {
+            self.put(&key, value);
+        }
+    }
+
+    /// Where the cache lives and how much it holds.
+    pub fn status(&self) -> CacheStatus {
+        let mut status = CacheStatus {
+            dir: self.dir.clone(),
+            entries: 0,
+            bytes: 0,
+            max_bytes: self.max_bytes,
+            evicted: self.evicted.load(Ordering::Relaxed),
+        };
+        let Some(entries) = self
+            .dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return status;
+        };
+        for entry in entries.flatten() {
+            if entry.path().extension().is_some_and(|ext| ext == "json") {
+                status.entries += 1;
+                status.bytes += entry.metadata().map(|m| m.len()).unwrap_or_default();
+            }
+        }
+        status
+    }
+
+    /// Sends a request through the cache.
+    pub async fn fetch<E>(
+        &self,
+        client: &ApiClient,
+        endpoint: &E,
+        ttl: Duration,
+    ) -> Result<E::Response, ApiError>
+    where
+        E: Endpoint,
+        E::Response: Serialize,
+    {
+        if let Some(value) = self.cached(endpoint, ttl) {
+            return Ok(value);
+        }
+        let value = client.send(endpoint).await?;
+        self.store(endpoint, &value);
+        Ok(value)
+    }
+}
+
+fn endpoint_key<E: Endpoint>(endpoint: &E) -> Option<String> {
+    let query = serde_json::to_string(endpoint.query()).ok()?;
+    Some(format!("api/{}?{}", endpoint.path(), query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> (Cache, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("rubl-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        (Cache::at(&dir), dir)
+    }
+
+    #[test]
+    fn round_trips_values_by_key() {
+        let (cache, dir) = temp_cache("round-trip");
+        let ttl = Duration::from_secs(60);
+        assert!(cache.get::<Vec<u32>>("a", ttl).is_none());
+
+        cache.put("a", &vec![1u32, 2]);
+        cache.put("b", &vec![3u32]);
+        assert_eq!(cache.get::<Vec<u32>>("a", ttl), Some(vec![1, 2]));
+        assert_eq!(cache.get::<Vec<u32>>("b", ttl), Some(vec![3]));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn expired_entries_miss() {
+        let (cache, dir) = temp_cache("expired");
+        cache.put("a", &1u32);
+        assert!(cache.get::<u32>("a", Duration::ZERO).is_none());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stale_entries_are_deleted_on_read() {
+        let (cache, dir) = temp_cache("stale");
+        cache.put("a", &1u32);
+        assert!(cache.get::<u32>("a", Duration::ZERO).is_none());
+        assert_eq!(cache.status().entries, 0);
+        assert_eq!(cache.status().evicted, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn sweep_trims_oldest_entries_to_the_size_cap() {
+        let (cache, dir) = temp_cache("sweep");
+        cache.put("a", &1u32);
+        let size = cache.status().bytes;
+        std::thread::sleep(Duration::from_millis(20));
+        cache.put("b", &2u32);
+        cache.put("c", &3u32);
+
+        let cache = Cache {
+            max_bytes: size * 2,
+            ..cache
+        };
+        assert_eq!(cache.sweep(), 1);
+        let ttl = Duration::from_secs(60);
+        assert!(cache.get::<u32>("a", ttl).is_none());
+        assert_eq!(cache.get::<u32>("c", ttl), Some(3));
+        assert_eq!(cache.status().evicted, 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn status_counts_entries() {
+        let (cache, dir) = temp_cache("status");
+        assert_eq!(cache.status().entries, 0);
+        cache.put("a", &1u32);
+        cache.put("b", &2u32);
+        let status = cache.status();
+        assert_eq!(status.entries, 2);
+        assert!(status.bytes > 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn without_a_directory_everything_misses() {
+        let cache = Cache::disabled();
+        cache.put("a", &1u32);
+        assert!(cache.get::<u32>("a", Duration::from_secs(60)).is_none());
+    }
+}
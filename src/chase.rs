@@ -0,0 +1,332 @@
+//! Rarity chase planning.
+//!
+//! Answers "is it still there, where, how far, and since when" for one
+//! species: the target location comes from the nearest recent sighting (or
+//! a location the caller picked from a rarity event), the location's recent
+//! checklist feed shows whether later birders found it, and distance plus
+//! staleness roll up into a reliability score.
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::geo;
+use crate::ids::{LocId, SpeciesCode, SubId};
+use crate::tools::checklist::{
+    ChecklistFeedQuery, FetchChecklistRequest, FetchRecentChecklistsRequest,
+};
+use crate::tools::observations::{
+    FetchSpeciesNearestRequest, FetchSpeciesRecentRequest, Observation,
+};
+
+/// Search radius for the nearest-sighting lookup (the API maximum).
+const SEARCH_RADIUS_KM: u32 = 50;
+/// Checklists pulled from the location feed.
+const FEED_SIZE: u32 = 30;
+/// Checklists opened to look for the species; each is one API call.
+const MAX_CHECKLISTS: usize = 10;
+/// Straight-line distance to driving distance.
+const ROAD_FACTOR: f64 = 1.3;
+const OTHER_LOCATIONS: usize = 5;
+
+pub struct ChaseRequest {
+    pub species_code: SpeciesCode,
+    /// Chase a specific location (e.g. from a rarity event) instead of the
+    /// nearest one.
+    pub location_id: Option<LocId>,
+    pub lat: f64,
+    pub lng: f64,
+    pub back: u32,
+    pub speed_kmh: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtherLocation {
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_id: Option<String>,
+    pub last_seen: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChasePlan {
+    pub common_name: String,
+    pub scientific_name: String,
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lng: Option<f64>,
+    pub last_seen: String,
+    /// Hours from `last_seen` to now. eBird times are the observer's local
+    /// time, compared here against the server's clock, so this is off by
+    /// the time zone difference when chasing far from the server.
+    pub hours_since: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_minutes: Option<u32>,
+    /// Complete checklists at the location since the last sighting that
+    /// did not report the species.
+    pub negative_checklists: u32,
+    /// Checklists newer than the latest report in the observation feed that
+    /// did report it (often still pending review).
+    pub positive_checklists: u32,
+    /// 0-100; higher means more likely to still be there.
+    pub reliability: u8,
+    pub recommendation: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub other_locations: Vec<OtherLocation>,
+}
+
+/// Scores how likely the bird is still present.
+///
+/// Staleness costs 12 points a day and each complete checklist that missed
+/// it since the last report costs 15; fresh positive checklists add 5.
+pub fn reliability(hours_since: f64, negatives: u32, positives: u32) -> u8 {
+    let score = 100.0 - 12.0 * (hours_since.max(0.0) / 24.0) - 15.0 * negatives as f64
+        + 5.0 * positives as f64;
+    score.clamp(0.0, 100.0).round() as u8
+}
+
+pub fn recommendation(score: u8) -> &'static str {
+    match score {
+        70.. => "Go: reported recently and not missed since",
+        40..=69 => "Possible: worth trying if you are close or can check for newer reports first",
+        _ => "Unlikely: stale or missed on recent checklists; wait for a fresh report",
+    }
+}
+
+/// Checklist outcomes after the last reported sighting, oldest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Followup {
+    Found(NaiveDateTime),
+    Missed,
+    Incomplete,
+}
+
+/// Folds follow-up checklists into (latest sighting, negatives, positives).
+/// Misses before a later positive do not count against the bird.
+pub fn tally_followups(
+    last_seen: NaiveDateTime,
+    followups: &[Followup],
+) -> (NaiveDateTime, u32, u32) {
+    let (mut latest, mut negatives, mut positives) = (last_seen, 0, 0);
+    for followup in followups {
+        match followup {
+            Followup::Found(at) => {
+                latest = latest.max(*at);
+                negatives = 0;
+                positives += 1;
+            }
+            Followup::Missed => negatives += 1,
+            Followup::Incomplete => {}
+        }
+    }
+    (latest, negatives, positives)
+}
+
+/// The newest [`MAX_CHECKLISTS`] checklists, oldest first. The newest say
+/// most about whether the bird is still there.
+fn most_recent<T: Ord>(mut checklists: Vec<(NaiveDateTime, T)>) -> Vec<(NaiveDateTime, T)> {
+    checklists.sort();
+    let stale = checklists.len().saturating_sub(MAX_CHECKLISTS);
+    checklists.drain(..stale);
+    checklists
+}
+
+fn distance_from(req: &ChaseRequest, obs: &Observation) -> Option<f64> {
+    obs.latitude
+        .zip(obs.longitude)
+        .map(|point| (geo::haversine_km((req.lat, req.lng), point) * 10.0).round() / 10.0)
+}
+
+/// Builds a chase plan, or `None` if the species has no recent reports.
+///
+/// `now` should be local wall-clock time, like eBird's observation times.
+pub async fn plan(
+    client: &ApiClient,
+    req: &ChaseRequest,
+    now: NaiveDateTime,
+) -> Result<Option<ChasePlan>, ApiError> {
+    let nearest = client
+        .send(&FetchSpeciesNearestRequest {
+            species_code: req.species_code.clone(),
+            lat: req.lat,
+            lng: req.lng,
+            dist: Some(SEARCH_RADIUS_KM),
+            back: Some(req.back),
+        })
+        .await?;
+
+    let location_id = match &req.location_id {
+        Some(id) => Some(id.clone()),
+        None => nearest
+            .first()
+            .and_then(|o| o.location_id.as_deref()?.parse().ok()),
+    };
+    let Some(location_id): Option<LocId> = location_id else {
+        return Ok(None);
+    };
+
+    // Latest report at the location itself; covers targets outside the
+    // nearest-search radius too.
+    let at_location = client
+        .send(&FetchSpeciesRecentRequest {
+            region_code: location_id.clone().into(),
+            species_code: req.species_code.clone(),
+            back: Some(req.back),
+        })
+        .await?;
+    let Some(target) = at_location
+        .into_iter()
+        .chain(
+            nearest
+                .iter()
+                .filter(|o| o.location_id.as_deref() == Some(location_id.as_str()))
+                .cloned(),
+        )
+        .max_by_key(|o| o.observed_at())
+    else {
+        return Ok(None);
+    };
+    let reported_at = target.observed_at().unwrap_or(now);
+
+    let feed = client
+        .send(&FetchRecentChecklistsRequest {
+            region_code: location_id.clone().into(),
+            query: ChecklistFeedQuery {
+                max_results: Some(FEED_SIZE),
+            },
+        })
+        .await?;
+    let later = most_recent(
+        feed.into_iter()
+            .filter_map(|c| Some((c.observed_at()?, c.sub_id.parse::<SubId>().ok()?)))
+            .filter(|(at, _)| *at > reported_at)
+            .collect(),
+    );
+
+    let mut followups = Vec::with_capacity(later.len());
+    for (at, sub_id) in later {
+        let checklist = client.send(&FetchChecklistRequest { sub_id }).await?;
+        followups.push(
+            if checklist
+                .obs
+                .iter()
+                .any(|o| req.species_code == *o.species_code)
+            {
+                Followup::Found(at)
+            } else if checklist.all_obs_reported {
+                Followup::Missed
+            } else {
+                Followup::Incomplete
+            },
+        );
+    }
+    let (last_seen, negatives, positives) = tally_followups(reported_at, &followups);
+
+    let hours_since = ((now - last_seen).num_minutes().max(0) as f64 / 60.0 * 10.0).round() / 10.0;
+    let score = reliability(hours_since, negatives, positives);
+    let distance_km = distance_from(req, &target);
+    let other_locations = nearest
+        .iter()
+        .filter(|o| o.location_id.as_deref() != Some(location_id.as_str()))
+        .take(OTHER_LOCATIONS)
+        .map(|o| OtherLocation {
+            location: o.location.clone(),
+            location_id: o.location_id.clone(),
+            last_seen: o.observation_date.clone(),
+            distance_km: distance_from(req, o),
+        })
+        .collect();
+
+    Ok(Some(ChasePlan {
+        common_name: target.common_name.clone(),
+        scientific_name: target.scientific_name.clone(),
+        location: target.location.clone(),
+        location_id: Some(location_id.into()),
+        lat: target.latitude,
+        lng: target.longitude,
+        last_seen: last_seen.format("%Y-%m-%d %H:%M").to_string(),
+        hours_since,
+        last_count: target.count,
+        distance_km,
+        eta_minutes: distance_km
+            .map(|km| (km * ROAD_FACTOR / req.speed_kmh.max(1.0) * 60.0).round() as u32),
+        negative_checklists: negatives,
+        positive_checklists: positives,
+        reliability: score,
+        recommendation: recommendation(score).to_string(),
+        other_locations,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn reliability_decays_with_time_and_misses() {
+        assert_eq!(reliability(0.0, 0, 0), 100);
+        assert_eq!(reliability(24.0, 0, 0), 88);
+        assert_eq!(reliability(24.0, 2, 0), 58);
+        assert_eq!(reliability(240.0, 0, 0), 0);
+        assert_eq!(reliability(48.0, 0, 2), 86);
+    }
+
+    #[test]
+    fn recommendation_tracks_score() {
+        assert!(recommendation(90).starts_with("Go"));
+        assert!(recommendation(50).starts_with("Possible"));
+        assert!(recommendation(10).starts_with("Unlikely"));
+    }
+
+    #[test]
+    fn later_positive_resets_misses() {
+        let last = at("2023-05-01 08:00");
+        let (latest, negatives, positives) = tally_followups(
+            last,
+            &[
+                Followup::Missed,
+                Followup::Found(at("2023-05-01 12:00")),
+                Followup::Incomplete,
+                Followup::Missed,
+            ],
+        );
+        assert_eq!(latest, at("2023-05-01 12:00"));
+        assert_eq!(negatives, 1);
+        assert_eq!(positives, 1);
+    }
+
+    #[test]
+    fn keeps_the_newest_checklists() {
+        let checklists: Vec<_> = (0..15u32)
+            .rev()
+            .map(|i| {
+                (
+                    at("2023-05-01 08:00") + chrono::Duration::hours(i.into()),
+                    i,
+                )
+            })
+            .collect();
+        let kept: Vec<u32> = most_recent(checklists)
+            .into_iter()
+            .map(|(_, i)| i)
+            .collect();
+        assert_eq!(kept, (5..15).collect::<Vec<_>>());
+    }
+}
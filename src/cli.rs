@@ -0,0 +1,538 @@
+//! Command-line interface.
+//!
+//! `rubl` (or `rubl serve`) runs the MCP server on stdio. The other
+//! subcommands are for debugging without an MCP client: they start the same
+//! server in-process, connect to it over an in-memory pipe, and print what a
+//! client would receive, so tool calls go through the same request structs,
+//! defaults, and output formatting.
+//!
+//! Tool parameters are passed as flags named after the schema's properties,
+//! in kebab or snake case: `rubl call fetch_notable_recent --region-code US-NC
+//! --back 3`. Values are read according to the parameter's type; array
+//! parameters take the flag once per element.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use rmcp::{
+    RoleClient, ServiceExt,
+    model::{CallToolRequestParams, Content, JsonObject, RawContent, ResourceContents, Tool},
+    service::{RunningService, ServiceError},
+};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::api::cassette::Cassette;
+use crate::config::{OutputFormat, Source};
+use crate::defaults::is_type;
+use crate::server::RublClient;
+
+#[derive(Debug, Parser)]
+#[command(name = "rubl", version, about = "eBird API MCP server")]
+pub struct Cli {
+    /// Config file to read instead of the default location.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Named profile from the config file.
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Output format for tool results, overriding the config.
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<OutputFormat>,
+    /// Save every eBird response to fixture files in DIR.
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Serve eBird responses from the fixture files in DIR, offline.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the MCP server on stdio (the default).
+    Serve,
+    /// List or describe the available tools.
+    Tools {
+        #[command(subcommand)]
+        command: ToolsCommand,
+    },
+    /// Call a tool and print its result.
+    Call {
+        /// Tool name, as listed by `rubl tools list`.
+        tool: String,
+        /// Tool parameters as `--name value` flags.
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "PARAMS"
+        )]
+        params: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ToolsCommand {
+    /// List tool names with a one-line summary.
+    List,
+    /// Show a tool's description and parameters.
+    Show { tool: String },
+}
+
+impl Cli {
+    pub fn source(&self) -> Source {
+        Source {
+            path: self.config.clone(),
+            profile: self.profile.clone(),
+        }
+    }
+
+    /// The cassette asked for on the command line, if any.
+    pub fn cassette(&self) -> Option<Cassette> {
+        match (&self.record, &self.replay) {
+            (Some(dir), _) => Some(Cassette::record(dir)),
+            (None, Some(dir)) => Some(Cassette::replay(dir)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Command {
+    /// Whether the command can do anything useful without an API key.
+    pub fn needs_api_key(&self) -> bool {
+        !matches!(self, Command::Tools { .. })
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ArgError {
+    #[error("Unknown tool '{0}'; see `rubl tools list`")]
+    UnknownTool(String),
+
+    #[error("Unknown parameter --{name}; expected one of: {expected}")]
+    UnknownParameter { name: String, expected: String },
+
+    #[error("Parameter --{0} needs a value")]
+    MissingValue(String),
+
+    #[error("Invalid value '{value}' for --{name}: expected {expected}")]
+    InvalidValue {
+        name: String,
+        value: String,
+        expected: &'static str,
+    },
+
+    #[error("Unexpected argument '{0}'; parameters are passed as --name value")]
+    Unexpected(String),
+}
+
+/// Reads one flag's value according to its schema.
+fn parse_value(name: &str, value: &str, schema: &Value) -> Result<Value, ArgError> {
+    let invalid = |expected| ArgError::InvalidValue {
+        name: name.to_string(),
+        value: value.to_string(),
+        expected,
+    };
+    if is_type(schema, "integer") {
+        value
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| invalid("an integer"))
+    } else if is_type(schema, "number") {
+        value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| invalid("a number"))
+    } else if is_type(schema, "boolean") {
+        value
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| invalid("true or false"))
+    } else if is_type(schema, "string") {
+        Ok(value.into())
+    } else {
+        // Enums and nested objects: JSON if it parses, else a plain string.
+        Ok(serde_json::from_str(value).unwrap_or_else(|_| value.into()))
+    }
+}
+
+/// Turns `--name value` flags into tool arguments using the tool's schema.
+pub fn arguments(tool: &Tool, params: &[String]) -> Result<JsonObject, ArgError> {
+    let empty = JsonObject::new();
+    let properties = tool
+        .input_schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let mut arguments = JsonObject::new();
+    let mut params = params.iter().peekable();
+    while let Some(param) = params.next() {
+        let Some(flag) = param.strip_prefix("--") else {
+            return Err(ArgError::Unexpected(param.clone()));
+        };
+        let (flag, inline) = match flag.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let name = flag.replace('-', "_");
+        let schema = properties
+            .get(&name)
+            .ok_or_else(|| ArgError::UnknownParameter {
+                name: flag.to_string(),
+                expected: properties
+                    .keys()
+                    .map(|key| format!("--{}", key.replace('_', "-")))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            })?;
+        let value = match inline {
+            Some(value) => value,
+            // A bare boolean flag means true.
+            None if is_type(schema, "boolean")
+                && params.peek().is_none_or(|next| next.starts_with("--")) =>
+            {
+                "true".into()
+            }
+            None => params
+                .next()
+                .cloned()
+                .ok_or_else(|| ArgError::MissingValue(flag.to_string()))?,
+        };
+        if is_type(schema, "array") {
+            let item = parse_value(flag, &value, &schema["items"])?;
+            match arguments
+                .entry(name)
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                Value::Array(items) => items.push(item),
+                _ => unreachable!("array parameters only ever hold arrays"),
+            }
+        } else {
+            arguments.insert(name, parse_value(flag, &value, schema)?);
+        }
+    }
+    Ok(arguments)
+}
+
+/// Starts `server` in-process and connects a client to it. Watches are not
+/// polled, so a one-off command never touches a running server's watch state.
+async fn connect(server: RublClient) -> Result<RunningService<RoleClient, ()>, ServiceError> {
+    let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+    let server = server.without_watch_poller();
+    tokio::spawn(async move {
+        match server.serve(server_io).await {
+            Ok(service) => {
+                let _ = service.waiting().await;
+            }
+            Err(error) => tracing::error!(%error, "In-process server failed to start"),
+        }
+    });
+    ().serve(client_io).await.map_err(|error| {
+        tracing::error!(%error, "Failed to connect to in-process server");
+        ServiceError::TransportClosed
+    })
+}
+
+fn summary(tool: &Tool) -> &str {
+    let description = tool.description.as_deref().unwrap_or_default();
+    match description.find(". ") {
+        Some(end) => &description[..=end],
+        None => description,
+    }
+}
+
+fn print_tool(tool: &Tool) {
+    println!("{}\n", tool.name);
+    println!("{}\n", tool.description.as_deref().unwrap_or_default());
+    let required: Vec<&str> = tool
+        .input_schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let Some(properties) = tool
+        .input_schema
+        .get("properties")
+        .and_then(Value::as_object)
+    else {
+        return;
+    };
+    println!("Parameters:");
+    for (name, schema) in properties {
+        let kind = match &schema["type"] {
+            Value::String(kind) => kind.clone(),
+            Value::Array(kinds) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|kind| *kind != "null")
+                .unwrap_or("value")
+                .to_string(),
+            _ => "value".into(),
+        };
+        let required = if required.contains(&name.as_str()) {
+            ", required"
+        } else {
+            ""
+        };
+        println!(
+            "  --{} <{}{}>  {}",
+            name.replace('_', "-"),
+            kind,
+            required,
+            schema["description"].as_str().unwrap_or_default()
+        );
+    }
+}
+
+fn print_content(content: &Content, error: bool) {
+    let text = match &content.raw {
+        RawContent::Text(text) => text.text.clone(),
+        RawContent::Resource(resource) => match &resource.resource {
+            ResourceContents::TextResourceContents { text, .. } => text.clone(),
+            other => serde_json::to_string(other).unwrap_or_default(),
+        },
+        other => serde_json::to_string(other).unwrap_or_default(),
+    };
+    if error {
+        eprintln!("{}", text);
+    } else {
+        println!("{}", text);
+    }
+}
+
+/// Runs a debugging subcommand against an in-process server, printing any
+/// failure to stderr.
+pub async fn run(command: Command, server: RublClient, format: OutputFormat) -> ExitCode {
+    match execute(command, server, format).await {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn execute(
+    command: Command,
+    server: RublClient,
+    format: OutputFormat,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let client = connect(server).await?;
+    let mut tools = client.list_all_tools().await?;
+    tools.sort_by(|a, b| a.name.cmp(&b.name));
+    let find = |name: &str| {
+        tools
+            .iter()
+            .find(|tool| tool.name == name)
+            .ok_or_else(|| ArgError::UnknownTool(name.to_string()))
+    };
+
+    let code = match command {
+        Command::Serve => unreachable!("serve is handled by main"),
+        Command::Tools {
+            command: ToolsCommand::List,
+        } => {
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&tools)?),
+                OutputFormat::Toon => {
+                    let width = tools.iter().map(|t| t.name.len()).max().unwrap_or(0);
+                    for tool in &tools {
+                        println!("{:width$}  {}", tool.name, summary(tool));
+                    }
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Tools {
+            command: ToolsCommand::Show { tool },
+        } => {
+            let tool = find(&tool)?;
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(tool)?),
+                OutputFormat::Toon => print_tool(tool),
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Call { tool, params } => {
+            let arguments = arguments(find(&tool)?, &params)?;
+            let result = client
+                .call_tool(CallToolRequestParams {
+                    meta: None,
+                    name: tool.into(),
+                    arguments: Some(arguments),
+                    task: None,
+                })
+                .await;
+            match result {
+                Ok(result) => {
+                    let error = result.is_error.unwrap_or(false);
+                    for content in &result.content {
+                        print_content(content, error);
+                    }
+                    if error {
+                        ExitCode::FAILURE
+                    } else {
+                        ExitCode::SUCCESS
+                    }
+                }
+                Err(ServiceError::McpError(error)) => {
+                    eprintln!("Error: {}", error.message);
+                    if let Some(data) = &error.data {
+                        eprintln!("{}", serde_json::to_string_pretty(data)?);
+                    }
+                    ExitCode::FAILURE
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    };
+    let _ = client.cancel().await;
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::AlertDispatcher;
+    use crate::config::Config;
+    use crate::tools::arrivals::FindArrivalsRequest;
+    use crate::tools::observations::{FetchGeoRecentRequest, FetchHistoricRequest};
+    use crate::watch::WatchManager;
+    use rmcp::handler::server::common::schema_for_type;
+
+    fn flags(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_subcommands_and_global_flags() {
+        let cli = Cli::try_parse_from([
+            "rubl",
+            "--profile=coast",
+            "call",
+            "fetch_notable_recent",
+            "--region-code",
+            "US-NC",
+        ])
+        .unwrap();
+        assert_eq!(cli.source().profile.as_deref(), Some("coast"));
+        let Some(Command::Call { tool, params }) = cli.command else {
+            panic!("expected call");
+        };
+        assert_eq!(tool, "fetch_notable_recent");
+        assert_eq!(params, flags(&["--region-code", "US-NC"]));
+
+        let cli = Cli::try_parse_from(["rubl", "--config", "/tmp/rubl.toml"]).unwrap();
+        assert_eq!(cli.source().path, Some(PathBuf::from("/tmp/rubl.toml")));
+        assert!(cli.command.is_none());
+        assert!(cli.cassette().is_none());
+
+        let cli =
+            Cli::try_parse_from(["rubl", "--replay", "fixtures/nc", "tools", "list"]).unwrap();
+        assert_eq!(cli.cassette(), Some(Cassette::replay("fixtures/nc")));
+        assert!(Cli::try_parse_from(["rubl", "--record", "a", "--replay", "b"]).is_err());
+    }
+
+    #[test]
+    fn converts_flags_by_schema_type() {
+        let tool = Tool::new("t", "", schema_for_type::<FetchGeoRecentRequest>());
+        let arguments = arguments(
+            &tool,
+            &flags(&["--lat", "35.78", "--lng=-78.64", "--dist", "10"]),
+        )
+        .unwrap();
+        assert_eq!(arguments["lat"], 35.78);
+        assert_eq!(arguments["lng"], -78.64);
+        assert_eq!(arguments["dist"], 10);
+        let request: FetchGeoRecentRequest =
+            serde_json::from_value(Value::Object(arguments)).unwrap();
+        assert_eq!(request.dist, Some(10));
+    }
+
+    #[test]
+    fn repeats_array_flags() {
+        let tool = Tool::new("t", "", schema_for_type::<FetchHistoricRequest>());
+        let arguments = arguments(
+            &tool,
+            &flags(&["--locations", "L123", "--locations", "US-NC-063"]),
+        )
+        .unwrap();
+        assert_eq!(
+            arguments["locations"],
+            serde_json::json!(["L123", "US-NC-063"])
+        );
+    }
+
+    #[test]
+    fn bare_boolean_flags_are_true() {
+        let tool = Tool::new("t", "", schema_for_type::<FindArrivalsRequest>());
+        let arguments = arguments(
+            &tool,
+            &flags(&["--include-departures", "--recent-days", "3"]),
+        )
+        .unwrap();
+        assert_eq!(arguments["include_departures"], true);
+        assert_eq!(arguments["recent_days"], 3);
+        let arguments = super::arguments(&tool, &flags(&["--include-departures=false"])).unwrap();
+        assert_eq!(arguments["include_departures"], false);
+    }
+
+    #[test]
+    fn rejects_bad_flags() {
+        let tool = Tool::new("t", "", schema_for_type::<FetchGeoRecentRequest>());
+        assert!(matches!(
+            arguments(&tool, &flags(&["--region", "US"])),
+            Err(ArgError::UnknownParameter { .. })
+        ));
+        assert_eq!(
+            arguments(&tool, &flags(&["--dist", "far"])),
+            Err(ArgError::InvalidValue {
+                name: "dist".into(),
+                value: "far".into(),
+                expected: "an integer",
+            })
+        );
+        assert_eq!(
+            arguments(&tool, &flags(&["--lat"])),
+            Err(ArgError::MissingValue("lat".into()))
+        );
+        assert_eq!(
+            arguments(&tool, &flags(&["US-NC"])),
+            Err(ArgError::Unexpected("US-NC".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn commands_leave_persisted_watches_alone() {
+        let dir = std::env::temp_dir().join(format!("rubl-cli-watches-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("watches.json");
+        // Long overdue, so a poller would fetch (and fail, replaying an
+        // empty cassette) and save its attempt at once.
+        let state = r#"{"nextId":1,"watches":[{"id":"w1","target":{"type":"region","region_code":"US-NC"},"back":7,"intervalMinutes":30,"createdAt":"2023-05-01T00:00:00Z","lastPolled":"2023-05-01T00:00:00Z","recent":[]}]}"#;
+        std::fs::write(&path, state).unwrap();
+
+        let config = Config {
+            cassette: Some(Cassette::replay(dir.join("cassette"))),
+            ..Config::default()
+        };
+        let server = RublClient::new(String::new(), &config).unwrap();
+        let watches = WatchManager::load_from(
+            server.api_client().clone(),
+            AlertDispatcher::new(Vec::new()),
+            Some(path.clone()),
+        );
+        let command = Command::Tools {
+            command: ToolsCommand::List,
+        };
+        run(command, server.with_watches(watches), OutputFormat::Json).await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), state);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,545 @@
+//! User configuration.
+//!
+//! Settings come from a TOML file: `--config <path>`, else `$RUBL_CONFIG`,
+//! else `config.toml` in the platform config directory (for example
+//! `~/.config/rubl/config.toml`), which may be absent. The `[defaults]`
+//! table fills in tool parameters the caller omits; a named profile
+//! (`--profile`, `$RUBL_PROFILE`, or `profile = "..."` in the file) is laid
+//! over it, and `RUBL_*` environment variables over that, which is how the
+//! bundle's user settings arrive. `RUBL_RECORD=<dir>` or `RUBL_REPLAY=<dir>`
+//! attach a cassette (see [`crate::api::cassette`]).
+//!
+//! ```toml
+//! profile = "home"
+//!
+//! [defaults]
+//! lat = 35.78
+//! lng = -78.64
+//! radius = 15
+//! units = "mi"
+//! back = 14
+//! locale = "en"
+//! format = "toon"
+//! disabled_tools = ["import_ebd"]
+//!
+//! [profiles.coast]
+//! lat = 34.69
+//! lng = -76.68
+//!
+//! [cache]
+//! dir = "/var/cache/rubl"
+//! recent_ttl_secs = 3600
+//! max_bytes = 536870912
+//!
+//! [http]
+//! timeout_secs = 30
+//! max_concurrent_requests = 8
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::api::cassette::Cassette;
+use crate::api::client::DEFAULT_MAX_CONCURRENT_REQUESTS;
+
+const CONFIG_FILE: &str = "config.toml";
+const KM_PER_MILE: f64 = 1.609_344;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("Unknown profile '{name}' (available: {available})")]
+    UnknownProfile { name: String, available: String },
+
+    #[error("Invalid {var} '{value}': {reason}")]
+    InvalidEnv {
+        var: &'static str,
+        value: String,
+        reason: String,
+    },
+
+    #[error("Invalid config: {0}")]
+    Invalid(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Km,
+    Mi,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Compact TOON text (default).
+    #[default]
+    Toon,
+    /// Plain JSON.
+    Json,
+}
+
+/// Values a profile can set; every field is optional so layers can be merged.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Defaults {
+    /// Home latitude, used when a tool's `lat`/`lng` are omitted.
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+    /// Search radius in `units`.
+    pub radius: Option<f64>,
+    /// Unit for `radius`, and the one the user prefers to read distances in.
+    pub units: Option<Units>,
+    /// Days back for recent-observation tools.
+    pub back: Option<u32>,
+    /// Locale for species common names (`spp_locale`).
+    pub locale: Option<String>,
+    pub format: Option<OutputFormat>,
+    /// If set, only these tools are offered.
+    pub enabled_tools: Option<Vec<String>>,
+    /// Tools never offered.
+    pub disabled_tools: Option<Vec<String>>,
+}
+
+impl Defaults {
+    /// `other`'s values win where it has them.
+    fn overlay(&mut self, other: Defaults) {
+        macro_rules! take {
+            ($($field:ident),*) => {
+                $(if other.$field.is_some() {
+                    self.$field = other.$field;
+                })*
+            };
+        }
+        take!(
+            lat,
+            lng,
+            radius,
+            units,
+            back,
+            locale,
+            format,
+            enabled_tools,
+            disabled_tools
+        );
+    }
+
+    /// Home coordinates, if both are configured.
+    pub fn home(&self) -> Option<(f64, f64)> {
+        self.lat.zip(self.lng)
+    }
+
+    /// The configured radius in kilometers.
+    pub fn radius_km(&self) -> Option<f64> {
+        self.radius.map(|r| match self.units.unwrap_or_default() {
+            Units::Km => r,
+            Units::Mi => r * KM_PER_MILE,
+        })
+    }
+
+    pub fn is_tool_enabled(&self, name: &str) -> bool {
+        self.enabled_tools
+            .as_ref()
+            .is_none_or(|enabled| enabled.iter().any(|t| t == name))
+            && !self
+                .disabled_tools
+                .as_ref()
+                .is_some_and(|disabled| disabled.iter().any(|t| t == name))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Defaults to `cache/` in the data directory.
+    pub dir: Option<PathBuf>,
+    /// Observations from the last week, which are still being submitted.
+    pub recent_ttl_secs: u64,
+    /// Older observations and checklist feeds.
+    pub historic_ttl_secs: u64,
+    /// Results computed from many responses, such as frequency tables.
+    pub derived_ttl_secs: u64,
+    /// Oldest entries are deleted once the cache grows past this.
+    pub max_bytes: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            recent_ttl_secs: 60 * 60,
+            historic_ttl_secs: 30 * 24 * 60 * 60,
+            derived_ttl_secs: 7 * 24 * 60 * 60,
+            max_bytes: 512 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Transport {
+    #[default]
+    Stdio,
+}
+
+impl Transport {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Transport::Stdio => "stdio",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportConfig {
+    pub kind: Transport,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// Whole-request timeout for eBird API calls.
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    /// Requests to eBird allowed in flight at once.
+    pub max_concurrent_requests: usize,
+    /// Defaults to `https://api.ebird.org/v2`.
+    pub base_url: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            base_url: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+}
+
+/// The file as written.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    api_key: Option<String>,
+    profile: Option<String>,
+    defaults: Defaults,
+    profiles: BTreeMap<String, Defaults>,
+    cache: CacheConfig,
+    transport: TransportConfig,
+    http: HttpConfig,
+}
+
+/// Where to look, from the command line (see [`crate::cli::Cli`]).
+#[derive(Debug, Default)]
+pub struct Source {
+    pub path: Option<PathBuf>,
+    pub profile: Option<String>,
+}
+
+/// Resolved configuration.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// The file settings were read from, if any.
+    pub path: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub api_key: Option<String>,
+    pub defaults: Defaults,
+    pub cache: CacheConfig,
+    pub transport: TransportConfig,
+    pub http: HttpConfig,
+    /// Record or replay eBird responses (`RUBL_RECORD` / `RUBL_REPLAY`).
+    pub cassette: Option<Cassette>,
+}
+
+fn env(var: &str) -> Option<String> {
+    std::env::var(var)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn env_parsed<T: std::str::FromStr>(var: &'static str) -> Result<Option<T>, ConfigError>
+where
+    T::Err: std::fmt::Display,
+{
+    env(var)
+        .map(|value| {
+            value.parse().map_err(|e: T::Err| ConfigError::InvalidEnv {
+                var,
+                reason: e.to_string(),
+                value,
+            })
+        })
+        .transpose()
+}
+
+fn env_enum<T: for<'de> Deserialize<'de>>(var: &'static str) -> Result<Option<T>, ConfigError> {
+    env(var)
+        .map(|value| {
+            T::deserialize(
+                serde::de::value::StrDeserializer::<serde::de::value::Error>::new(
+                    &value.to_ascii_lowercase(),
+                ),
+            )
+            .map_err(|e| ConfigError::InvalidEnv {
+                var,
+                reason: e.to_string(),
+                value,
+            })
+        })
+        .transpose()
+}
+
+/// Settings from `RUBL_*` variables.
+fn env_defaults() -> Result<Defaults, ConfigError> {
+    let list = |var| env(var).map(|v| v.split(',').map(|t| t.trim().to_string()).collect());
+    Ok(Defaults {
+        lat: env_parsed("RUBL_LAT")?,
+        lng: env_parsed("RUBL_LNG")?,
+        radius: env_parsed("RUBL_RADIUS")?,
+        units: env_enum("RUBL_UNITS")?,
+        back: env_parsed("RUBL_BACK")?,
+        locale: env("RUBL_LOCALE"),
+        format: env_enum("RUBL_FORMAT")?,
+        enabled_tools: list("RUBL_ENABLED_TOOLS"),
+        disabled_tools: list("RUBL_DISABLED_TOOLS"),
+    })
+}
+
+fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rubl").join(CONFIG_FILE))
+}
+
+impl Config {
+    /// Loads the file `source` points at (a missing default file is fine),
+    /// applies the profile, then the environment.
+    pub fn load(source: Source) -> Result<Self, ConfigError> {
+        let explicit = source
+            .path
+            .or_else(|| env("RUBL_CONFIG").map(PathBuf::from));
+        let (file, path) = match explicit {
+            Some(path) => (read(&path)?, Some(path)),
+            None => match default_path().filter(|path| path.exists()) {
+                Some(path) => (read(&path)?, Some(path)),
+                None => (ConfigFile::default(), None),
+            },
+        };
+        let profile = source
+            .profile
+            .or_else(|| env("RUBL_PROFILE"))
+            .or(file.profile.clone());
+        let mut config = Self::resolve(file, profile, path)?;
+        config.defaults.overlay(env_defaults()?);
+        if let Some(key) = env("EBIRD_API_KEY") {
+            config.api_key = Some(key);
+        }
+        config.cassette = match (env("RUBL_RECORD"), env("RUBL_REPLAY")) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::Invalid(
+                    "set RUBL_RECORD or RUBL_REPLAY, not both".into(),
+                ));
+            }
+            (Some(dir), None) => Some(Cassette::record(dir)),
+            (None, Some(dir)) => Some(Cassette::replay(dir)),
+            (None, None) => None,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn resolve(
+        mut file: ConfigFile,
+        profile: Option<String>,
+        path: Option<PathBuf>,
+    ) -> Result<Self, ConfigError> {
+        let mut defaults = file.defaults;
+        if let Some(name) = &profile {
+            let layer = file
+                .profiles
+                .remove(name)
+                .ok_or_else(|| ConfigError::UnknownProfile {
+                    name: name.clone(),
+                    available: file.profiles.keys().cloned().collect::<Vec<_>>().join(", "),
+                })?;
+            defaults.overlay(layer);
+        }
+        Ok(Self {
+            path,
+            profile,
+            api_key: file.api_key,
+            defaults,
+            cache: file.cache,
+            transport: file.transport,
+            http: file.http,
+            cassette: None,
+        })
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let d = &self.defaults;
+        if d.lat.is_some() != d.lng.is_some() {
+            return Err(ConfigError::Invalid(
+                "lat and lng must be set together".into(),
+            ));
+        }
+        if let Some((lat, lng)) = d.home()
+            && !((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng))
+        {
+            return Err(ConfigError::Invalid(format!(
+                "home {}, {} is not a valid coordinate",
+                lat, lng
+            )));
+        }
+        if d.radius.is_some_and(|r| r <= 0.0) {
+            return Err(ConfigError::Invalid("radius must be positive".into()));
+        }
+        if d.back == Some(0) {
+            return Err(ConfigError::Invalid("back must be at least 1".into()));
+        }
+        if self.cache.max_bytes == 0 {
+            return Err(ConfigError::Invalid(
+                "cache max_bytes must be positive".into(),
+            ));
+        }
+        if self.http.timeout_secs == 0 || self.http.connect_timeout_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "HTTP timeouts must be positive".into(),
+            ));
+        }
+        if self.http.max_concurrent_requests == 0 {
+            return Err(ConfigError::Invalid(
+                "max_concurrent_requests must be at least 1".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn read(path: &Path) -> Result<ConfigFile, ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    toml::from_str(&text).map_err(|source| ConfigError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> ConfigFile {
+        toml::from_str(text).unwrap()
+    }
+
+    const SAMPLE: &str = r#"
+        api_key = "abc"
+        profile = "home"
+
+        [defaults]
+        lat = 35.78
+        lng = -78.64
+        radius = 10
+        units = "mi"
+        back = 14
+        disabled_tools = ["import_ebd"]
+
+        [profiles.home]
+        locale = "es"
+
+        [profiles.coast]
+        lat = 34.69
+        lng = -76.68
+        back = 3
+        format = "json"
+
+        [cache]
+        recent_ttl_secs = 60
+
+        [http]
+        timeout_secs = 5
+    "#;
+
+    #[test]
+    fn profile_overlays_defaults() {
+        let config = Config::resolve(parse(SAMPLE), Some("coast".into()), None).unwrap();
+        assert_eq!(config.defaults.home(), Some((34.69, -76.68)));
+        assert_eq!(config.defaults.back, Some(3));
+        assert_eq!(config.defaults.format, Some(OutputFormat::Json));
+        assert_eq!(config.defaults.locale, None);
+        assert!((config.defaults.radius_km().unwrap() - 16.09344).abs() < 1e-9);
+        assert_eq!(config.cache.recent_ttl_secs, 60);
+        assert_eq!(
+            config.cache.historic_ttl_secs,
+            CacheConfig::default().historic_ttl_secs
+        );
+        assert_eq!(config.http.timeout(), Duration::from_secs(5));
+        assert_eq!(config.http.connect_timeout_secs, 10);
+        assert_eq!(
+            config.http.max_concurrent_requests,
+            DEFAULT_MAX_CONCURRENT_REQUESTS
+        );
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn unknown_profile_lists_available() {
+        let error = Config::resolve(parse(SAMPLE), Some("mountains".into()), None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown profile 'mountains' (available: coast, home)"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_half_a_home() {
+        assert!(toml::from_str::<ConfigFile>("[defaults]\nradius_km = 5").is_err());
+        assert!(toml::from_str::<ConfigFile>("[transport]\nkind = \"carrier-pigeon\"").is_err());
+        let config = Config::resolve(parse("[defaults]\nlat = 35.0"), None, None).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn tool_filters() {
+        let defaults = Defaults {
+            enabled_tools: Some(vec!["fetch_rare".into(), "import_ebd".into()]),
+            disabled_tools: Some(vec!["import_ebd".into()]),
+            ..Defaults::default()
+        };
+        assert!(defaults.is_tool_enabled("fetch_rare"));
+        assert!(!defaults.is_tool_enabled("import_ebd"));
+        assert!(!defaults.is_tool_enabled("query_ebd"));
+        assert!(Defaults::default().is_tool_enabled("query_ebd"));
+    }
+}
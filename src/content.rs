@@ -33,6 +33,9 @@ mod tests {
                 location_id: Some("L123456".into()),
                 latitude: Some(35.9132),
                 longitude: Some(-79.0558),
+                species_code: None,
+                submission_id: None,
+                details: Default::default(),
             };
 
             // First verify the JSON serialization works
@@ -60,6 +63,9 @@ mod tests {
                 location_id: None,
                 latitude: None,
                 longitude: None,
+                species_code: None,
+                submission_id: None,
+                details: Default::default(),
             };
 
             // Verify JSON serialization with null optional fields
@@ -86,6 +92,9 @@ mod tests {
                     location_id: None,
                     latitude: None,
                     longitude: None,
+                    species_code: None,
+                    submission_id: None,
+                    details: Default::default(),
                 },
                 Observation {
                     common_name: "Eastern Bluebird".into(),
@@ -96,6 +105,9 @@ mod tests {
                     location_id: None,
                     latitude: None,
                     longitude: None,
+                    species_code: None,
+                    submission_id: None,
+                    details: Default::default(),
                 },
             ];
 
@@ -271,4 +283,56 @@ mod tests {
             assert!(content.is_ok());
         }
     }
+
+    /// Renders responses replayed from a recorded cassette and compares them
+    /// with the snapshots next to it, so formatting changes show up as diffs.
+    mod cassette_snapshots {
+        use super::*;
+        use crate::api::cassette::Cassette;
+        use crate::api::client::ApiClient;
+        use crate::events;
+        use crate::tools::observations::{FetchNotableRecentRequest, RarityView};
+
+        const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+        fn text(content: Content) -> String {
+            content.as_text().unwrap().text.clone()
+        }
+
+        fn snapshot(name: &str) -> String {
+            std::fs::read_to_string(format!("{}/snapshots/{}", FIXTURES, name)).unwrap()
+        }
+
+        async fn notable_reports() -> Vec<crate::tools::observations::RareBird> {
+            let client = ApiClient::new("").with_cassette(Cassette::replay(format!(
+                "{}/cassettes/us-nc-notable",
+                FIXTURES
+            )));
+            let request = FetchNotableRecentRequest {
+                region_code: "US-NC".parse().unwrap(),
+                back: Some(3),
+                view: RarityView::Reports,
+            };
+            client.send(&request).await.unwrap()
+        }
+
+        #[tokio::test]
+        async fn notable_reports_match_snapshot() {
+            let content = notable_reports().await.to_content().unwrap();
+            assert_eq!(
+                text(content).trim_end(),
+                snapshot("us-nc-notable-reports.toon").trim_end()
+            );
+        }
+
+        #[tokio::test]
+        async fn notable_events_match_snapshot() {
+            let events = events::cluster(notable_reports().await);
+            let content = events.to_content().unwrap();
+            assert_eq!(
+                text(content).trim_end(),
+                snapshot("us-nc-notable-events.toon").trim_end()
+            );
+        }
+    }
 }
@@ -0,0 +1,238 @@
+//! Fills omitted tool parameters from the configured defaults.
+//!
+//! At startup each tool's input schema is checked for the parameters a
+//! default exists for (`lat`/`lng` from home, `dist`, `back`, `spp_locale`).
+//! The value is fitted to the parameter's type and range, the schema notes
+//! the default, and `lat`/`lng` stop being required. At call time any of
+//! those parameters the caller left out are filled in before the arguments
+//! are parsed, so the tools themselves never see the difference.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rmcp::model::{JsonObject, Tool};
+use serde_json::Value;
+
+use crate::config::Defaults;
+
+/// Per-tool argument values to use when the caller omits them.
+#[derive(Debug, Clone, Default)]
+pub struct ParameterDefaults {
+    by_tool: HashMap<String, JsonObject>,
+}
+
+/// Whether a JSON schema allows `wanted` (`"integer"`, `"array"`, ...).
+pub fn is_type(schema: &Value, wanted: &str) -> bool {
+    match &schema["type"] {
+        Value::String(t) => t == wanted,
+        Value::Array(types) => types.iter().any(|t| t == wanted),
+        _ => false,
+    }
+}
+
+/// Fits a number to the parameter: rounded for integers, clamped to its range.
+fn fit_number(value: f64, schema: &Value) -> Option<Value> {
+    let min = schema["minimum"].as_f64().unwrap_or(f64::MIN);
+    let max = schema["maximum"].as_f64().unwrap_or(f64::MAX);
+    let value = value.clamp(min, max);
+    if is_type(schema, "integer") {
+        Some((value.round() as i64).into())
+    } else if is_type(schema, "number") {
+        serde_json::Number::from_f64(value).map(Value::Number)
+    } else {
+        None
+    }
+}
+
+impl ParameterDefaults {
+    /// Works out which defaults apply to each tool and notes them in the
+    /// tools' schemas.
+    pub fn prepare<'a>(defaults: &Defaults, tools: impl IntoIterator<Item = &'a mut Tool>) -> Self {
+        let mut by_tool = HashMap::new();
+        for tool in tools {
+            let values = Self::for_schema(defaults, &tool.input_schema);
+            if values.is_empty() {
+                continue;
+            }
+            let mut schema = (*tool.input_schema).clone();
+            if let Some(Value::Array(required)) = schema.get_mut("required") {
+                required.retain(|name| name.as_str().is_none_or(|n| !values.contains_key(n)));
+            }
+            if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+                for (name, value) in &values {
+                    let Some(Value::Object(property)) = properties.get_mut(name) else {
+                        continue;
+                    };
+                    let note = format!("Defaults to {} from the rubl config.", value);
+                    let description = match property.get("description").and_then(Value::as_str) {
+                        Some(text) if text.ends_with('.') => format!("{} {}", text, note),
+                        Some(text) => format!("{}. {}", text, note),
+                        None => note,
+                    };
+                    property.insert("description".into(), description.into());
+                }
+            }
+            tool.input_schema = Arc::new(schema);
+            by_tool.insert(tool.name.to_string(), values);
+        }
+        Self { by_tool }
+    }
+
+    fn for_schema(defaults: &Defaults, schema: &JsonObject) -> JsonObject {
+        let mut values = JsonObject::new();
+        let Some(Value::Object(properties)) = schema.get("properties") else {
+            return values;
+        };
+        let mut numbers = Vec::new();
+        if let Some((lat, lng)) = defaults.home()
+            && properties.contains_key("lat")
+            && properties.contains_key("lng")
+        {
+            numbers.extend([("lat", lat), ("lng", lng)]);
+        }
+        if let Some(radius) = defaults.radius_km() {
+            numbers.push(("dist", radius));
+        }
+        if let Some(back) = defaults.back {
+            numbers.push(("back", back.into()));
+        }
+        for (name, value) in numbers {
+            if let Some(value) = properties.get(name).and_then(|p| fit_number(value, p)) {
+                values.insert(name.into(), value);
+            }
+        }
+        if let Some(locale) = &defaults.locale
+            && properties
+                .get("spp_locale")
+                .is_some_and(|p| is_type(p, "string"))
+        {
+            values.insert("spp_locale".into(), locale.as_str().into());
+        }
+        values
+    }
+
+    /// Adds the defaults for `tool` that `arguments` leaves out (or sets to null).
+    pub fn apply(&self, tool: &str, arguments: &mut Option<JsonObject>) {
+        let Some(values) = self.by_tool.get(tool) else {
+            return;
+        };
+        let arguments = arguments.get_or_insert_with(JsonObject::new);
+        let given =
+            |arguments: &JsonObject, name: &str| arguments.get(name).is_some_and(|v| !v.is_null());
+        // Home coordinates only make sense as a pair.
+        let located = given(arguments, "lat") || given(arguments, "lng");
+        for (name, value) in values {
+            let is_location = matches!(name.as_str(), "lat" | "lng");
+            let skip = given(arguments, name) || (is_location && located);
+            if !skip {
+                arguments.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Units;
+    use crate::tools::observations::{FetchGeoRecentRequest, FetchHistoricRequest};
+    use rmcp::handler::server::common::schema_for_type;
+
+    fn defaults() -> Defaults {
+        Defaults {
+            lat: Some(35.78),
+            lng: Some(-78.64),
+            radius: Some(50.0),
+            units: Some(Units::Mi),
+            back: Some(45),
+            locale: Some("es".into()),
+            ..Defaults::default()
+        }
+    }
+
+    fn tools() -> Vec<Tool> {
+        vec![
+            Tool::new(
+                "fetch_geo_recent",
+                "",
+                schema_for_type::<FetchGeoRecentRequest>(),
+            ),
+            Tool::new(
+                "fetch_historic",
+                "",
+                schema_for_type::<FetchHistoricRequest>(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn fits_defaults_to_each_schema() {
+        let mut tools = tools();
+        let prepared = ParameterDefaults::prepare(&defaults(), &mut tools);
+
+        let mut arguments = None;
+        prepared.apply("fetch_geo_recent", &mut arguments);
+        let request: FetchGeoRecentRequest =
+            serde_json::from_value(Value::Object(arguments.unwrap())).unwrap();
+        assert_eq!((request.lat, request.lng), (35.78, -78.64));
+        // 50 mi is past the 50 km cap; back is capped at 30 days.
+        assert_eq!(request.dist, Some(50));
+        assert_eq!(request.back, Some(30));
+
+        let geo = &tools[0].input_schema;
+        assert!(
+            !geo["required"]
+                .as_array()
+                .is_some_and(|r| r.iter().any(|n| n == "lat"))
+        );
+        assert!(
+            geo["properties"]["lat"]["description"]
+                .as_str()
+                .unwrap()
+                .contains("Defaults to 35.78")
+        );
+
+        let mut arguments = serde_json::json!({ "region_code": "US-NC", "date": "2023-05-09" })
+            .as_object()
+            .cloned();
+        prepared.apply("fetch_historic", &mut arguments);
+        let arguments = arguments.unwrap();
+        assert_eq!(arguments["spp_locale"], "es");
+        assert!(!arguments.contains_key("lat"));
+    }
+
+    #[test]
+    fn caller_values_win() {
+        let mut tools = tools();
+        let prepared = ParameterDefaults::prepare(&defaults(), &mut tools);
+        let mut arguments = serde_json::json!({ "lat": 40.0, "lng": -75.0, "back": 2 })
+            .as_object()
+            .cloned();
+        prepared.apply("fetch_geo_recent", &mut arguments);
+        let arguments = arguments.unwrap();
+        assert_eq!(arguments["lat"], 40.0);
+        assert_eq!(arguments["lng"], -75.0);
+        assert_eq!(arguments["back"], 2);
+        assert_eq!(arguments["dist"], 50);
+    }
+
+    #[test]
+    fn half_a_location_is_left_for_the_tool_to_reject() {
+        let mut tools = tools();
+        let prepared = ParameterDefaults::prepare(&defaults(), &mut tools);
+        let mut arguments = serde_json::json!({ "lat": 40.0 }).as_object().cloned();
+        prepared.apply("fetch_geo_recent", &mut arguments);
+        assert!(!arguments.unwrap().contains_key("lng"));
+    }
+
+    #[test]
+    fn nothing_configured_leaves_schemas_alone() {
+        let mut tools = tools();
+        let before = tools[0].input_schema.clone();
+        let prepared = ParameterDefaults::prepare(&Defaults::default(), &mut tools);
+        assert_eq!(tools[0].input_schema, before);
+        let mut arguments = None;
+        prepared.apply("fetch_geo_recent", &mut arguments);
+        assert!(arguments.is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Health checks: is the API key good, can we reach eBird, and what state
+//! is the server in.
+//!
+//! The probe calls `ref/taxonomy/versions`, which is tiny and uncached, so
+//! it measures the round trip and tells us the current taxonomy in one go.
+//! Setting `RUBL_VALIDATE_KEY=1` runs it once at startup and refuses to
+//! start with a key eBird rejects.
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::error::{ApiError, ErrorKind};
+use crate::cache::CacheStatus;
+use crate::tools::species::{FetchTaxonomyVersionsRequest, TaxonomyVersion};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Probe {
+    /// `None` when eBird could not be asked.
+    pub key_valid: Option<bool>,
+    pub reachable: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taxonomy_version: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Probe {
+    fn from_result(result: Result<Vec<TaxonomyVersion>, ApiError>, latency_ms: u64) -> Self {
+        match result {
+            Ok(versions) => Self {
+                key_valid: Some(true),
+                reachable: true,
+                latency_ms,
+                taxonomy_version: versions
+                    .iter()
+                    .find(|v| v.latest)
+                    .or_else(|| versions.last())
+                    .map(|v| v.authority_ver),
+                error: None,
+            },
+            Err(error) => Self {
+                key_valid: match error.kind() {
+                    ErrorKind::Unauthorized => Some(false),
+                    _ => None,
+                },
+                reachable: error.status().is_some(),
+                latency_ms,
+                taxonomy_version: None,
+                error: Some(error.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub version: &'static str,
+    pub transport: &'static str,
+    pub api: Probe,
+    pub cache: CacheStatus,
+}
+
+/// Times one cheap authenticated request.
+pub async fn probe(client: &ApiClient) -> Probe {
+    let started = Instant::now();
+    let result = client.send(&FetchTaxonomyVersionsRequest).await;
+    Probe::from_result(result, started.elapsed().as_millis() as u64)
+}
+
+/// Whether `RUBL_VALIDATE_KEY` asks for a startup probe.
+pub fn startup_probe_enabled() -> bool {
+    std::env::var("RUBL_VALIDATE_KEY").is_ok_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes"
+        )
+    })
+}
+
+/// Fails only if eBird rejects the key; an unreachable API is logged and
+/// left for tool calls to report.
+pub async fn check_key(client: &ApiClient) -> Result<(), String> {
+    let probe = probe(client).await;
+    match (probe.key_valid, probe.error) {
+        (Some(false), Some(error)) => Err(format!("EBIRD_API_KEY was rejected: {}", error)),
+        (_, Some(error)) => {
+            tracing::warn!(%error, "Could not verify EBIRD_API_KEY");
+            Ok(())
+        }
+        _ => {
+            tracing::info!(latency_ms = probe.latency_ms, "API key verified");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    mod from_result {
+        use super::*;
+
+        #[test]
+        fn reports_latest_taxonomy() {
+            let versions = vec![
+                TaxonomyVersion {
+                    authority_ver: 2023.0,
+                    latest: false,
+                },
+                TaxonomyVersion {
+                    authority_ver: 2024.0,
+                    latest: true,
+                },
+            ];
+            let probe = Probe::from_result(Ok(versions), 120);
+            assert_eq!(probe.key_valid, Some(true));
+            assert!(probe.reachable);
+            assert_eq!(probe.taxonomy_version, Some(2024.0));
+        }
+
+        #[test]
+        fn rejected_key_is_invalid_but_reachable() {
+            let error = ApiError::from_response(StatusCode::FORBIDDEN, String::new(), None);
+            let probe = Probe::from_result(Err(error), 80);
+            assert_eq!(probe.key_valid, Some(false));
+            assert!(probe.reachable);
+            assert!(probe.error.is_some());
+        }
+
+        #[test]
+        fn outage_leaves_key_unknown() {
+            let error = ApiError::from_response(StatusCode::BAD_GATEWAY, String::new(), None);
+            let probe = Probe::from_result(Err(error), 80);
+            assert_eq!(probe.key_valid, None);
+            assert!(probe.reachable);
+
+            let error = ApiError::InvalidRequest("never sent".into());
+            assert!(!Probe::from_result(Err(error), 0).reachable);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Hotspot dossiers: everything a trip leader reads before an outing.
+//!
+//! The info record is required; recent and notable observations, the
+//! all-time species list, and the latest checklists are fetched alongside
+//! it and reported under `errors` if they fail.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::geo;
+use crate::ids::LocId;
+use crate::tools::checklist::{ChecklistFeedQuery, ChecklistSummary, FetchRecentChecklistsRequest};
+use crate::tools::hotspot::{FetchHotspotInfoRequest, FetchSpeciesListRequest, Hotspot};
+use crate::tools::observations::{
+    FetchNotableRecentRequest, FetchRegionRecentRequest, Observation, RareBird, RarityView,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SunSummary {
+    /// Today at the hotspot, from UTC and its longitude rather than the
+    /// server's clock.
+    pub date: NaiveDate,
+    /// UTC; absent during polar day or night.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunrise: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotspotDossier {
+    pub hotspot: Hotspot,
+    pub sun: SunSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent: Option<Vec<Observation>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notable: Option<Vec<RareBird>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_all_time: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_checklists: Option<Vec<ChecklistSummary>>,
+    /// Parts that could not be fetched, by part name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<&'static str, String>,
+}
+
+pub struct DossierRequest {
+    pub loc_id: LocId,
+    pub back: u32,
+    pub checklists: u32,
+    /// The current time; sunrise and sunset are for this date at the hotspot.
+    pub now: DateTime<Utc>,
+}
+
+fn keep<T>(
+    part: &'static str,
+    result: Result<T, ApiError>,
+    errors: &mut BTreeMap<&'static str, String>,
+) -> Option<T> {
+    result.map_err(|e| errors.insert(part, e.to_string())).ok()
+}
+
+/// Fetches every part concurrently; fails only if the info record does.
+pub async fn build(client: &ApiClient, req: &DossierRequest) -> Result<HotspotDossier, ApiError> {
+    let info_request = FetchHotspotInfoRequest {
+        loc_id: req.loc_id.clone(),
+    };
+    let recent_request = FetchRegionRecentRequest {
+        region_code: req.loc_id.clone().into(),
+        back: Some(req.back),
+    };
+    let notable_request = FetchNotableRecentRequest {
+        region_code: req.loc_id.clone().into(),
+        back: Some(req.back),
+        view: RarityView::Reports,
+    };
+    let species_request = FetchSpeciesListRequest {
+        region_code: req.loc_id.clone().into(),
+    };
+    let checklists_request = FetchRecentChecklistsRequest {
+        region_code: req.loc_id.clone().into(),
+        query: ChecklistFeedQuery {
+            max_results: Some(req.checklists),
+        },
+    };
+    let (info, recent, notable, species, checklists) = tokio::join!(
+        client.send(&info_request),
+        client.send(&recent_request),
+        client.send(&notable_request),
+        client.send(&species_request),
+        client.send(&checklists_request),
+    );
+
+    let hotspot = info?;
+    let date = geo::local_date(req.now, hotspot.longitude);
+    let times = geo::sun_times(date, hotspot.latitude, hotspot.longitude);
+    let mut errors = BTreeMap::new();
+    Ok(HotspotDossier {
+        sun: SunSummary {
+            date,
+            sunrise: times.sunrise,
+            sunset: times.sunset,
+        },
+        hotspot,
+        recent: keep("recent", recent, &mut errors),
+        notable: keep("notable", notable, &mut errors),
+        species_all_time: keep("speciesAllTime", species, &mut errors),
+        latest_checklists: keep("latestChecklists", checklists, &mut errors),
+        errors,
+    })
+}
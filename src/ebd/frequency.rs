@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, params};
+
+use super::EbdError;
+use super::query::region_column;
+use crate::frequency::{Tally, Taxon, week_of};
+
+/// Tallies complete checklists in a region, one sample per checklist.
+///
+/// Uses sampling-event rows when the region has any, otherwise falls back
+/// to the checklists implied by the observations themselves.
+pub(super) fn tally(
+    conn: &Connection,
+    region_code: &str,
+    years: Option<RangeInclusive<i32>>,
+    weeks: RangeInclusive<usize>,
+) -> Result<Tally, EbdError> {
+    let column = region_column(region_code)?;
+    let (start, end) = match &years {
+        Some(years) => (
+            format!("{:04}-01-01", years.start()),
+            format!("{:04}-12-31", years.end()),
+        ),
+        None => ("0000-01-01".to_string(), "9999-12-31".to_string()),
+    };
+
+    let in_weeks = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(week_of)
+            .filter(|week| weeks.contains(week))
+    };
+
+    let mut checklists: Vec<(String, usize)> = Vec::new();
+    let mut statement = conn.prepare(&format!(
+        "SELECT checklist_id, observation_date FROM checklists
+         WHERE {column} = ?1 AND all_species_reported = 1
+           AND observation_date BETWEEN ?2 AND ?3"
+    ))?;
+    let mut rows = statement.query(params![region_code, start, end])?;
+    while let Some(row) = rows.next()? {
+        let date: String = row.get(1)?;
+        if let Some(week) = in_weeks(&date) {
+            checklists.push((row.get(0)?, week));
+        }
+    }
+    let from_observations = checklists.is_empty();
+
+    let mut taxa: HashMap<String, Vec<Taxon>> = HashMap::new();
+    let mut statement = conn.prepare(&format!(
+        "SELECT checklist_id, observation_date, common_name, scientific_name FROM observations
+         WHERE {column} = ?1 AND approved = 1
+           AND observation_date BETWEEN ?2 AND ?3"
+    ))?;
+    let mut rows = statement.query(params![region_code, start, end])?;
+    while let Some(row) = rows.next()? {
+        let checklist_id: String = row.get(0)?;
+        let date: String = row.get(1)?;
+        let Some(week) = in_weeks(&date) else {
+            continue;
+        };
+        let entry = taxa.entry(checklist_id.clone()).or_insert_with(|| {
+            if from_observations {
+                checklists.push((checklist_id, week));
+            }
+            Vec::new()
+        });
+        entry.push(Taxon {
+            species_code: None,
+            common_name: Some(row.get(2)?),
+            scientific_name: Some(row.get(3)?),
+        });
+    }
+
+    let mut tally = Tally::default();
+    for (checklist_id, week) in checklists {
+        tally.record_sample(week, taxa.remove(&checklist_id).unwrap_or_default());
+    }
+    Ok(tally)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture_store;
+    use crate::frequency::week_of;
+
+    #[test]
+    fn frequencies_use_complete_checklists() {
+        let (store, dir) = fixture_store("frequency");
+        let table = store
+            .frequencies("US-NC", Some(2023..=2023), 0..=47)
+            .unwrap()
+            .into_table();
+
+        let may = week_of("2023-05-15".parse().unwrap());
+        let june = week_of("2023-06-02".parse().unwrap());
+        assert_eq!(table.samples[may], 1);
+        assert_eq!(table.samples[june], 2);
+
+        let swallow = table
+            .species
+            .iter()
+            .find(|s| s.common_name.as_deref() == Some("Barn Swallow"))
+            .unwrap();
+        assert_eq!(swallow.frequencies[may], 1.0);
+        assert_eq!(swallow.frequencies[june], 0.5);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
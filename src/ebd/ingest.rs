@@ -0,0 +1,376 @@
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::rc::Rc;
+
+use csv::StringRecord;
+use flate2::read::MultiGzDecoder;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+
+use super::EbdError;
+
+/// Rows per transaction; large batches keep multi-GB imports fast.
+const BATCH_SIZE: usize = 50_000;
+const PROGRESS_EVERY: u64 = 1_000_000;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub file: String,
+    pub rows_imported: u64,
+    pub rows_skipped: u64,
+}
+
+/// Column positions looked up by header name, since EBD releases add and
+/// reorder columns over time.
+struct Columns {
+    headers: StringRecord,
+}
+
+impl Columns {
+    fn required(&self, name: &'static str) -> Result<usize, EbdError> {
+        self.optional(name).ok_or(EbdError::MissingColumn(name))
+    }
+
+    fn optional(&self, name: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name))
+    }
+}
+
+fn field(record: &StringRecord, index: Option<usize>) -> Option<&str> {
+    index
+        .and_then(|i| record.get(i))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Counts bytes read from the file on disk, so progress can be reported
+/// against its size even when the contents are gzipped.
+struct Counted<R> {
+    inner: R,
+    read: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for Counted<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.set(self.read.get() + n as u64);
+        Ok(n)
+    }
+}
+
+fn open(file: &Path, read: Rc<Cell<u64>>) -> Result<csv::Reader<Box<dyn Read>>, EbdError> {
+    let raw = BufReader::with_capacity(
+        1 << 20,
+        Counted {
+            inner: File::open(file)?,
+            read,
+        },
+    );
+    let reader: Box<dyn Read> = if file.extension().is_some_and(|ext| ext == "gz") {
+        Box::new(MultiGzDecoder::new(raw))
+    } else {
+        Box::new(raw)
+    };
+    // EBD is tab-separated with unescaped quotes in free-text columns.
+    Ok(csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .quoting(false)
+        .flexible(true)
+        .from_reader(reader))
+}
+
+/// Streams rows into the database in batched transactions.
+///
+/// `resolve` maps header names to column positions once; `insert` binds one
+/// record and returns `false` if it was skipped for missing required fields.
+/// `on_progress` gets the bytes read so far and the file size after each batch.
+fn load<C, R, F>(
+    conn: &mut Connection,
+    file: &Path,
+    sql: &str,
+    resolve: R,
+    mut insert: F,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<ImportSummary, EbdError>
+where
+    R: FnOnce(&Columns) -> Result<C, EbdError>,
+    F: FnMut(&mut rusqlite::Statement<'_>, &StringRecord, &C) -> Result<bool, EbdError>,
+{
+    let size = std::fs::metadata(file)?.len() as usize;
+    let read = Rc::new(Cell::new(0));
+    let mut reader = open(file, read.clone())?;
+    let columns = resolve(&Columns {
+        headers: reader.headers()?.clone(),
+    })?;
+    conn.execute_batch("PRAGMA synchronous = OFF;")?;
+
+    let mut summary = ImportSummary {
+        file: file.display().to_string(),
+        rows_imported: 0,
+        rows_skipped: 0,
+    };
+    let mut record = StringRecord::new();
+    let mut done = false;
+    while !done {
+        let tx = conn.transaction()?;
+        {
+            let mut statement = tx.prepare_cached(sql)?;
+            for _ in 0..BATCH_SIZE {
+                if !reader.read_record(&mut record)? {
+                    done = true;
+                    break;
+                }
+                if insert(&mut statement, &record, &columns)? {
+                    summary.rows_imported += 1;
+                    if summary.rows_imported.is_multiple_of(PROGRESS_EVERY) {
+                        tracing::info!(
+                            rows = summary.rows_imported,
+                            file = %summary.file,
+                            "EBD import progress"
+                        );
+                    }
+                } else {
+                    summary.rows_skipped += 1;
+                }
+            }
+        }
+        tx.commit()?;
+        on_progress((read.get() as usize).min(size), size);
+    }
+
+    conn.execute_batch("PRAGMA synchronous = NORMAL; ANALYZE;")?;
+    Ok(summary)
+}
+
+struct ObservationColumns {
+    id: usize,
+    checklist: usize,
+    common: usize,
+    scientific: usize,
+    date: usize,
+    category: Option<usize>,
+    count: Option<usize>,
+    country: Option<usize>,
+    state: Option<usize>,
+    county: Option<usize>,
+    locality: Option<usize>,
+    locality_id: Option<usize>,
+    lat: Option<usize>,
+    lng: Option<usize>,
+    time: Option<usize>,
+    approved: Option<usize>,
+}
+
+impl ObservationColumns {
+    fn resolve(columns: &Columns) -> Result<Self, EbdError> {
+        Ok(Self {
+            id: columns.required("GLOBAL UNIQUE IDENTIFIER")?,
+            checklist: columns.required("SAMPLING EVENT IDENTIFIER")?,
+            common: columns.required("COMMON NAME")?,
+            scientific: columns.required("SCIENTIFIC NAME")?,
+            date: columns.required("OBSERVATION DATE")?,
+            category: columns.optional("CATEGORY"),
+            count: columns.optional("OBSERVATION COUNT"),
+            country: columns.optional("COUNTRY CODE"),
+            state: columns.optional("STATE CODE"),
+            county: columns.optional("COUNTY CODE"),
+            locality: columns.optional("LOCALITY"),
+            locality_id: columns.optional("LOCALITY ID"),
+            lat: columns.optional("LATITUDE"),
+            lng: columns.optional("LONGITUDE"),
+            time: columns.optional("TIME OBSERVATIONS STARTED"),
+            approved: columns.optional("APPROVED"),
+        })
+    }
+}
+
+pub(super) fn observations(
+    conn: &mut Connection,
+    file: &Path,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<ImportSummary, EbdError> {
+    let sql = "INSERT OR REPLACE INTO observations (
+        global_id, checklist_id, category, common_name, scientific_name, observation_count,
+        country_code, state_code, county_code, locality, locality_id, latitude, longitude,
+        observation_date, time_started, approved
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)";
+
+    load(
+        conn,
+        file,
+        sql,
+        ObservationColumns::resolve,
+        |statement, record, c| {
+            let (Some(id), Some(checklist), Some(common), Some(scientific), Some(date)) = (
+                field(record, Some(c.id)),
+                field(record, Some(c.checklist)),
+                field(record, Some(c.common)),
+                field(record, Some(c.scientific)),
+                field(record, Some(c.date)),
+            ) else {
+                return Ok(false);
+            };
+            // "X" means the species was present but not counted.
+            let count: Option<u32> = field(record, c.count).and_then(|v| v.parse().ok());
+            let approved = field(record, c.approved).is_none_or(|v| v != "0");
+            statement.execute(params![
+                id,
+                checklist,
+                field(record, c.category),
+                common,
+                scientific,
+                count,
+                field(record, c.country),
+                field(record, c.state),
+                field(record, c.county),
+                field(record, c.locality),
+                field(record, c.locality_id),
+                field(record, c.lat).and_then(|v| v.parse::<f64>().ok()),
+                field(record, c.lng).and_then(|v| v.parse::<f64>().ok()),
+                date,
+                field(record, c.time),
+                approved,
+            ])?;
+            Ok(true)
+        },
+        on_progress,
+    )
+}
+
+struct SamplingColumns {
+    checklist: usize,
+    date: usize,
+    country: Option<usize>,
+    state: Option<usize>,
+    county: Option<usize>,
+    locality: Option<usize>,
+    locality_id: Option<usize>,
+    lat: Option<usize>,
+    lng: Option<usize>,
+    time: Option<usize>,
+    protocol: Option<usize>,
+    duration: Option<usize>,
+    distance: Option<usize>,
+    observers: Option<usize>,
+    complete: Option<usize>,
+}
+
+impl SamplingColumns {
+    fn resolve(columns: &Columns) -> Result<Self, EbdError> {
+        Ok(Self {
+            checklist: columns.required("SAMPLING EVENT IDENTIFIER")?,
+            date: columns.required("OBSERVATION DATE")?,
+            country: columns.optional("COUNTRY CODE"),
+            state: columns.optional("STATE CODE"),
+            county: columns.optional("COUNTY CODE"),
+            locality: columns.optional("LOCALITY"),
+            locality_id: columns.optional("LOCALITY ID"),
+            lat: columns.optional("LATITUDE"),
+            lng: columns.optional("LONGITUDE"),
+            time: columns.optional("TIME OBSERVATIONS STARTED"),
+            protocol: columns.optional("PROTOCOL TYPE"),
+            duration: columns.optional("DURATION MINUTES"),
+            distance: columns.optional("EFFORT DISTANCE KM"),
+            observers: columns.optional("NUMBER OBSERVERS"),
+            complete: columns.optional("ALL SPECIES REPORTED"),
+        })
+    }
+}
+
+pub(super) fn sampling(
+    conn: &mut Connection,
+    file: &Path,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<ImportSummary, EbdError> {
+    let sql = "INSERT OR REPLACE INTO checklists (
+        checklist_id, country_code, state_code, county_code, locality, locality_id, latitude,
+        longitude, observation_date, time_started, protocol, duration_minutes,
+        effort_distance_km, number_observers, all_species_reported
+    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)";
+
+    load(
+        conn,
+        file,
+        sql,
+        SamplingColumns::resolve,
+        |statement, record, c| {
+            let (Some(checklist), Some(date)) = (
+                field(record, Some(c.checklist)),
+                field(record, Some(c.date)),
+            ) else {
+                return Ok(false);
+            };
+            statement.execute(params![
+                checklist,
+                field(record, c.country),
+                field(record, c.state),
+                field(record, c.county),
+                field(record, c.locality),
+                field(record, c.locality_id),
+                field(record, c.lat).and_then(|v| v.parse::<f64>().ok()),
+                field(record, c.lng).and_then(|v| v.parse::<f64>().ok()),
+                date,
+                field(record, c.time),
+                field(record, c.protocol),
+                field(record, c.duration).and_then(|v| v.parse::<u32>().ok()),
+                field(record, c.distance).and_then(|v| v.parse::<f64>().ok()),
+                field(record, c.observers).and_then(|v| v.parse::<u32>().ok()),
+                field(record, c.complete).map(|v| v == "1"),
+            ])?;
+            Ok(true)
+        },
+        on_progress,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, write::GzEncoder};
+
+    use super::super::EbdStore;
+    use super::super::tests::OBSERVATIONS;
+
+    #[test]
+    fn reads_gzipped_extracts() {
+        let dir = std::env::temp_dir().join(format!("rubl-ebd-gz-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("ebd.txt.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(OBSERVATIONS.as_bytes()).unwrap();
+        std::fs::write(&file, encoder.finish().unwrap()).unwrap();
+
+        let store = EbdStore::at(dir.join("ebd.sqlite"));
+        let mut reports = Vec::new();
+        let summary = store
+            .import_observations(&file, |done, total| reports.push((done, total)))
+            .unwrap();
+        assert_eq!(summary.rows_imported, 4);
+        let size = std::fs::metadata(&file).unwrap().len() as usize;
+        assert_eq!(reports.last(), Some(&(size, size)));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_required_column_is_an_error() {
+        let dir = std::env::temp_dir().join(format!("rubl-ebd-cols-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("bad.txt");
+        std::fs::write(
+            &file,
+            "COMMON NAME\tOBSERVATION DATE\nBarn Swallow\t2023-05-15\n",
+        )
+        .unwrap();
+
+        let store = EbdStore::at(dir.join("ebd.sqlite"));
+        let err = store.import_observations(&file, |_, _| {}).unwrap_err();
+        assert!(err.to_string().contains("GLOBAL UNIQUE IDENTIFIER"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,287 @@
+//! Offline eBird Basic Dataset (EBD) store.
+//!
+//! EBD observation and sampling-event TSV extracts (optionally gzipped) are
+//! streamed into a local SQLite database with indexes on date, region,
+//! location, and species. Queries return the same [`Observation`] shape as
+//! the live API, for any date range and without network access.
+//!
+//! The database lives at `$RUBL_EBD_PATH`, defaulting to `ebd.sqlite` in
+//! the data directory.
+
+mod frequency;
+mod ingest;
+mod query;
+
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use thiserror::Error;
+
+pub use ingest::ImportSummary;
+pub use query::EbdFilter;
+
+use crate::frequency::Tally;
+use crate::storage;
+use crate::tools::observations::Observation;
+
+const DB_FILE: &str = "ebd.sqlite";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS observations (
+    global_id TEXT PRIMARY KEY,
+    checklist_id TEXT NOT NULL,
+    category TEXT,
+    common_name TEXT NOT NULL,
+    scientific_name TEXT NOT NULL,
+    observation_count INTEGER,
+    country_code TEXT,
+    state_code TEXT,
+    county_code TEXT,
+    locality TEXT,
+    locality_id TEXT,
+    latitude REAL,
+    longitude REAL,
+    observation_date TEXT NOT NULL,
+    time_started TEXT,
+    approved INTEGER NOT NULL DEFAULT 1
+);
+CREATE INDEX IF NOT EXISTS observations_date ON observations (observation_date);
+CREATE INDEX IF NOT EXISTS observations_state ON observations (state_code, observation_date);
+CREATE INDEX IF NOT EXISTS observations_county ON observations (county_code, observation_date);
+CREATE INDEX IF NOT EXISTS observations_locality ON observations (locality_id, observation_date);
+CREATE INDEX IF NOT EXISTS observations_scientific ON observations (scientific_name COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS observations_common ON observations (common_name COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS observations_checklist ON observations (checklist_id);
+
+CREATE TABLE IF NOT EXISTS checklists (
+    checklist_id TEXT PRIMARY KEY,
+    country_code TEXT,
+    state_code TEXT,
+    county_code TEXT,
+    locality TEXT,
+    locality_id TEXT,
+    latitude REAL,
+    longitude REAL,
+    observation_date TEXT NOT NULL,
+    time_started TEXT,
+    protocol TEXT,
+    duration_minutes INTEGER,
+    effort_distance_km REAL,
+    number_observers INTEGER,
+    all_species_reported INTEGER
+);
+CREATE INDEX IF NOT EXISTS checklists_date ON checklists (observation_date);
+CREATE INDEX IF NOT EXISTS checklists_state ON checklists (state_code, observation_date);
+CREATE INDEX IF NOT EXISTS checklists_county ON checklists (county_code, observation_date);
+CREATE INDEX IF NOT EXISTS checklists_locality ON checklists (locality_id, observation_date);
+";
+
+#[derive(Debug, Error)]
+pub enum EbdError {
+    #[error("EBD database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("Failed to read EBD file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse EBD file: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("EBD file is missing required column '{0}'")]
+    MissingColumn(&'static str),
+
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("No data directory available for the EBD store (set RUBL_EBD_PATH)")]
+    NoPath,
+}
+
+/// Handle to the local EBD database.
+///
+/// Connections are opened per operation so the store can be shared across
+/// blocking tasks.
+#[derive(Debug, Clone)]
+pub struct EbdStore {
+    path: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EbdStats {
+    pub path: String,
+    pub observations: u64,
+    pub checklists: u64,
+    pub first_date: Option<String>,
+    pub last_date: Option<String>,
+}
+
+impl EbdStore {
+    /// Opens the store at the configured location.
+    pub fn open_default() -> Result<Self, EbdError> {
+        let path = std::env::var_os("RUBL_EBD_PATH")
+            .map(PathBuf::from)
+            .or_else(|| storage::data_file(DB_FILE))
+            .ok_or(EbdError::NoPath)?;
+        Ok(Self::at(path))
+    }
+
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Whether any data has been imported yet.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    fn connect(&self) -> Result<Connection, EbdError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&self.path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(conn)
+    }
+
+    /// Imports an EBD observation extract (`ebd_*.txt` or `.txt.gz`).
+    pub fn import_observations(
+        &self,
+        file: &Path,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<ImportSummary, EbdError> {
+        let mut conn = self.connect()?;
+        ingest::observations(&mut conn, file, on_progress)
+    }
+
+    /// Imports a sampling-event extract (`ebd_sampling_*.txt` or `.txt.gz`).
+    pub fn import_sampling(
+        &self,
+        file: &Path,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<ImportSummary, EbdError> {
+        let mut conn = self.connect()?;
+        ingest::sampling(&mut conn, file, on_progress)
+    }
+
+    pub fn query(&self, filter: &EbdFilter) -> Result<Vec<Observation>, EbdError> {
+        let conn = self.connect()?;
+        query::observations(&conn, filter)
+    }
+
+    /// Tallies bar-chart frequencies for a region or location.
+    pub fn frequencies(
+        &self,
+        region_code: &str,
+        years: Option<RangeInclusive<i32>>,
+        weeks: RangeInclusive<usize>,
+    ) -> Result<Tally, EbdError> {
+        let conn = self.connect()?;
+        frequency::tally(&conn, region_code, years, weeks)
+    }
+
+    /// Counts what has been imported. Reports an empty store without
+    /// creating the database, so asking doesn't look like an import.
+    pub fn stats(&self) -> Result<EbdStats, EbdError> {
+        if !self.exists() {
+            return Ok(EbdStats {
+                path: self.path.display().to_string(),
+                observations: 0,
+                checklists: 0,
+                first_date: None,
+                last_date: None,
+            });
+        }
+        let conn = self.connect()?;
+        let count = |table: &str| -> Result<u64, EbdError> {
+            Ok(
+                conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })?,
+            )
+        };
+        let (first_date, last_date) = conn.query_row(
+            "SELECT MIN(observation_date), MAX(observation_date) FROM observations",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(EbdStats {
+            path: self.path.display().to_string(),
+            observations: count("observations")?,
+            checklists: count("checklists")?,
+            first_date,
+            last_date,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const OBSERVATIONS: &str = "\
+GLOBAL UNIQUE IDENTIFIER\tLAST EDITED DATE\tTAXONOMIC ORDER\tCATEGORY\tCOMMON NAME\tSCIENTIFIC NAME\tOBSERVATION COUNT\tCOUNTRY CODE\tSTATE CODE\tCOUNTY CODE\tLOCALITY\tLOCALITY ID\tLATITUDE\tLONGITUDE\tOBSERVATION DATE\tTIME OBSERVATIONS STARTED\tSAMPLING EVENT IDENTIFIER\tAPPROVED
+URN:CornellLabOfOrnithology:EBIRD:OBS1\t2023-05-16\t1\tspecies\tBarn Swallow\tHirundo rustica\t3\tUS\tUS-NC\tUS-NC-063\tDuke Forest\tL100\t36.0\t-78.9\t2023-05-15\t07:30:00\tS1\t1
+URN:CornellLabOfOrnithology:EBIRD:OBS2\t2023-05-16\t2\tspecies\tWhimbrel\tNumenius phaeopus\tX\tUS\tUS-NC\tUS-NC-063\tDuke Forest\tL100\t36.0\t-78.9\t2023-05-15\t07:30:00\tS1\t1
+URN:CornellLabOfOrnithology:EBIRD:OBS3\t2023-05-20\t1\tspecies\tBarn Swallow\tHirundo rustica\t5\tUS\tUS-NC\tUS-NC-183\tLake Johnson\tL200\t35.8\t-78.7\t2023-06-02\t\tS2\t1
+URN:CornellLabOfOrnithology:EBIRD:OBS4\t2023-05-20\t1\tspecies\tBarn Swallow\tHirundo rustica\t1\tUS\tUS-VA\tUS-VA-059\tSomewhere\tL300\t38.8\t-77.3\t2023-05-15\t08:00:00\tS3\t0
+";
+
+    pub(crate) const SAMPLING: &str = "\
+LAST EDITED DATE\tCOUNTRY CODE\tSTATE CODE\tCOUNTY CODE\tLOCALITY\tLOCALITY ID\tLATITUDE\tLONGITUDE\tOBSERVATION DATE\tTIME OBSERVATIONS STARTED\tSAMPLING EVENT IDENTIFIER\tPROTOCOL TYPE\tDURATION MINUTES\tEFFORT DISTANCE KM\tNUMBER OBSERVERS\tALL SPECIES REPORTED
+2023-05-16\tUS\tUS-NC\tUS-NC-063\tDuke Forest\tL100\t36.0\t-78.9\t2023-05-15\t07:30:00\tS1\tTraveling\t90\t2.1\t1\t1
+2023-05-20\tUS\tUS-NC\tUS-NC-183\tLake Johnson\tL200\t35.8\t-78.7\t2023-06-02\t\tS2\tStationary\t30\t\t2\t1
+2023-05-20\tUS\tUS-NC\tUS-NC-183\tLake Johnson\tL200\t35.8\t-78.7\t2023-06-03\t06:45:00\tS4\tStationary\t30\t\t1\t1
+";
+
+    /// Creates a store in a unique temp directory populated with the fixtures.
+    pub(crate) fn fixture_store(name: &str) -> (EbdStore, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("rubl-ebd-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let observations = dir.join("ebd.txt");
+        let sampling = dir.join("sampling.txt");
+        std::fs::write(&observations, OBSERVATIONS).unwrap();
+        std::fs::write(&sampling, SAMPLING).unwrap();
+
+        let store = EbdStore::at(dir.join("ebd.sqlite"));
+        store.import_observations(&observations, |_, _| {}).unwrap();
+        store.import_sampling(&sampling, |_, _| {}).unwrap();
+        (store, dir)
+    }
+
+    #[test]
+    fn stats_cover_imported_rows() {
+        let (store, dir) = fixture_store("stats");
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.observations, 4);
+        assert_eq!(stats.checklists, 3);
+        assert_eq!(stats.first_date.as_deref(), Some("2023-05-15"));
+        assert_eq!(stats.last_date.as_deref(), Some("2023-06-02"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn stats_leave_a_missing_store_missing() {
+        let dir = std::env::temp_dir().join(format!("rubl-ebd-missing-{}", std::process::id()));
+        let store = EbdStore::at(dir.join("ebd.sqlite"));
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.observations, 0);
+        assert_eq!(stats.first_date, None);
+        assert!(!store.exists());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn reimport_is_idempotent() {
+        let (store, dir) = fixture_store("reimport");
+        store
+            .import_observations(&dir.join("ebd.txt"), |_, _| {})
+            .unwrap();
+        assert_eq!(store.stats().unwrap().observations, 4);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
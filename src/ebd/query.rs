@@ -0,0 +1,183 @@
+use chrono::NaiveDate;
+use rusqlite::{Connection, ToSql};
+
+use super::EbdError;
+use crate::tools::observations::Observation;
+
+/// Column to filter on for an eBird region or location code.
+pub(crate) fn region_column(code: &str) -> Result<&'static str, EbdError> {
+    let is_location =
+        code.len() > 1 && code.starts_with('L') && code[1..].chars().all(|c| c.is_ascii_digit());
+    if is_location {
+        return Ok("locality_id");
+    }
+    if code == "world" {
+        return Err(EbdError::InvalidQuery(
+            "'world' is not a region filter; use a country, subnational, or location code".into(),
+        ));
+    }
+    match code.split('-').count() {
+        1 => Ok("country_code"),
+        2 => Ok("state_code"),
+        3 => Ok("county_code"),
+        _ => Err(EbdError::InvalidQuery(format!(
+            "'{}' is not a country, subnational, or location code",
+            code
+        ))),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EbdFilter {
+    /// Country, subnational1, subnational2, or location code.
+    pub region_code: Option<String>,
+    /// Common or scientific name, matched case-insensitively.
+    pub species: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub limit: usize,
+    /// Include records that have not passed eBird review.
+    pub include_unapproved: bool,
+}
+
+pub(super) fn observations(
+    conn: &Connection,
+    filter: &EbdFilter,
+) -> Result<Vec<Observation>, EbdError> {
+    if filter.end_date < filter.start_date {
+        return Err(EbdError::InvalidQuery(
+            "end_date is before start_date".into(),
+        ));
+    }
+
+    let mut sql = String::from(
+        "SELECT common_name, scientific_name, locality, observation_date, time_started,
+                observation_count, locality_id, latitude, longitude, checklist_id
+         FROM observations
+         WHERE observation_date BETWEEN ?1 AND ?2",
+    );
+    let start = filter.start_date.to_string();
+    let end = filter.end_date.to_string();
+    let mut params: Vec<&dyn ToSql> = vec![&start, &end];
+
+    if let Some(region) = &filter.region_code {
+        sql.push_str(&format!(
+            " AND {} = ?{}",
+            region_column(region)?,
+            params.len() + 1
+        ));
+        params.push(region);
+    }
+    if let Some(species) = &filter.species {
+        let n = params.len() + 1;
+        sql.push_str(&format!(
+            " AND (common_name = ?{n} COLLATE NOCASE OR scientific_name = ?{n} COLLATE NOCASE)"
+        ));
+        params.push(species);
+    }
+    if !filter.include_unapproved {
+        sql.push_str(" AND approved = 1");
+    }
+    let limit = filter.limit as i64;
+    sql.push_str(&format!(
+        " ORDER BY observation_date DESC, time_started DESC LIMIT ?{}",
+        params.len() + 1
+    ));
+    params.push(&limit);
+
+    let mut statement = conn.prepare(&sql)?;
+    let rows = statement.query_map(params.as_slice(), |row| {
+        let date: String = row.get(3)?;
+        let time: Option<String> = row.get(4)?;
+        let observation_date = match time {
+            Some(time) => format!("{} {}", date, time.get(..5).unwrap_or(&time)),
+            None => date,
+        };
+        Ok(Observation {
+            common_name: row.get(0)?,
+            scientific_name: row.get(1)?,
+            location: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+            observation_date,
+            count: row.get(5)?,
+            location_id: row.get(6)?,
+            latitude: row.get(7)?,
+            longitude: row.get(8)?,
+            species_code: None,
+            submission_id: row.get(9)?,
+            details: Default::default(),
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::fixture_store;
+    use super::*;
+
+    fn filter(start: &str, end: &str) -> EbdFilter {
+        EbdFilter {
+            region_code: None,
+            species: None,
+            start_date: start.parse().unwrap(),
+            end_date: end.parse().unwrap(),
+            limit: 100,
+            include_unapproved: false,
+        }
+    }
+
+    #[test]
+    fn filters_by_date_range_and_skips_unapproved() {
+        let (store, dir) = fixture_store("dates");
+        let results = store.query(&filter("2023-05-01", "2023-05-31")).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(
+            results
+                .iter()
+                .all(|o| o.observation_date.starts_with("2023-05-15"))
+        );
+
+        let mut with_unapproved = filter("2023-05-01", "2023-05-31");
+        with_unapproved.include_unapproved = true;
+        assert_eq!(store.query(&with_unapproved).unwrap().len(), 3);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn filters_by_region_and_species() {
+        let (store, dir) = fixture_store("region");
+        let mut f = filter("2023-01-01", "2023-12-31");
+        f.region_code = Some("US-NC-183".into());
+        let results = store.query(&f).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].location, "Lake Johnson");
+        assert_eq!(results[0].observation_date, "2023-06-02");
+
+        f.region_code = Some("L100".into());
+        f.species = Some("numenius PHAEOPUS".into());
+        let results = store.query(&f).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].common_name, "Whimbrel");
+        assert_eq!(results[0].count, None);
+        assert_eq!(results[0].observation_date, "2023-05-15 07:30");
+        assert_eq!(results[0].submission_id.as_deref(), Some("S1"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_inverted_range() {
+        let (store, dir) = fixture_store("inverted");
+        assert!(store.query(&filter("2023-06-01", "2023-05-01")).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn maps_region_codes_to_columns() {
+        assert_eq!(region_column("US").unwrap(), "country_code");
+        assert_eq!(region_column("US-NC").unwrap(), "state_code");
+        assert_eq!(region_column("US-NC-063").unwrap(), "county_code");
+        assert_eq!(region_column("L123456").unwrap(), "locality_id");
+        assert!(region_column("US-NC-063-X").is_err());
+        assert!(region_column("world").is_err());
+    }
+}
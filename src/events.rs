@@ -0,0 +1,227 @@
+//! Clustering of notable sightings into rarity events.
+//!
+//! One chaseable vagrant typically produces a report per checklist, often
+//! dozens over several days from the same few locations. Reports of the
+//! same species are grouped when they are within [`MAX_DISTANCE_KM`] of a
+//! report already in the event and no more than [`MAX_GAP_DAYS`] after its
+//! latest report.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::geo;
+use crate::tools::observations::RareBird;
+
+pub const MAX_DISTANCE_KM: f64 = 10.0;
+pub const MAX_GAP_DAYS: i64 = 3;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_id: Option<String>,
+    pub name: String,
+    pub reports: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RarityEvent {
+    pub common_name: String,
+    pub scientific_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_code: Option<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub report_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lng: Option<f64>,
+    pub locations: Vec<EventLocation>,
+}
+
+struct Cluster {
+    reports: Vec<(NaiveDateTime, RareBird)>,
+    points: Vec<(f64, f64)>,
+    last_seen: NaiveDateTime,
+}
+
+impl Cluster {
+    fn accepts(&self, time: NaiveDateTime, report: &RareBird) -> bool {
+        if (time - self.last_seen).num_days() > MAX_GAP_DAYS {
+            return false;
+        }
+        let same_location = report.location_id.is_some()
+            && self
+                .reports
+                .iter()
+                .any(|(_, r)| r.location_id == report.location_id);
+        let nearby = report.latitude.zip(report.longitude).is_some_and(|point| {
+            self.points
+                .iter()
+                .any(|p| geo::haversine_km(*p, point) <= MAX_DISTANCE_KM)
+        });
+        same_location || nearby
+    }
+
+    fn push(&mut self, time: NaiveDateTime, report: RareBird) {
+        if let Some(point) = report.latitude.zip(report.longitude) {
+            self.points.push(point);
+        }
+        self.last_seen = self.last_seen.max(time);
+        self.reports.push((time, report));
+    }
+
+    fn into_event(self) -> RarityEvent {
+        let centroid = geo::centroid(&self.points);
+        let mut locations: Vec<EventLocation> = Vec::new();
+        for (_, report) in &self.reports {
+            match locations
+                .iter_mut()
+                .find(|l| l.location_id == report.location_id && l.name == report.location)
+            {
+                Some(location) => location.reports += 1,
+                None => locations.push(EventLocation {
+                    location_id: report.location_id.clone(),
+                    name: report.location.clone(),
+                    reports: 1,
+                }),
+            }
+        }
+        locations.sort_by_key(|l| std::cmp::Reverse(l.reports));
+
+        let (_, first) = self.reports.first().expect("clusters are never empty");
+        let (_, last) = self.reports.last().expect("clusters are never empty");
+        RarityEvent {
+            common_name: first.common_name.clone(),
+            scientific_name: first.scientific_name.clone(),
+            species_code: first.species_code.clone(),
+            first_seen: first.observation_date.clone(),
+            last_seen: last.observation_date.clone(),
+            report_count: self.reports.len() as u32,
+            max_count: self.reports.iter().filter_map(|(_, r)| r.count).max(),
+            lat: centroid.map(|c| c.0),
+            lng: centroid.map(|c| c.1),
+            locations,
+        }
+    }
+}
+
+/// Groups reports into events, most recently active first.
+pub fn cluster(reports: Vec<RareBird>) -> Vec<RarityEvent> {
+    let mut by_species: BTreeMap<String, Vec<(NaiveDateTime, RareBird)>> = BTreeMap::new();
+    for report in reports {
+        let key = report
+            .species_code
+            .clone()
+            .unwrap_or_else(|| report.scientific_name.clone());
+        by_species
+            .entry(key)
+            .or_default()
+            .push((report.observed_at().unwrap_or_default(), report));
+    }
+
+    let mut events = Vec::new();
+    for (_, mut reports) in by_species {
+        reports.sort_by_key(|(time, _)| *time);
+        let mut clusters: Vec<Cluster> = Vec::new();
+        for (time, report) in reports {
+            match clusters.iter_mut().find(|c| c.accepts(time, &report)) {
+                Some(cluster) => cluster.push(time, report),
+                None => {
+                    let mut cluster = Cluster {
+                        reports: Vec::new(),
+                        points: Vec::new(),
+                        last_seen: time,
+                    };
+                    cluster.push(time, report);
+                    clusters.push(cluster);
+                }
+            }
+        }
+        events.extend(clusters.into_iter().map(|c| (c.last_seen, c.into_event())));
+    }
+    events.sort_by(|a, b| {
+        b.0.cmp(&a.0)
+            .then_with(|| a.1.common_name.cmp(&b.1.common_name))
+    });
+    events.into_iter().map(|(_, event)| event).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(
+        name: &str,
+        loc: &str,
+        point: (f64, f64),
+        date: &str,
+        count: Option<u32>,
+    ) -> RareBird {
+        RareBird {
+            scientific_name: format!("{} sp.", name),
+            count,
+            location_id: Some(loc.into()),
+            latitude: Some(point.0),
+            longitude: Some(point.1),
+            ..RareBird::test(name, loc, date)
+        }
+    }
+
+    #[test]
+    fn merges_repeat_reports_of_one_bird() {
+        let events = cluster(vec![
+            report(
+                "Ross's Gull",
+                "L1",
+                (35.00, -76.00),
+                "2023-01-02 09:00",
+                Some(1),
+            ),
+            report(
+                "Ross's Gull",
+                "L1",
+                (35.00, -76.00),
+                "2023-01-01 15:30",
+                None,
+            ),
+            report(
+                "Ross's Gull",
+                "L2",
+                (35.03, -76.02),
+                "2023-01-03 08:00",
+                Some(2),
+            ),
+        ]);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.report_count, 3);
+        assert_eq!(event.first_seen, "2023-01-01 15:30");
+        assert_eq!(event.last_seen, "2023-01-03 08:00");
+        assert_eq!(event.max_count, Some(2));
+        assert_eq!(event.locations.len(), 2);
+        assert_eq!(event.locations[0].name, "L1");
+        assert_eq!(event.locations[0].reports, 2);
+        assert!((event.lat.unwrap() - 35.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn splits_distant_or_stale_reports() {
+        let events = cluster(vec![
+            report("Ross's Gull", "L1", (35.0, -76.0), "2023-01-01 09:00", None),
+            // Same species, 100+ km away.
+            report("Ross's Gull", "L9", (36.0, -77.0), "2023-01-01 10:00", None),
+            // Same place, but a week later.
+            report("Ross's Gull", "L1", (35.0, -76.0), "2023-01-09 09:00", None),
+            report("Ivory Gull", "L1", (35.0, -76.0), "2023-01-01 09:00", None),
+        ]);
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].last_seen, "2023-01-09 09:00");
+    }
+}
@@ -0,0 +1,40 @@
+use serde_json::{Value, json};
+
+use super::{GeoFeature, RoutePoint};
+
+pub(super) fn features<T: GeoFeature>(features: &[&T]) -> String {
+    let features: Vec<Value> = features
+        .iter()
+        .filter_map(|f| {
+            let (lat, lng) = f.coordinates()?;
+            Some(json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [lng, lat] },
+                "properties": f.properties(),
+            }))
+        })
+        .collect();
+    collection(features)
+}
+
+/// Emits the route as a `LineString` followed by one point per stop.
+pub(super) fn route(title: &str, points: &[RoutePoint]) -> String {
+    let line: Vec<[f64; 2]> = points.iter().map(|p| [p.lng, p.lat]).collect();
+    let mut features = vec![json!({
+        "type": "Feature",
+        "geometry": { "type": "LineString", "coordinates": line },
+        "properties": { "name": title },
+    })];
+    features.extend(points.iter().enumerate().map(|(i, p)| {
+        json!({
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [p.lng, p.lat] },
+            "properties": { "name": p.name, "sequence": i + 1, "notes": p.notes },
+        })
+    }));
+    collection(features)
+}
+
+fn collection(features: Vec<Value>) -> String {
+    json!({ "type": "FeatureCollection", "features": features }).to_string()
+}
@@ -0,0 +1,54 @@
+use std::fmt::Write;
+
+use super::{GeoFeature, RoutePoint, display_value, escape_xml};
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="rubl" xmlns="http://www.topografix.com/GPX/1/1">
+"#;
+
+/// Emits one `<wpt>` per feature; properties go in `<desc>` as `key: value` lines.
+pub(super) fn waypoints<T: GeoFeature>(title: &str, features: &[&T]) -> String {
+    let mut out = String::from(HEADER);
+    let _ = writeln!(
+        out,
+        "  <metadata><name>{}</name></metadata>",
+        escape_xml(title)
+    );
+    for feature in features {
+        let Some((lat, lng)) = feature.coordinates() else {
+            continue;
+        };
+        let desc = feature
+            .properties()
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, display_value(v)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let _ = writeln!(out, r#"  <wpt lat="{}" lon="{}">"#, lat, lng);
+        let _ = writeln!(out, "    <name>{}</name>", escape_xml(feature.name()));
+        let _ = writeln!(out, "    <desc>{}</desc>", escape_xml(&desc));
+        out.push_str("  </wpt>\n");
+    }
+    out.push_str("</gpx>\n");
+    out
+}
+
+pub(super) fn route(title: &str, points: &[RoutePoint]) -> String {
+    let mut out = String::from(HEADER);
+    out.push_str("  <rte>\n");
+    let _ = writeln!(out, "    <name>{}</name>", escape_xml(title));
+    for point in points {
+        let _ = writeln!(
+            out,
+            r#"    <rtept lat="{}" lon="{}">"#,
+            point.lat, point.lng
+        );
+        let _ = writeln!(out, "      <name>{}</name>", escape_xml(&point.name));
+        if let Some(notes) = &point.notes {
+            let _ = writeln!(out, "      <desc>{}</desc>", escape_xml(notes));
+        }
+        out.push_str("    </rtept>\n");
+    }
+    out.push_str("  </rte>\n</gpx>\n");
+    out
+}
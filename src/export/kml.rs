@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+use super::{GeoFeature, RoutePoint, display_value, escape_xml};
+
+fn open(title: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kml xmlns=\"http://www.opengis.net/kml/2.2\">\n\
+         <Document>\n  <name>{}</name>\n",
+        escape_xml(title)
+    )
+}
+
+const CLOSE: &str = "</Document>\n</kml>\n";
+
+/// Emits one `<Placemark>` per feature with properties as `<ExtendedData>`.
+pub(super) fn placemarks<T: GeoFeature>(title: &str, features: &[&T]) -> String {
+    let mut out = open(title);
+    for feature in features {
+        let Some((lat, lng)) = feature.coordinates() else {
+            continue;
+        };
+        out.push_str("  <Placemark>\n");
+        let _ = writeln!(out, "    <name>{}</name>", escape_xml(feature.name()));
+        out.push_str("    <ExtendedData>\n");
+        for (key, value) in feature.properties() {
+            let _ = writeln!(
+                out,
+                r#"      <Data name="{}"><value>{}</value></Data>"#,
+                escape_xml(&key),
+                escape_xml(&display_value(&value))
+            );
+        }
+        out.push_str("    </ExtendedData>\n");
+        let _ = writeln!(
+            out,
+            "    <Point><coordinates>{},{}</coordinates></Point>",
+            lng, lat
+        );
+        out.push_str("  </Placemark>\n");
+    }
+    out.push_str(CLOSE);
+    out
+}
+
+/// Emits the route as a `LineString` placemark followed by one placemark per stop.
+pub(super) fn route(title: &str, points: &[RoutePoint]) -> String {
+    let mut out = open(title);
+    let coordinates = points
+        .iter()
+        .map(|p| format!("{},{}", p.lng, p.lat))
+        .collect::<Vec<_>>()
+        .join(" ");
+    out.push_str("  <Placemark>\n");
+    let _ = writeln!(out, "    <name>{}</name>", escape_xml(title));
+    let _ = writeln!(
+        out,
+        "    <LineString><coordinates>{}</coordinates></LineString>",
+        coordinates
+    );
+    out.push_str("  </Placemark>\n");
+    for point in points {
+        out.push_str("  <Placemark>\n");
+        let _ = writeln!(out, "    <name>{}</name>", escape_xml(&point.name));
+        if let Some(notes) = &point.notes {
+            let _ = writeln!(out, "    <description>{}</description>", escape_xml(notes));
+        }
+        let _ = writeln!(
+            out,
+            "    <Point><coordinates>{},{}</coordinates></Point>",
+            point.lng, point.lat
+        );
+        out.push_str("  </Placemark>\n");
+    }
+    out.push_str(CLOSE);
+    out
+}
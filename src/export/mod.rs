@@ -0,0 +1,338 @@
+//! Geospatial exports of eBird results.
+//!
+//! Hotspots and observations can be rendered as GeoJSON, GPX, or KML so they
+//! can be loaded into GIS tools and GPS units. Exports are returned to MCP
+//! clients as embedded resources rather than TOON text.
+
+mod geojson;
+mod gpx;
+mod kml;
+
+use rmcp::model::{Content, ResourceContents};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::tools::hotspot::Hotspot;
+use crate::tools::observations::Observation;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    GeoJson,
+    Gpx,
+    Kml,
+}
+
+impl ExportFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::GeoJson => "application/geo+json",
+            ExportFormat::Gpx => "application/gpx+xml",
+            ExportFormat::Kml => "application/vnd.google-earth.kml+xml",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::GeoJson => "geojson",
+            ExportFormat::Gpx => "gpx",
+            ExportFormat::Kml => "kml",
+        }
+    }
+}
+
+/// A record that can be placed on a map.
+///
+/// Properties are taken from the record's serialized fields, minus the
+/// coordinates, so exported attributes use the same names as eBird.
+pub trait GeoFeature {
+    fn name(&self) -> &str;
+
+    /// Returns `(latitude, longitude)`, or `None` if the record has no position.
+    fn coordinates(&self) -> Option<(f64, f64)>;
+
+    fn properties(&self) -> Map<String, Value>;
+}
+
+/// Serializes `value` to a JSON object and strips the coordinate fields.
+fn properties_of<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(mut map)) => {
+            map.remove("lat");
+            map.remove("lng");
+            map.retain(|_, v| !v.is_null());
+            map
+        }
+        _ => Map::new(),
+    }
+}
+
+impl GeoFeature for Hotspot {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((self.latitude, self.longitude))
+    }
+
+    fn properties(&self) -> Map<String, Value> {
+        properties_of(self)
+    }
+}
+
+impl GeoFeature for Observation {
+    fn name(&self) -> &str {
+        &self.common_name
+    }
+
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        self.latitude.zip(self.longitude)
+    }
+
+    fn properties(&self) -> Map<String, Value> {
+        properties_of(self)
+    }
+}
+
+/// A stop on an itinerary, exported in order as a route.
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RoutePoint {
+    #[schemars(description = "Stop name (e.g., hotspot name)")]
+    pub name: String,
+    #[schemars(description = "Latitude")]
+    pub lat: f64,
+    #[schemars(description = "Longitude")]
+    pub lng: f64,
+    #[schemars(description = "Optional notes for this stop (targets, timing, parking)")]
+    pub notes: Option<String>,
+}
+
+/// Renders point features in the requested format.
+///
+/// Records without coordinates are skipped.
+pub fn export_features<T: GeoFeature>(title: &str, features: &[T], format: ExportFormat) -> String {
+    let features: Vec<&T> = features
+        .iter()
+        .filter(|f| f.coordinates().is_some())
+        .collect();
+    match format {
+        ExportFormat::GeoJson => geojson::features(&features),
+        ExportFormat::Gpx => gpx::waypoints(title, &features),
+        ExportFormat::Kml => kml::placemarks(title, &features),
+    }
+}
+
+/// Renders an ordered list of stops as a route in the requested format.
+pub fn export_route(title: &str, points: &[RoutePoint], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::GeoJson => geojson::route(title, points),
+        ExportFormat::Gpx => gpx::route(title, points),
+        ExportFormat::Kml => kml::route(title, points),
+    }
+}
+
+/// Wraps an export as an embedded MCP resource.
+pub fn to_resource(slug: &str, body: String, format: ExportFormat) -> Content {
+    Content::resource(ResourceContents::TextResourceContents {
+        uri: format!("rubl://export/{}.{}", slug, format.extension()),
+        mime_type: Some(format.mime_type().into()),
+        text: body,
+        meta: None,
+    })
+}
+
+/// Renders a property value for the text-only formats (GPX, KML).
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotspot() -> Hotspot {
+        Hotspot {
+            location_id: "L123456".into(),
+            name: "Mason Farm & Bottomlands".into(),
+            latitude: 35.8893,
+            longitude: -79.0176,
+            country_code: "US".into(),
+            country_name: None,
+            subnational1_code: "US-NC".into(),
+            subnational1_name: None,
+            subnational2_code: Some("US-NC-135".into()),
+            subnational2_name: None,
+            is_hotspot: Some(true),
+            hierarchical_name: None,
+            latest_observation_date: Some("2023-05-15 07:12".into()),
+            num_species_all_time: Some(215),
+            num_checklists_all_time: None,
+        }
+    }
+
+    fn observation(latitude: Option<f64>, longitude: Option<f64>) -> Observation {
+        Observation {
+            scientific_name: "Hirundo rustica".into(),
+            count: Some(3),
+            location_id: Some("L123456".into()),
+            latitude,
+            longitude,
+            species_code: Some("barswa".into()),
+            submission_id: Some("S123456789".into()),
+            ..Observation::test("Barn Swallow", "My Backyard", "2023-05-15 10:30")
+        }
+    }
+
+    fn route() -> Vec<RoutePoint> {
+        vec![
+            RoutePoint {
+                name: "Jordan Lake".into(),
+                lat: 35.7346,
+                lng: -79.0151,
+                notes: Some("Dawn: Prothonotary Warbler".into()),
+            },
+            RoutePoint {
+                name: "Mason Farm".into(),
+                lat: 35.8893,
+                lng: -79.0176,
+                notes: None,
+            },
+        ]
+    }
+
+    mod properties {
+        use super::*;
+
+        #[test]
+        fn strips_coordinates_and_nulls() {
+            let props = hotspot().properties();
+            assert_eq!(props["locId"], "L123456");
+            assert_eq!(props["numSpeciesAllTime"], 215);
+            assert!(!props.contains_key("lat"));
+            assert!(!props.contains_key("lng"));
+            assert!(!props.contains_key("countryName"));
+        }
+
+        #[test]
+        fn observation_without_coordinates_has_none() {
+            assert!(observation(None, None).coordinates().is_none());
+            assert!(observation(Some(35.0), None).coordinates().is_none());
+        }
+    }
+
+    mod geojson {
+        use super::*;
+
+        #[test]
+        fn features_use_lng_lat_order() {
+            let body = export_features("Hotspots", &[hotspot()], ExportFormat::GeoJson);
+            let json: Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(json["type"], "FeatureCollection");
+            let feature = &json["features"][0];
+            assert_eq!(feature["geometry"]["type"], "Point");
+            assert_eq!(feature["geometry"]["coordinates"][0], -79.0176);
+            assert_eq!(feature["geometry"]["coordinates"][1], 35.8893);
+            assert_eq!(feature["properties"]["locName"], "Mason Farm & Bottomlands");
+        }
+
+        #[test]
+        fn skips_records_without_coordinates() {
+            let observations = vec![
+                observation(None, None),
+                observation(Some(35.9), Some(-79.0)),
+            ];
+            let body = export_features("Observations", &observations, ExportFormat::GeoJson);
+            let json: Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(json["features"].as_array().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn route_is_line_string_with_stops() {
+            let body = export_route("Saturday", &route(), ExportFormat::GeoJson);
+            let json: Value = serde_json::from_str(&body).unwrap();
+            let features = json["features"].as_array().unwrap();
+            assert_eq!(features.len(), 3);
+            assert_eq!(features[0]["geometry"]["type"], "LineString");
+            assert_eq!(features[0]["geometry"]["coordinates"][1][0], -79.0176);
+            assert_eq!(features[1]["properties"]["sequence"], 1);
+        }
+    }
+
+    mod gpx {
+        use super::*;
+
+        #[test]
+        fn waypoints_escape_names() {
+            let body = export_features("Hotspots", &[hotspot()], ExportFormat::Gpx);
+            assert!(body.contains(r#"<wpt lat="35.8893" lon="-79.0176">"#));
+            assert!(body.contains("<name>Mason Farm &amp; Bottomlands</name>"));
+            assert!(body.contains("locId: L123456"));
+        }
+
+        #[test]
+        fn itinerary_exports_as_route() {
+            let body = export_route("Saturday", &route(), ExportFormat::Gpx);
+            assert!(body.contains("<rte>"));
+            assert_eq!(body.matches("<rtept ").count(), 2);
+            assert!(body.contains("<desc>Dawn: Prothonotary Warbler</desc>"));
+            assert!(!body.contains("<wpt "));
+        }
+    }
+
+    mod kml {
+        use super::*;
+
+        #[test]
+        fn placemarks_carry_extended_data() {
+            let body = export_features("Hotspots", &[hotspot()], ExportFormat::Kml);
+            assert!(body.contains("<coordinates>-79.0176,35.8893</coordinates>"));
+            assert!(body.contains(r#"<Data name="locId"><value>L123456</value></Data>"#));
+        }
+
+        #[test]
+        fn route_is_line_string() {
+            let body = export_route("Saturday", &route(), ExportFormat::Kml);
+            assert!(body.contains("<LineString>"));
+            assert!(body.contains("-79.0151,35.7346 -79.0176,35.8893"));
+        }
+    }
+
+    mod resource {
+        use super::*;
+
+        #[test]
+        fn uses_format_mime_type_and_extension() {
+            let content = to_resource("hotspots/US-NC", "{}".into(), ExportFormat::GeoJson);
+            let resource = content.as_resource().unwrap();
+            match &resource.resource {
+                ResourceContents::TextResourceContents { uri, mime_type, .. } => {
+                    assert_eq!(uri, "rubl://export/hotspots/US-NC.geojson");
+                    assert_eq!(mime_type.as_deref(), Some("application/geo+json"));
+                }
+                other => panic!("unexpected resource contents: {:?}", other),
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use super::{Tally, Taxon, week_of};
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::cache::Cache;
+use crate::ids::RegionCode;
+use crate::tools::checklist::{
+    ChecklistFeedQuery, FetchChecklistFeedRequest, FetchChecklistRequest,
+};
+
+/// Checklists are rarely edited after the fact.
+const CHECKLIST_TTL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Treats each complete checklist on a sampled day as one sample.
+pub(super) async fn tally(
+    client: &ApiClient,
+    cache: &Cache,
+    region_code: &RegionCode,
+    dates: &[NaiveDate],
+    per_day: u32,
+) -> Result<Tally, ApiError> {
+    let mut tally = Tally::default();
+    for date in dates {
+        let feed = FetchChecklistFeedRequest {
+            region_code: region_code.clone(),
+            date: (*date).into(),
+            query: ChecklistFeedQuery {
+                max_results: Some(per_day.max(1) * 4),
+            },
+        };
+        let summaries = cache.fetch(client, &feed, cache.ttls.historic).await?;
+        let mut read = 0;
+        for summary in summaries {
+            if read >= per_day {
+                break;
+            }
+            let request = FetchChecklistRequest {
+                sub_id: summary.sub_id.parse()?,
+            };
+            let checklist = cache.fetch(client, &request, CHECKLIST_TTL).await?;
+            // Incidental checklists say nothing about what was missed.
+            if !checklist.all_obs_reported {
+                continue;
+            }
+            read += 1;
+            tally.record_sample(
+                week_of(*date),
+                checklist.obs.into_iter().map(|obs| Taxon {
+                    species_code: Some(obs.species_code),
+                    ..Default::default()
+                }),
+            );
+        }
+    }
+    Ok(tally)
+}
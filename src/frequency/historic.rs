@@ -0,0 +1,22 @@
+use chrono::NaiveDate;
+
+use super::{Tally, Taxon, week_of};
+use crate::api::error::ApiError;
+use crate::historic::{DEFAULT_CONCURRENCY, HistoricFetcher};
+use crate::ids::RegionCode;
+
+/// Treats each sampled day as one sample of the species reported that day.
+pub(super) async fn tally(
+    fetcher: &HistoricFetcher,
+    region_code: &RegionCode,
+    dates: Vec<NaiveDate>,
+) -> Result<Tally, ApiError> {
+    let mut tally = Tally::default();
+    let days = fetcher
+        .fetch_days(region_code, dates, DEFAULT_CONCURRENCY, |_, _| {})
+        .await?;
+    for day in days {
+        tally.record_sample(week_of(day.date), day.observations.iter().map(Taxon::from));
+    }
+    Ok(tally)
+}
@@ -0,0 +1,580 @@
+//! Seasonal occurrence frequencies in eBird's 48-week bar-chart layout.
+//!
+//! Every month is split into four buckets (days 1-7, 8-14, 15-21 and 22 to
+//! month end). A species' frequency in a bucket is the share of samples it
+//! was reported on. What counts as a sample depends on the source:
+//!
+//! - [`FrequencySource::Historic`] samples days through the historic
+//!   observations endpoint, so frequency is the share of sampled days with
+//!   any report in the region. Works best for hotspots and counties.
+//! - [`FrequencySource::Checklists`] samples complete checklists from the
+//!   daily checklist feed, matching eBird's own definition.
+//! - [`FrequencySource::Ebd`] uses every complete checklist in the offline
+//!   EBD store and needs no network.
+//!
+//! API-built tables and the responses behind them are cached on disk.
+
+mod checklists;
+mod historic;
+
+use std::collections::{BTreeMap, HashSet};
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::api::error::ApiError;
+use crate::ebd::{EbdError, EbdStore};
+use crate::historic::HistoricFetcher;
+use crate::ids::RegionCode;
+use crate::tools::observations::Observation;
+
+pub const WEEKS: usize = 48;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Upper bound on API calls a single table may need before it is rejected.
+const MAX_REQUESTS: usize = 1500;
+
+#[derive(Debug, Error)]
+pub enum FrequencyError {
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    #[error(transparent)]
+    Ebd(#[from] EbdError),
+
+    #[error(
+        "This table would need about {0} eBird API calls; narrow it with month, week, or fewer years"
+    )]
+    TooManyRequests(usize),
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum FrequencySource {
+    #[default]
+    Historic,
+    Checklists,
+    Ebd,
+}
+
+/// Returns the bar-chart bucket (0-47) a date falls in.
+pub fn week_of(date: NaiveDate) -> usize {
+    let quarter = ((date.day0() / 7) as usize).min(3);
+    date.month0() as usize * 4 + quarter
+}
+
+/// Human-readable label for a bucket, e.g. "May 8-14".
+pub fn week_label(week: usize) -> String {
+    let (month, quarter) = (week / 4, week % 4);
+    let start = quarter * 7 + 1;
+    if quarter == 3 {
+        format!("{} {}-end", MONTHS[month], start)
+    } else {
+        format!("{} {}-{}", MONTHS[month], start, start + 6)
+    }
+}
+
+/// Picks `count` evenly spaced days inside a bucket of a given year.
+pub fn sample_days(year: i32, week: usize, count: u32) -> Vec<NaiveDate> {
+    let month = (week / 4) as u32 + 1;
+    let first = (week % 4) as u32 * 7 + 1;
+    let last = if week % 4 == 3 {
+        days_in_month(year, month)
+    } else {
+        first + 6
+    };
+    let len = last - first + 1;
+    let count = count.clamp(1, len);
+    (0..count)
+        .filter_map(|k| {
+            NaiveDate::from_ymd_opt(year, month, first + len * (2 * k + 1) / (2 * count))
+        })
+        .collect()
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+    next.and_then(|d| d.pred_opt()).map_or(28, |d| d.day())
+}
+
+/// A species as identified by whichever source reported it.
+#[derive(Debug, Clone, Default)]
+pub struct Taxon {
+    pub species_code: Option<String>,
+    pub common_name: Option<String>,
+    pub scientific_name: Option<String>,
+}
+
+impl Taxon {
+    fn key(&self) -> String {
+        self.species_code
+            .as_deref()
+            .or(self.scientific_name.as_deref())
+            .or(self.common_name.as_deref())
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+}
+
+impl From<&Observation> for Taxon {
+    fn from(obs: &Observation) -> Self {
+        Self {
+            species_code: obs.species_code.clone(),
+            common_name: Some(obs.common_name.clone()),
+            scientific_name: Some(obs.scientific_name.clone()),
+        }
+    }
+}
+
+struct SpeciesTally {
+    taxon: Taxon,
+    detections: [u32; WEEKS],
+}
+
+/// Running sample and detection counts per bucket.
+pub struct Tally {
+    samples: [u32; WEEKS],
+    species: BTreeMap<String, SpeciesTally>,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self {
+            samples: [0; WEEKS],
+            species: BTreeMap::new(),
+        }
+    }
+}
+
+impl Tally {
+    /// Records one sample (a day or a checklist) and the species on it.
+    pub fn record_sample(&mut self, week: usize, taxa: impl IntoIterator<Item = Taxon>) {
+        self.samples[week] += 1;
+        let mut seen = HashSet::new();
+        for taxon in taxa {
+            let key = taxon.key();
+            if key.is_empty() || !seen.insert(key.clone()) {
+                continue;
+            }
+            self.species
+                .entry(key)
+                .or_insert_with(|| SpeciesTally {
+                    taxon,
+                    detections: [0; WEEKS],
+                })
+                .detections[week] += 1;
+        }
+    }
+
+    pub fn into_table(self) -> FrequencyTable {
+        let samples = self.samples;
+        let species = self
+            .species
+            .into_values()
+            .map(|tally| SpeciesFrequency {
+                species_code: tally.taxon.species_code,
+                common_name: tally.taxon.common_name,
+                scientific_name: tally.taxon.scientific_name,
+                frequencies: tally
+                    .detections
+                    .iter()
+                    .zip(samples)
+                    .map(|(&hits, n)| {
+                        if n == 0 {
+                            0.0
+                        } else {
+                            (hits as f64 / n as f64 * 1000.0).round() / 1000.0
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        FrequencyTable {
+            samples: samples.to_vec(),
+            species,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesFrequency {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub common_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scientific_name: Option<String>,
+    pub frequencies: Vec<f64>,
+}
+
+impl SpeciesFrequency {
+    fn matches(&self, name: &str) -> bool {
+        [&self.species_code, &self.common_name, &self.scientific_name]
+            .into_iter()
+            .flatten()
+            .any(|value| value.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Frequencies for all 48 buckets; buckets with no samples read as zero.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrequencyTable {
+    pub samples: Vec<u32>,
+    pub species: Vec<SpeciesFrequency>,
+}
+
+/// What to build a table from and for which part of the year.
+#[derive(Debug, Clone)]
+pub struct FrequencyQuery {
+    /// Region or hotspot (location ID) code.
+    pub region_code: RegionCode,
+    pub source: FrequencySource,
+    /// Years to draw samples from; `None` (EBD only) means all years.
+    pub years: Option<RangeInclusive<i32>>,
+    pub weeks: RangeInclusive<usize>,
+    /// Days sampled per bucket per year (API sources).
+    pub days_per_week: u32,
+    /// Checklists read per sampled day (checklist source).
+    pub checklists_per_day: u32,
+}
+
+impl FrequencyQuery {
+    fn cache_key(&self) -> String {
+        let years = self
+            .years
+            .as_ref()
+            .map_or("all".into(), |y| format!("{}-{}", y.start(), y.end()));
+        format!(
+            "frequency/{:?}/{}/{}/{}-{}/{}/{}",
+            self.source,
+            self.region_code,
+            years,
+            self.weeks.start(),
+            self.weeks.end(),
+            self.days_per_week,
+            self.checklists_per_day
+        )
+    }
+
+    /// Upper bound on the eBird calls an API-built table needs, computed
+    /// without building the sample dates.
+    fn request_estimate(&self) -> usize {
+        let years = self.years.as_ref().map_or(0, |y| {
+            (*y.end() as i64 - *y.start() as i64 + 1).max(0) as usize
+        });
+        let dates = years
+            .saturating_mul(self.weeks.clone().count())
+            .saturating_mul(self.days_per_week as usize);
+        match self.source {
+            FrequencySource::Checklists => {
+                dates.saturating_mul(1 + self.checklists_per_day as usize)
+            }
+            FrequencySource::Historic | FrequencySource::Ebd => dates,
+        }
+    }
+
+    /// Sampled dates, skipping any that have not happened yet.
+    fn sample_dates(&self, today: NaiveDate) -> Vec<NaiveDate> {
+        let Some(years) = &self.years else {
+            return Vec::new();
+        };
+        years
+            .clone()
+            .flat_map(|year| {
+                self.weeks
+                    .clone()
+                    .flat_map(move |week| sample_days(year, week, self.days_per_week))
+            })
+            .filter(|date| *date < today)
+            .collect()
+    }
+}
+
+/// Builds (or loads from cache) a frequency table.
+pub async fn build(
+    fetcher: &HistoricFetcher,
+    query: &FrequencyQuery,
+) -> Result<FrequencyTable, FrequencyError> {
+    if query.source == FrequencySource::Ebd {
+        // Local and cheap to recompute; caching would go stale on re-import.
+        let store = EbdStore::open_default()?;
+        let query = query.clone();
+        let tally = tokio::task::spawn_blocking(move || {
+            store.frequencies(query.region_code.as_str(), query.years, query.weeks)
+        })
+        .await
+        .map_err(|e| EbdError::Io(std::io::Error::other(e)))??;
+        return Ok(tally.into_table());
+    }
+
+    let key = query.cache_key();
+    let cache = &fetcher.cache;
+    if let Some(table) = cache.get(&key, cache.ttls.derived) {
+        return Ok(table);
+    }
+
+    let calls = query.request_estimate();
+    if calls > MAX_REQUESTS {
+        return Err(FrequencyError::TooManyRequests(calls));
+    }
+    let dates = query.sample_dates(chrono::Utc::now().date_naive());
+    let tally = match query.source {
+        FrequencySource::Historic => historic::tally(fetcher, &query.region_code, dates).await?,
+        FrequencySource::Checklists => {
+            checklists::tally(
+                &fetcher.client,
+                cache,
+                &query.region_code,
+                &dates,
+                query.checklists_per_day,
+            )
+            .await?
+        }
+        FrequencySource::Ebd => unreachable!("handled above"),
+    };
+    let table = tally.into_table();
+    cache.put(&key, &table);
+    Ok(table)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesRow {
+    pub species: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scientific_name: Option<String>,
+    pub frequencies: Vec<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrequencyReport {
+    pub region_code: RegionCode,
+    pub source: FrequencySource,
+    pub years: String,
+    pub weeks: Vec<String>,
+    pub samples: Vec<u32>,
+    pub species: Vec<SpeciesRow>,
+}
+
+/// How to slice a table for display.
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub species: Option<String>,
+    pub min_frequency: f64,
+    pub limit: usize,
+}
+
+impl FrequencyTable {
+    /// Restricts the table to the queried buckets, most frequent species first.
+    pub fn report(&self, query: &FrequencyQuery, options: &ReportOptions) -> FrequencyReport {
+        let weeks = query.weeks.clone();
+        let mut rows: Vec<(f64, SpeciesRow)> = self
+            .species
+            .iter()
+            .filter(|s| {
+                options
+                    .species
+                    .as_deref()
+                    .is_none_or(|name| s.matches(name))
+            })
+            .filter_map(|s| {
+                let frequencies = s.frequencies[weeks.clone()].to_vec();
+                let peak = frequencies.iter().copied().fold(0.0, f64::max);
+                let mean = frequencies.iter().sum::<f64>() / frequencies.len() as f64;
+                (peak > 0.0 && peak >= options.min_frequency).then(|| {
+                    let species = s
+                        .common_name
+                        .clone()
+                        .or_else(|| s.species_code.clone())
+                        .or_else(|| s.scientific_name.clone())
+                        .unwrap_or_default();
+                    (
+                        mean,
+                        SpeciesRow {
+                            species,
+                            scientific_name: s.scientific_name.clone(),
+                            frequencies,
+                        },
+                    )
+                })
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| a.1.species.cmp(&b.1.species))
+        });
+        rows.truncate(options.limit);
+
+        FrequencyReport {
+            region_code: query.region_code.clone(),
+            source: query.source,
+            years: query
+                .years
+                .as_ref()
+                .map_or("all".into(), |y| format!("{}-{}", y.start(), y.end())),
+            weeks: weeks.clone().map(week_label).collect(),
+            samples: self.samples[weeks].to_vec(),
+            species: rows.into_iter().map(|(_, row)| row).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn taxon(name: &str) -> Taxon {
+        Taxon {
+            common_name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    mod week_of {
+        use super::*;
+
+        #[test]
+        fn maps_dates_to_quarter_month_buckets() {
+            assert_eq!(week_of(date("2023-01-01")), 0);
+            assert_eq!(week_of(date("2023-01-07")), 0);
+            assert_eq!(week_of(date("2023-01-08")), 1);
+            assert_eq!(week_of(date("2023-05-08")), 17);
+            assert_eq!(week_of(date("2024-02-29")), 7);
+            assert_eq!(week_of(date("2023-12-31")), 47);
+        }
+
+        #[test]
+        fn labels_buckets() {
+            assert_eq!(week_label(0), "Jan 1-7");
+            assert_eq!(week_label(17), "May 8-14");
+            assert_eq!(week_label(47), "Dec 22-end");
+        }
+    }
+
+    mod sample_days {
+        use super::*;
+
+        #[test]
+        fn picks_days_inside_the_bucket() {
+            assert_eq!(sample_days(2023, 17, 1), vec![date("2023-05-11")]);
+            let last = sample_days(2023, 7, 7);
+            assert_eq!(last.len(), 7);
+            assert!(last.iter().all(|d| week_of(*d) == 7));
+            assert_eq!(sample_days(2023, 0, 30).len(), 7);
+        }
+
+        #[test]
+        fn skips_future_dates() {
+            let query = FrequencyQuery {
+                region_code: "L1".parse().unwrap(),
+                source: FrequencySource::Historic,
+                years: Some(2023..=2024),
+                weeks: 17..=17,
+                days_per_week: 1,
+                checklists_per_day: 0,
+            };
+            assert_eq!(
+                query.sample_dates(date("2024-01-01")),
+                vec![date("2023-05-11")]
+            );
+        }
+
+        #[test]
+        fn estimates_requests_without_building_dates() {
+            let mut query = FrequencyQuery {
+                region_code: "L1".parse().unwrap(),
+                source: FrequencySource::Checklists,
+                years: Some(2021..=2023),
+                weeks: 0..=47,
+                days_per_week: 2,
+                checklists_per_day: 5,
+            };
+            assert_eq!(query.request_estimate(), 3 * 48 * 2 * 6);
+            query.source = FrequencySource::Historic;
+            query.years = Some(i32::MIN..=i32::MAX);
+            assert!(query.request_estimate() > MAX_REQUESTS);
+        }
+    }
+
+    mod report {
+        use super::*;
+
+        fn table() -> FrequencyTable {
+            let mut tally = Tally::default();
+            tally.record_sample(17, [taxon("Barn Swallow"), taxon("Barn Swallow")]);
+            tally.record_sample(17, [taxon("Barn Swallow"), taxon("Whimbrel")]);
+            tally.record_sample(18, [taxon("Whimbrel")]);
+            tally.record_sample(18, [taxon("Whimbrel")]);
+            tally.record_sample(47, []);
+            tally.into_table()
+        }
+
+        fn query(weeks: RangeInclusive<usize>) -> FrequencyQuery {
+            FrequencyQuery {
+                region_code: "US-NC".parse().unwrap(),
+                source: FrequencySource::Historic,
+                years: Some(2020..=2022),
+                weeks,
+                days_per_week: 1,
+                checklists_per_day: 0,
+            }
+        }
+
+        fn options() -> ReportOptions {
+            ReportOptions {
+                species: None,
+                min_frequency: 0.0,
+                limit: 10,
+            }
+        }
+
+        #[test]
+        fn counts_each_species_once_per_sample() {
+            let table = table();
+            assert_eq!(table.samples[17], 2);
+            let swallow = &table.species[0];
+            assert_eq!(swallow.common_name.as_deref(), Some("Barn Swallow"));
+            assert_eq!(swallow.frequencies[17], 1.0);
+            assert_eq!(table.species[1].frequencies[18], 1.0);
+            assert_eq!(table.samples[47], 1);
+        }
+
+        #[test]
+        fn sorts_selected_weeks_by_mean_frequency() {
+            let report = table().report(&query(17..=18), &options());
+            assert_eq!(report.weeks, vec!["May 8-14", "May 15-21"]);
+            assert_eq!(report.samples, vec![2, 2]);
+            assert_eq!(report.species[0].species, "Whimbrel");
+            assert_eq!(report.species[0].frequencies, vec![0.5, 1.0]);
+            assert_eq!(report.species[1].species, "Barn Swallow");
+        }
+
+        #[test]
+        fn filters_by_species_and_drops_absent_ones() {
+            let mut options = options();
+            options.species = Some("barn swallow".into());
+            let report = table().report(&query(18..=18), &options);
+            assert!(report.species.is_empty());
+
+            let report = table().report(&query(17..=17), &options);
+            assert_eq!(report.species.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Small geographic helpers.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+/// Julian day of 2000-01-01 12:00 UTC.
+const J2000: f64 = 2451545.0;
+/// Julian day of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+/// Great-circle distance between two (lat, lng) points in kilometers.
+pub fn haversine_km(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (lat1, lat2) = (a.0.to_radians(), b.0.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lng = (b.1 - a.1).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
+}
+
+/// Mean of a set of points. Fine for the small areas rarity events span.
+pub fn centroid(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    if points.is_empty() {
+        return None;
+    }
+    let n = points.len() as f64;
+    let (lat, lng) = points
+        .iter()
+        .fold((0.0, 0.0), |(lat, lng), p| (lat + p.0, lng + p.1));
+    Some((lat / n, lng / n))
+}
+
+/// Sunrise and sunset in UTC. Both are `None` during polar day or night.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunTimes {
+    pub sunrise: Option<DateTime<Utc>>,
+    pub sunset: Option<DateTime<Utc>>,
+}
+
+/// The calendar date at a longitude, by mean solar time (15 degrees an
+/// hour). Within an hour or so of the civil date, without a time zone
+/// database.
+pub fn local_date(now: DateTime<Utc>, lng: f64) -> NaiveDate {
+    let offset = chrono::Duration::seconds((lng / 15.0 * 3600.0).round() as i64);
+    (now + offset).date_naive()
+}
+
+fn julian_to_utc(jd: f64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(((jd - UNIX_EPOCH_JD) * 86400.0).round() as i64, 0)
+}
+
+/// Sunrise and sunset for a date at a point, using NOAA's sunrise equation
+/// (accurate to a minute or two away from the poles).
+pub fn sun_times(date: NaiveDate, lat: f64, lng: f64) -> SunTimes {
+    let noon_jd = date
+        .and_hms_opt(12, 0, 0)
+        .expect("noon is a valid time")
+        .and_utc()
+        .timestamp() as f64
+        / 86400.0
+        + UNIX_EPOCH_JD;
+    let day = (noon_jd - J2000).round();
+    let mean_solar_noon = day - lng / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_solar_noon).rem_euclid(360.0);
+    let m = anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let longitude = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000 + mean_solar_noon + 0.0053 * m.sin() - 0.0069 * (2.0 * longitude).sin();
+    let declination = (longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+
+    let phi = lat.to_radians();
+    let cos_hour_angle = ((-0.833_f64).to_radians().sin() - phi.sin() * declination.sin())
+        / (phi.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return SunTimes {
+            sunrise: None,
+            sunset: None,
+        };
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    SunTimes {
+        sunrise: julian_to_utc(transit - half_day),
+        sunset: julian_to_utc(transit + half_day),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn haversine_matches_known_distance() {
+        // Raleigh to Durham, NC is roughly 35 km.
+        let d = haversine_km((35.7796, -78.6382), (35.9940, -78.8986));
+        assert!((d - 33.5).abs() < 1.5, "got {}", d);
+        assert_eq!(haversine_km((10.0, 20.0), (10.0, 20.0)), 0.0);
+    }
+
+    #[test]
+    fn centroid_averages_points() {
+        assert_eq!(centroid(&[(1.0, 2.0), (3.0, 4.0)]), Some((2.0, 3.0)));
+        assert_eq!(centroid(&[]), None);
+    }
+
+    #[test]
+    fn local_date_follows_longitude() {
+        let now: DateTime<Utc> = "2023-06-21T23:30:00Z".parse().unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2023, 6, d).unwrap();
+        assert_eq!(local_date(now, -78.6), day(21));
+        assert_eq!(local_date(now, 151.2), day(22));
+        assert_eq!(local_date(now, 0.0), day(21));
+    }
+
+    #[test]
+    fn sun_times_match_almanac() {
+        // Raleigh, NC on the 2023 summer solstice: sunrise 06:00 EDT,
+        // sunset 20:33 EDT.
+        let date = NaiveDate::from_ymd_opt(2023, 6, 21).unwrap();
+        let times = sun_times(date, 35.7796, -78.6382);
+        let expect = |actual: Option<DateTime<Utc>>, value: &str| {
+            let expected: DateTime<Utc> = value.parse().unwrap();
+            let diff = (actual.unwrap() - expected).num_minutes().abs();
+            assert!(diff <= 3, "got {:?}, expected {}", actual, value);
+        };
+        expect(times.sunrise, "2023-06-21T10:00:00Z");
+        expect(times.sunset, "2023-06-22T00:33:00Z");
+    }
+
+    #[test]
+    fn sun_times_none_in_polar_night() {
+        let date = NaiveDate::from_ymd_opt(2023, 12, 21).unwrap();
+        let times = sun_times(date, 78.2, 15.6);
+        assert_eq!(times.sunrise, None);
+        assert_eq!(times.sunset, None);
+    }
+}
@@ -0,0 +1,245 @@
+//! Multi-day historic observations.
+//!
+//! eBird's historic endpoint covers one day per call. Ranges are fetched
+//! day by day with bounded concurrency, spaced by a shared rate limiter and
+//! served from the on-disk cache where possible, then either merged or
+//! summarized per species.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::throttle::{RateLimiter, fan_out};
+use crate::cache::{Cache, Ttls};
+use crate::ids::RegionCode;
+use crate::telemetry;
+use crate::tools::observations::{FetchHistoricRequest, Observation};
+
+const RECENT_DAYS: u64 = 7;
+
+fn ttl_for(date: NaiveDate, today: NaiveDate, ttls: &Ttls) -> Duration {
+    if date + chrono::Days::new(RECENT_DAYS) >= today {
+        ttls.recent
+    } else {
+        ttls.historic
+    }
+}
+
+/// Days fetched in parallel unless the caller asks otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Observations reported on one day.
+pub struct HistoricDay {
+    pub date: NaiveDate,
+    pub observations: Vec<Observation>,
+}
+
+/// Shared dependencies for fetching historic days.
+#[derive(Clone)]
+pub struct HistoricFetcher {
+    pub client: ApiClient,
+    pub cache: Cache,
+    pub limiter: RateLimiter,
+}
+
+impl HistoricFetcher {
+    async fn fetch_day(
+        self,
+        region_code: RegionCode,
+        date: NaiveDate,
+    ) -> Result<HistoricDay, ApiError> {
+        let request = FetchHistoricRequest::for_date(region_code, date);
+        let ttl = ttl_for(date, chrono::Utc::now().date_naive(), &self.cache.ttls);
+        if let Some(observations) = self.cache.cached(&request, ttl) {
+            return Ok(HistoricDay { date, observations });
+        }
+
+        let mut attempt = 1;
+        let observations = loop {
+            self.limiter.wait().await;
+            match self.client.send(&request).await {
+                Err(error) if attempt < MAX_ATTEMPTS && error.is_retryable() => {
+                    telemetry::retry(telemetry::endpoint_name::<FetchHistoricRequest>());
+                    tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+                    attempt += 1;
+                }
+                result => break result?,
+            }
+        };
+        self.cache.store(&request, &observations);
+        Ok(HistoricDay { date, observations })
+    }
+
+    /// Fetches every date, at most `concurrency` at a time, in date order.
+    pub async fn fetch_days(
+        &self,
+        region_code: &RegionCode,
+        dates: Vec<NaiveDate>,
+        concurrency: usize,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<HistoricDay>, ApiError> {
+        fan_out(
+            dates,
+            concurrency,
+            |date| self.clone().fetch_day(region_code.clone(), date),
+            on_progress,
+        )
+        .await
+    }
+}
+
+/// Every date from `start` to `end`, inclusive.
+pub fn date_range(start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    start.iter_days().take_while(|date| *date <= end).collect()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesSummary {
+    pub common_name: String,
+    pub scientific_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub species_code: Option<String>,
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+    pub days_detected: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u32>,
+}
+
+/// Per-species first/last date, days detected, and highest count, most
+/// frequently detected first.
+pub fn summarize(days: &[HistoricDay]) -> Vec<SpeciesSummary> {
+    let mut species: BTreeMap<String, SpeciesSummary> = BTreeMap::new();
+    for day in days {
+        for obs in &day.observations {
+            let key = obs
+                .species_code
+                .clone()
+                .unwrap_or_else(|| obs.scientific_name.clone());
+            let summary = species.entry(key).or_insert_with(|| SpeciesSummary {
+                common_name: obs.common_name.clone(),
+                scientific_name: obs.scientific_name.clone(),
+                species_code: obs.species_code.clone(),
+                first_date: day.date,
+                last_date: day.date,
+                days_detected: 0,
+                max_count: None,
+            });
+            // The endpoint returns one record per species per day by default,
+            // but guard against counting a day twice.
+            if summary.days_detected == 0 || summary.last_date != day.date {
+                summary.days_detected += 1;
+            }
+            summary.first_date = summary.first_date.min(day.date);
+            summary.last_date = summary.last_date.max(day.date);
+            summary.max_count = summary.max_count.max(obs.count);
+        }
+    }
+    let mut summaries: Vec<_> = species.into_values().collect();
+    summaries.sort_by(|a, b| {
+        b.days_detected
+            .cmp(&a.days_detected)
+            .then_with(|| a.common_name.cmp(&b.common_name))
+    });
+    summaries
+}
+
+/// All observations across the range, newest first.
+pub fn merge(days: Vec<HistoricDay>) -> Vec<Observation> {
+    let mut observations: Vec<_> = days.into_iter().flat_map(|day| day.observations).collect();
+    observations.sort_by(|a, b| b.observation_date.cmp(&a.observation_date));
+    observations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        value.parse().unwrap()
+    }
+
+    fn obs(name: &str, date: &str, count: Option<u32>) -> Observation {
+        Observation {
+            scientific_name: format!("{} sp.", name),
+            count,
+            ..Observation::test(name, "Somewhere", &format!("{} 08:00", date))
+        }
+    }
+
+    fn days() -> Vec<HistoricDay> {
+        ["2023-05-01", "2023-05-02", "2023-05-03"]
+            .into_iter()
+            .map(|d| HistoricDay {
+                date: date(d),
+                observations: match d {
+                    "2023-05-02" => vec![obs("Whimbrel", d, Some(4))],
+                    _ => vec![obs("Whimbrel", d, None), obs("Barn Swallow", d, Some(2))],
+                },
+            })
+            .collect()
+    }
+
+    mod date_range {
+        use super::*;
+
+        #[test]
+        fn includes_both_ends() {
+            let range = date_range(date("2023-02-27"), date("2023-03-01"));
+            assert_eq!(
+                range,
+                vec![date("2023-02-27"), date("2023-02-28"), date("2023-03-01")]
+            );
+            assert!(date_range(date("2023-03-02"), date("2023-03-01")).is_empty());
+        }
+    }
+
+    mod ttl_for {
+        use super::*;
+
+        #[test]
+        fn recent_days_expire_quickly() {
+            let today = date("2023-05-20");
+            let ttls = Ttls::default();
+            assert_eq!(ttl_for(date("2023-05-20"), today, &ttls), ttls.recent);
+            assert_eq!(ttl_for(date("2023-05-13"), today, &ttls), ttls.recent);
+            assert_eq!(ttl_for(date("2023-05-12"), today, &ttls), ttls.historic);
+        }
+    }
+
+    mod summarize {
+        use super::*;
+
+        #[test]
+        fn aggregates_per_species() {
+            let summary = summarize(&days());
+            assert_eq!(summary[0].common_name, "Whimbrel");
+            assert_eq!(summary[0].days_detected, 3);
+            assert_eq!(summary[0].max_count, Some(4));
+            assert_eq!(summary[0].first_date, date("2023-05-01"));
+            assert_eq!(summary[0].last_date, date("2023-05-03"));
+
+            assert_eq!(summary[1].common_name, "Barn Swallow");
+            assert_eq!(summary[1].days_detected, 2);
+        }
+    }
+
+    mod merge {
+        use super::*;
+
+        #[test]
+        fn returns_newest_first() {
+            let merged = merge(days());
+            assert_eq!(merged.len(), 5);
+            assert!(merged[0].observation_date.starts_with("2023-05-03"));
+            assert!(merged[4].observation_date.starts_with("2023-05-01"));
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! Validated eBird identifiers.
+//!
+//! Codes end up interpolated into request paths, so they are parsed and
+//! normalized (trimmed, canonical case) when a tool call is deserialized.
+//! A malformed code is rejected locally with the expected shape instead of
+//! costing a round trip, and nothing containing `/` or `?` reaches a URL.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::api::error::ApiError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid {kind} '{value}': expected {expected}")]
+pub struct InvalidId {
+    pub kind: &'static str,
+    pub value: String,
+    pub expected: &'static str,
+}
+
+impl From<InvalidId> for ApiError {
+    fn from(error: InvalidId) -> Self {
+        ApiError::InvalidRequest(error.to_string())
+    }
+}
+
+fn is_location(value: &str) -> bool {
+    value
+        .strip_prefix('L')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_submission(value: &str) -> bool {
+    value
+        .strip_prefix('S')
+        .is_some_and(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn is_region(value: &str) -> bool {
+    let mut parts = value.split('-');
+    let country = parts.next().unwrap_or_default();
+    let rest: Vec<&str> = parts.collect();
+    country.len() == 2
+        && country.bytes().all(|b| b.is_ascii_uppercase())
+        && rest.len() <= 2
+        && rest
+            .iter()
+            .all(|p| (1..=4).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_alphanumeric()))
+}
+
+macro_rules! id_type {
+    (
+        $(#[$meta:meta])*
+        $name:ident, $kind:literal, $expected:literal, $pattern:literal,
+        |$raw:ident| $normalize:expr
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            pub const EXPECTED: &'static str = $expected;
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = InvalidId;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let $raw = value.trim();
+                let normalized: Option<String> = $normalize;
+                normalized.map(Self).ok_or_else(|| InvalidId {
+                    kind: $kind,
+                    value: value.to_string(),
+                    expected: $expected,
+                })
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = InvalidId;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                value.parse()
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = InvalidId;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                value.parse()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn inline_schema() -> bool {
+                true
+            }
+
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "pattern": $pattern,
+                })
+            }
+        }
+    };
+}
+
+id_type!(
+    /// A country, subnational1, or subnational2 code (`US`, `US-NC`,
+    /// `US-NC-063`), a location ID (`L123456`), or `world`. eBird's
+    /// region-scoped endpoints accept all of them.
+    RegionCode,
+    "region code",
+    "a country (US), subnational1 (US-NC), subnational2 (US-NC-063) code or a location ID (L123456)",
+    "^\\s*([A-Za-z]{2}(-[A-Za-z0-9]{1,4}){0,2}|[Ll][0-9]+|[Ww][Oo][Rr][Ll][Dd])\\s*$",
+    |raw| {
+        let upper = raw.to_ascii_uppercase();
+        if upper == "WORLD" {
+            Some("world".to_string())
+        } else {
+            (is_region(&upper) || is_location(&upper)).then_some(upper)
+        }
+    }
+);
+
+id_type!(
+    /// An eBird location ID such as `L123456`.
+    LocId,
+    "location ID",
+    "an eBird location ID such as L123456",
+    "^\\s*[Ll][0-9]+\\s*$",
+    |raw| {
+        let upper = raw.to_ascii_uppercase();
+        is_location(&upper).then_some(upper)
+    }
+);
+
+id_type!(
+    /// An eBird species code such as `barswa`.
+    SpeciesCode,
+    "species code",
+    "an eBird species code of 3-12 letters and digits such as barswa",
+    "^\\s*[A-Za-z0-9]{3,12}\\s*$",
+    |raw| {
+        ((3..=12).contains(&raw.len()) && raw.bytes().all(|b| b.is_ascii_alphanumeric()))
+            .then(|| raw.to_ascii_lowercase())
+    }
+);
+
+id_type!(
+    /// An eBird checklist submission ID such as `S123456789`.
+    SubId,
+    "checklist ID",
+    "an eBird checklist ID such as S123456789",
+    "^\\s*[Ss][0-9]+\\s*$",
+    |raw| {
+        let upper = raw.to_ascii_uppercase();
+        is_submission(&upper).then_some(upper)
+    }
+);
+
+impl From<LocId> for RegionCode {
+    fn from(id: LocId) -> Self {
+        RegionCode(id.0)
+    }
+}
+
+impl RegionCode {
+    /// The location ID, if this code names a single location.
+    pub fn as_loc_id(&self) -> Option<LocId> {
+        is_location(&self.0).then(|| LocId(self.0.clone()))
+    }
+}
+
+/// An observation date, written `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ObsDate(NaiveDate);
+
+impl ObsDate {
+    pub fn date(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+impl From<NaiveDate> for ObsDate {
+    fn from(date: NaiveDate) -> Self {
+        ObsDate(date)
+    }
+}
+
+impl From<ObsDate> for NaiveDate {
+    fn from(date: ObsDate) -> Self {
+        date.0
+    }
+}
+
+impl FromStr for ObsDate {
+    type Err = InvalidId;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+            .map(ObsDate)
+            .map_err(|_| InvalidId {
+                kind: "date",
+                value: value.to_string(),
+                expected: "a calendar date written YYYY-MM-DD",
+            })
+    }
+}
+
+impl TryFrom<String> for ObsDate {
+    type Error = InvalidId;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ObsDate> for String {
+    fn from(date: ObsDate) -> Self {
+        date.to_string()
+    }
+}
+
+impl fmt::Display for ObsDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format("%Y-%m-%d"))
+    }
+}
+
+impl JsonSchema for ObsDate {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "ObsDate".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "format": "date",
+            "pattern": "^\\s*[0-9]{4}-[0-9]{2}-[0-9]{2}\\s*$",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_codes_normalize_case_and_whitespace() {
+        assert_eq!(" us-nc ".parse::<RegionCode>().unwrap(), "US-NC");
+        assert_eq!("us-nc-063".parse::<RegionCode>().unwrap(), "US-NC-063");
+        assert_eq!("l123456".parse::<RegionCode>().unwrap(), "L123456");
+        assert_eq!("World".parse::<RegionCode>().unwrap(), "world");
+        assert!(
+            "L123".parse::<RegionCode>().unwrap().as_loc_id().is_some()
+                && "US".parse::<RegionCode>().unwrap().as_loc_id().is_none()
+        );
+    }
+
+    #[test]
+    fn region_codes_reject_path_characters() {
+        for bad in [
+            "US/NC",
+            "US-NC?x=1",
+            "",
+            "USA",
+            "US-",
+            "US-NC-063-1",
+            "L12a",
+        ] {
+            let error = bad.parse::<RegionCode>().unwrap_err();
+            assert!(error.to_string().contains("US-NC"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn other_ids_validate_shape() {
+        assert_eq!(" BarSwa ".parse::<SpeciesCode>().unwrap(), "barswa");
+        assert!("bar swa".parse::<SpeciesCode>().is_err());
+        assert!("ab".parse::<SpeciesCode>().is_err());
+        assert_eq!("l99".parse::<LocId>().unwrap(), "L99");
+        assert!("US-NC".parse::<LocId>().is_err());
+        assert_eq!("s123".parse::<SubId>().unwrap(), "S123");
+        assert!("S12/3".parse::<SubId>().is_err());
+    }
+
+    #[test]
+    fn dates_parse_strictly() {
+        let date: ObsDate = "2023-05-09".parse().unwrap();
+        assert_eq!(date.to_string(), "2023-05-09");
+        assert!("2023-02-30".parse::<ObsDate>().is_err());
+        assert!("05/09/2023".parse::<ObsDate>().is_err());
+    }
+
+    #[test]
+    fn deserialization_reports_expected_shape() {
+        let error = serde_json::from_value::<RegionCode>(serde_json::json!("US/NC")).unwrap_err();
+        assert!(error.to_string().contains("invalid region code 'US/NC'"));
+        let code: SpeciesCode = serde_json::from_value(serde_json::json!("BARSWA")).unwrap();
+        assert_eq!(serde_json::to_value(&code).unwrap(), "barswa");
+    }
+}
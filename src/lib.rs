@@ -0,0 +1,79 @@
+//! A typed client for the [eBird API 2.0](https://documenter.getpostman.com/view/664302/S1ENwy59),
+//! and the `rubl` MCP server built on it.
+//!
+//! The library's public API is:
+//!
+//! - [`ApiClient`], which sends authenticated requests and classifies
+//!   failures as [`ApiError`]s.
+//! - The [`Endpoint`] trait, which describes one eBird API call: its path,
+//!   query parameters, and response type.
+//! - The request and response types in [`tools`], one module per area of
+//!   the API, each request implementing [`Endpoint`].
+//! - The validated identifiers in [`ids`] that requests are built from.
+//!
+//! These follow semver. The server's own modules (`server`, `config`,
+//! `cli`, and what they use) are public only so the `rubl` binary can be a
+//! thin wrapper around them; they are hidden from the docs and may change
+//! in any release.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use rubl::ApiClient;
+//! use rubl::ids::RegionCode;
+//! use rubl::tools::observations::FetchRegionRecentRequest;
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = ApiClient::new(std::env::var("EBIRD_API_KEY")?);
+//! let request = FetchRegionRecentRequest {
+//!     region_code: "US-NC".parse::<RegionCode>()?,
+//!     back: Some(7),
+//! };
+//! for observation in client.send(&request).await? {
+//!     println!("{} at {}", observation.common_name, observation.location);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod api;
+pub mod ids;
+pub mod tools;
+
+pub use api::client::ApiClient;
+pub use api::endpoint::Endpoint;
+pub use api::error::{ApiError, ErrorKind};
+
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod config;
+#[doc(hidden)]
+pub mod diagnostics;
+#[doc(hidden)]
+pub mod logging;
+#[doc(hidden)]
+pub mod server;
+#[doc(hidden)]
+pub mod telemetry;
+
+mod alerts;
+mod arrivals;
+mod cache;
+mod chase;
+mod content;
+mod defaults;
+mod dossier;
+mod ebd;
+mod events;
+mod export;
+mod frequency;
+mod geo;
+mod historic;
+mod lifelist;
+mod profile;
+mod progress;
+mod recommend;
+mod storage;
+mod sync;
+mod watch;
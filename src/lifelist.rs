@@ -0,0 +1,401 @@
+//! Personal life list and lifer search.
+//!
+//! A life list is imported from eBird's "Download My Data" CSV or from a
+//! plain list of species codes, and persisted to `life_list.json` in the
+//! data directory. Recent observations are then filtered down to species
+//! not on the list and grouped by location.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::storage;
+use crate::tools::observations::Observation;
+
+const STATE_FILE: &str = "life_list.json";
+
+#[derive(Debug, Error)]
+pub enum LifeListError {
+    #[error("Failed to read life list CSV: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Life list CSV has no 'Scientific Name' or 'Common Name' column")]
+    MissingColumns,
+
+    #[error("Failed to access life list file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("No data directory available to store the life list (set RUBL_DATA_DIR)")]
+    NoDataDir,
+}
+
+/// Species the user has already seen.
+///
+/// Species are matched by eBird species code, scientific name, or common
+/// name, since the eBird data export does not include species codes.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifeList {
+    species_codes: BTreeSet<String>,
+    scientific_names: BTreeSet<String>,
+    common_names: BTreeSet<String>,
+}
+
+/// What an import changed.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifeListImport {
+    pub species_added: usize,
+    pub life_list_size: usize,
+}
+
+impl LifeList {
+    /// Loads the persisted life list, or an empty one if none was imported.
+    pub fn load() -> Result<Self, LifeListError> {
+        match path() {
+            Some(path) => Ok(storage::load_json(&path)?.unwrap_or_default()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), LifeListError> {
+        let path = path().ok_or(LifeListError::NoDataDir)?;
+        storage::save_json(&path, self)?;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.species_codes.is_empty()
+            && self.scientific_names.is_empty()
+            && self.common_names.is_empty()
+    }
+
+    /// Approximate number of species, taking whichever key set is largest.
+    pub fn len(&self) -> usize {
+        self.species_codes
+            .len()
+            .max(self.scientific_names.len())
+            .max(self.common_names.len())
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Adds species from an eBird "Download My Data" CSV export.
+    ///
+    /// Returns the number of species added. Spuhs, slashes, and hybrids are
+    /// skipped since they do not count toward a life list; subspecies and
+    /// forms are collapsed to their species.
+    pub fn import_csv(&mut self, data: &str) -> Result<usize, LifeListError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(data.as_bytes());
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|h| h.trim() == name);
+        let scientific = column("Scientific Name");
+        let common = column("Common Name");
+        if scientific.is_none() && common.is_none() {
+            return Err(LifeListError::MissingColumns);
+        }
+
+        let mut added = 0;
+        for record in reader.records() {
+            let record = record?;
+            let scientific = scientific.and_then(|i| record.get(i)).unwrap_or("");
+            let common = common.and_then(|i| record.get(i)).unwrap_or("");
+            if !is_countable(scientific, common) {
+                continue;
+            }
+            // A species is new if its scientific name is, or its common
+            // name when the row has no usable scientific name.
+            let new_scientific =
+                species_binomial(scientific).map(|name| self.scientific_names.insert(name));
+            let new_common = species_common_name(common).map(|name| self.common_names.insert(name));
+            if new_scientific.or(new_common).unwrap_or(false) {
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+
+    /// Adds species codes (e.g. `barswa`). Returns the number added.
+    pub fn import_codes<I, S>(&mut self, codes: I) -> usize
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let before = self.species_codes.len();
+        self.species_codes.extend(
+            codes
+                .into_iter()
+                .map(|c| c.as_ref().trim().to_ascii_lowercase())
+                .filter(|c| !c.is_empty()),
+        );
+        self.species_codes.len() - before
+    }
+
+    pub fn contains(&self, observation: &Observation) -> bool {
+        observation
+            .species_code
+            .as_deref()
+            .is_some_and(|code| self.species_codes.contains(&code.to_ascii_lowercase()))
+            || species_binomial(&observation.scientific_name)
+                .is_some_and(|name| self.scientific_names.contains(&name))
+            || species_common_name(&observation.common_name)
+                .is_some_and(|name| self.common_names.contains(&name))
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    storage::data_file(STATE_FILE)
+}
+
+/// Spuhs (`Empidonax sp.`), slashes (`Greater/Lesser Yellowlegs`), and
+/// hybrids (`Mallard x American Black Duck`) are not countable species.
+fn is_countable(scientific: &str, common: &str) -> bool {
+    let name = if scientific.is_empty() {
+        common
+    } else {
+        scientific
+    };
+    !name.is_empty()
+        && !name.contains(" sp.")
+        && !name.contains('/')
+        && !name.contains(" x ")
+        && !common.contains("(hybrid)")
+        && !common.contains("Domestic")
+}
+
+/// Reduces `Setophaga coronata [coronata Group]` to `setophaga coronata`.
+fn species_binomial(scientific: &str) -> Option<String> {
+    let mut words = scientific.split_whitespace();
+    let genus = words.next()?;
+    let species = words.next()?;
+    Some(format!("{} {}", genus, species).to_lowercase())
+}
+
+/// Reduces `Yellow-rumped Warbler (Myrtle)` to `yellow-rumped warbler`.
+fn species_common_name(common: &str) -> Option<String> {
+    let name = common.split(" (").next()?.trim();
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiferSighting {
+    pub common_name: String,
+    pub scientific_name: String,
+    pub species_code: Option<String>,
+    pub observation_date: String,
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiferLocation {
+    pub location_id: Option<String>,
+    pub location: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub needed_species: usize,
+    pub species: Vec<LiferSighting>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiferReport {
+    pub life_list_size: usize,
+    pub species_reported: usize,
+    pub lifers_available: usize,
+    pub locations: Vec<LiferLocation>,
+}
+
+/// Groups observations of species not on the life list by location, ranked
+/// by how many needed species each location has.
+pub fn find_lifers(
+    life_list: &LifeList,
+    observations: Vec<Observation>,
+    limit: usize,
+) -> LiferReport {
+    let species_reported = observations.len();
+    let mut by_location: HashMap<String, LiferLocation> = HashMap::new();
+    let mut lifers = BTreeSet::new();
+
+    for obs in observations {
+        if life_list.contains(&obs) || !is_countable(&obs.scientific_name, &obs.common_name) {
+            continue;
+        }
+        lifers.insert(obs.scientific_name.clone());
+        let key = obs
+            .location_id
+            .clone()
+            .unwrap_or_else(|| obs.location.clone());
+        let location = by_location.entry(key).or_insert_with(|| LiferLocation {
+            location_id: obs.location_id.clone(),
+            location: obs.location.clone(),
+            latitude: obs.latitude,
+            longitude: obs.longitude,
+            needed_species: 0,
+            species: Vec::new(),
+        });
+        location.needed_species += 1;
+        location.species.push(LiferSighting {
+            common_name: obs.common_name,
+            scientific_name: obs.scientific_name,
+            species_code: obs.species_code,
+            observation_date: obs.observation_date,
+            count: obs.count,
+        });
+    }
+
+    let mut locations: Vec<LiferLocation> = by_location.into_values().collect();
+    for location in &mut locations {
+        location
+            .species
+            .sort_by(|a, b| b.observation_date.cmp(&a.observation_date));
+    }
+    locations.sort_by(|a, b| {
+        b.needed_species.cmp(&a.needed_species).then_with(|| {
+            let latest = |l: &LiferLocation| l.species.first().map(|s| s.observation_date.clone());
+            latest(b).cmp(&latest(a))
+        })
+    });
+    locations.truncate(limit);
+
+    LiferReport {
+        life_list_size: life_list.len(),
+        species_reported,
+        lifers_available: lifers.len(),
+        locations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "\
+Submission ID,Common Name,Scientific Name,Taxonomic Order,Count,State/Province,County,Location ID,Location,Latitude,Longitude,Date
+S1,Barn Swallow,Hirundo rustica,1,3,US-NC,Wake,L1,Yard,35.9,-78.8,2023-05-15
+S1,Yellow-rumped Warbler (Myrtle),Setophaga coronata coronata,2,1,US-NC,Wake,L1,Yard,35.9,-78.8,2023-05-15
+S2,Empidonax sp.,Empidonax sp.,3,1,US-NC,Wake,L1,Yard,35.9,-78.8,2023-05-16
+S2,Greater/Lesser Yellowlegs,Tringa melanoleuca/flavipes,4,2,US-NC,Wake,L1,Yard,35.9,-78.8,2023-05-16
+S3,Barn Swallow,Hirundo rustica,1,5,US-NC,Wake,L1,Yard,35.9,-78.8,2023-05-17
+";
+
+    fn observation(common: &str, scientific: &str, code: &str, loc: &str) -> Observation {
+        Observation {
+            scientific_name: scientific.into(),
+            count: Some(1),
+            location_id: Some(loc.into()),
+            latitude: Some(35.9),
+            longitude: Some(-78.8),
+            species_code: Some(code.into()),
+            ..Observation::test(common, &format!("Location {}", loc), "2023-05-15 10:30")
+        }
+    }
+
+    mod import {
+        use super::*;
+
+        #[test]
+        fn csv_counts_species_once_and_skips_uncountable() {
+            let mut list = LifeList::default();
+            assert_eq!(list.import_csv(EXPORT).unwrap(), 2);
+            assert!(list.scientific_names.contains("hirundo rustica"));
+            assert!(list.scientific_names.contains("setophaga coronata"));
+            assert!(list.common_names.contains("yellow-rumped warbler"));
+            assert!(!list.scientific_names.contains("empidonax sp."));
+        }
+
+        #[test]
+        fn csv_without_name_columns_is_rejected() {
+            let mut list = LifeList::default();
+            let err = list.import_csv("Date,Count\n2023-05-15,1\n").unwrap_err();
+            assert!(matches!(err, LifeListError::MissingColumns));
+        }
+
+        #[test]
+        fn csv_counts_new_species_alongside_codes() {
+            let mut list = LifeList::default();
+            let codes: Vec<String> = (0..5).map(|i| format!("code{}", i)).collect();
+            assert_eq!(list.import_codes(&codes), 5);
+            assert_eq!(list.import_csv(EXPORT).unwrap(), 2);
+            assert_eq!(list.import_csv(EXPORT).unwrap(), 0);
+        }
+
+        #[test]
+        fn codes_are_normalized() {
+            let mut list = LifeList::default();
+            assert_eq!(list.import_codes([" BARSWA", "barswa", "", "norcar"]), 2);
+            assert!(list.species_codes.contains("barswa"));
+        }
+    }
+
+    mod contains {
+        use super::*;
+
+        #[test]
+        fn matches_by_code_scientific_or_common_name() {
+            let mut list = LifeList::default();
+            list.import_codes(["norcar"]);
+            list.import_csv(EXPORT).unwrap();
+
+            assert!(list.contains(&observation(
+                "Northern Cardinal",
+                "Cardinalis cardinalis",
+                "norcar",
+                "L1"
+            )));
+            assert!(list.contains(&observation("Barn Swallow", "Hirundo rustica", "x", "L1")));
+            assert!(list.contains(&observation(
+                "Yellow-rumped Warbler (Audubon's)",
+                "Setophaga coronata auduboni",
+                "y",
+                "L1"
+            )));
+            assert!(!list.contains(&observation(
+                "Whimbrel",
+                "Numenius phaeopus",
+                "whimbr",
+                "L1"
+            )));
+        }
+    }
+
+    mod find_lifers {
+        use super::*;
+
+        #[test]
+        fn ranks_locations_by_needed_species() {
+            let mut list = LifeList::default();
+            list.import_codes(["barswa"]);
+            let observations = vec![
+                observation("Barn Swallow", "Hirundo rustica", "barswa", "L1"),
+                observation("Whimbrel", "Numenius phaeopus", "whimbr", "L1"),
+                observation("Red Knot", "Calidris canutus", "redkno", "L2"),
+                observation("Sanderling", "Calidris alba", "sander", "L2"),
+                observation("Calidris sp.", "Calidris sp.", "calidr", "L2"),
+            ];
+            let report = find_lifers(&list, observations, 10);
+            assert_eq!(report.species_reported, 5);
+            assert_eq!(report.lifers_available, 3);
+            assert_eq!(report.locations[0].location_id.as_deref(), Some("L2"));
+            assert_eq!(report.locations[0].needed_species, 2);
+            assert_eq!(report.locations[1].needed_species, 1);
+        }
+
+        #[test]
+        fn respects_limit() {
+            let list = LifeList::default();
+            let observations = vec![
+                observation("Whimbrel", "Numenius phaeopus", "whimbr", "L1"),
+                observation("Red Knot", "Calidris canutus", "redkno", "L2"),
+            ];
+            assert_eq!(find_lifers(&list, observations, 1).locations.len(), 1);
+        }
+    }
+}
@@ -1,24 +1,224 @@
-//! Stderr logging utilities.
+//! Logging via `tracing`.
 //!
-//! MCP clients capture stderr for debugging, making it useful for
-//! diagnosing server issues.
+//! Events go to stderr, which MCP clients capture for debugging, as text or
+//! one JSON object per line (`RUBL_LOG_FORMAT=json`). `RUBL_LOG` filters them
+//! with `tracing` directives such as `debug` or `rubl=debug,rmcp=warn` and
+//! defaults to `info` for the server and `error` for the CLI commands.
+//!
+//! Once a client connects, rubl's own events are also forwarded to it as
+//! `notifications/message`, at or above the level it picked with
+//! `logging/setLevel` (warning until it picks one). Each tool call runs in a
+//! `tool_call` span and each eBird request in an `ebird_request` span, and
+//! their fields travel with every event inside them.
+
+use std::sync::Mutex;
+
+use rmcp::{
+    Peer, RoleServer,
+    model::{LoggingLevel, LoggingMessageNotificationParam},
+};
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber, span};
+use tracing_subscriber::{
+    EnvFilter, Layer, filter::filter_fn, layer::Context, prelude::*, registry::LookupSpan,
+};
+
+/// Only events from this crate are forwarded, so the notifications rmcp
+/// logs while sending them can't feed back into the client.
+const TARGET: &str = "rubl";
+
+struct ClientSink {
+    peer: Option<Peer<RoleServer>>,
+    level: LoggingLevel,
+}
+
+static CLIENT: Mutex<ClientSink> = Mutex::new(ClientSink {
+    peer: None,
+    level: LoggingLevel::Warning,
+});
+
+fn client() -> std::sync::MutexGuard<'static, ClientSink> {
+    CLIENT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Installs the global subscriber, filtering at `default_level` unless
+/// `RUBL_LOG` says otherwise. Safe to call more than once.
+pub fn init(default_level: &str) {
+    let filter =
+        EnvFilter::try_from_env("RUBL_LOG").unwrap_or_else(|_| EnvFilter::new(default_level));
+    let json = std::env::var("RUBL_LOG_FORMAT").is_ok_and(|f| f.eq_ignore_ascii_case("json"));
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(false);
+    let stderr = if json {
+        stderr.json().with_current_span(true).boxed()
+    } else {
+        stderr.boxed()
+    };
+    let _ = tracing_subscriber::registry()
+        .with(stderr.with_filter(filter))
+        .with(ClientLayer.with_filter(filter_fn(|metadata| metadata.target().starts_with(TARGET))))
+        .try_init();
+}
+
+/// Starts forwarding events to a connected client.
+pub fn attach_client(peer: Peer<RoleServer>) {
+    client().peer = Some(peer);
+}
+
+/// Applies `logging/setLevel`.
+pub fn set_client_level(level: LoggingLevel) {
+    client().level = level;
+}
 
-const PREFIX: &str = "rubl";
+fn rank(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
 
-pub fn info(message: impl std::fmt::Display) {
-    eprintln!("[{}] {}", PREFIX, message);
+fn mcp_level(level: Level) -> LoggingLevel {
+    match level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        Level::DEBUG | Level::TRACE => LoggingLevel::Debug,
+    }
 }
 
-pub fn error(message: impl std::fmt::Display) {
-    eprintln!("[{}] ERROR: {}", PREFIX, message);
+/// Collects event and span fields as JSON.
+#[derive(Default)]
+struct JsonFields(Map<String, Value>);
+
+impl Visit for JsonFields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().into(), format!("{:?}", value).into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
 }
 
-// Used by panic hook to capture crashes.
-pub fn panic(message: impl std::fmt::Display) {
-    eprintln!("[{}] PANIC: {}", PREFIX, message);
+/// Forwards events to the connected MCP client.
+struct ClientLayer;
+
+impl<S> Layer<S> for ClientLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut fields = JsonFields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id)
+            && let Some(fields) = span.extensions_mut().get_mut::<JsonFields>()
+        {
+            values.record(fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = mcp_level(*metadata.level());
+        let peer = {
+            let sink = client();
+            if rank(level) < rank(sink.level) {
+                return;
+            }
+            match &sink.peer {
+                Some(peer) => peer.clone(),
+                None => return,
+            }
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let mut data = JsonFields::default();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                if let Some(fields) = span.extensions().get::<JsonFields>() {
+                    data.0.extend(fields.0.clone());
+                }
+            }
+        }
+        event.record(&mut data);
+        let message = LoggingMessageNotificationParam {
+            level,
+            logger: Some(metadata.target().replace("::", ".")),
+            data: Value::Object(data.0),
+        };
+        runtime.spawn(async move {
+            // Nowhere left to report a failure to; stderr already has the event.
+            let _ = peer.notify_logging_message(message).await;
+        });
+    }
 }
 
-#[allow(dead_code)]
-pub fn warn(message: impl std::fmt::Display) {
-    eprintln!("[{}] WARN: {}", PREFIX, message);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_rank_in_protocol_order() {
+        assert!(rank(LoggingLevel::Debug) < rank(LoggingLevel::Info));
+        assert!(rank(LoggingLevel::Notice) < rank(LoggingLevel::Warning));
+        assert!(rank(mcp_level(Level::ERROR)) > rank(mcp_level(Level::WARN)));
+        assert_eq!(mcp_level(Level::TRACE), LoggingLevel::Debug);
+    }
+
+    #[test]
+    fn collects_typed_fields() {
+        static CAPTURED: Mutex<Option<Map<String, Value>>> = Mutex::new(None);
+
+        struct CaptureLayer;
+        impl<S: Subscriber> Layer<S> for CaptureLayer {
+            fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+                let mut fields = JsonFields::default();
+                event.record(&mut fields);
+                *CAPTURED.lock().unwrap() = Some(fields.0);
+            }
+        }
+
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(status = 404u64, cache_hit = false, path = "ref/x", "done");
+        });
+        let fields = CAPTURED.lock().unwrap().take().unwrap();
+        assert_eq!(fields["status"], 404);
+        assert_eq!(fields["cache_hit"], false);
+        assert_eq!(fields["path"], "ref/x");
+        assert_eq!(fields["message"], "done");
+    }
 }
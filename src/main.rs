@@ -1,38 +1,87 @@
-mod api;
-mod content;
-mod logging;
-mod server;
-mod tools;
+//! The `rubl` MCP server binary; everything it runs lives in the library.
 
+use std::process::ExitCode;
+
+use clap::Parser;
 use rmcp::{ServiceExt, transport::stdio};
 
-use server::RublClient;
+use rubl::api::cassette::Mode;
+use rubl::cli::{self, Cli, Command};
+use rubl::config::Config;
+use rubl::server::RublClient;
+use rubl::{diagnostics, logging, telemetry};
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut cli = Cli::parse();
+    let command = cli.command.take().unwrap_or(Command::Serve);
+    // The debugging commands report failures themselves; keep stderr quiet.
+    let serving = matches!(command, Command::Serve);
+    logging::init(if serving { "info" } else { "error" });
+
     // Set up panic hook to log to stderr (will appear in MCP client logs)
     std::panic::set_hook(Box::new(|panic_info| {
-        logging::panic(panic_info);
+        tracing::error!(panic = %panic_info, "Server panicked");
     }));
 
-    let api_key = std::env::var("EBIRD_API_KEY").map_err(|_| {
-        logging::error("EBIRD_API_KEY environment variable is required");
-        "EBIRD_API_KEY environment variable is required"
-    })?;
+    let mut config = Config::load(cli.source())
+        .inspect_err(|e| tracing::error!(error = %e, "Invalid configuration"))?;
+    if let Some(path) = &config.path {
+        tracing::info!(path = %path.display(), profile = config.profile.as_deref(), "Loaded config file");
+    }
+    if let Some(format) = cli.format {
+        config.defaults.format = Some(format);
+    }
+    if let Some(cassette) = cli.cassette() {
+        config.cassette = Some(cassette);
+    }
+    let replaying = config
+        .cassette
+        .as_ref()
+        .is_some_and(|cassette| cassette.mode() == Mode::Replay);
+
+    let api_key = match config.api_key.clone() {
+        Some(key) => key,
+        None if replaying || !command.needs_api_key() => String::new(),
+        None => {
+            let message =
+                "EBIRD_API_KEY environment variable (or api_key in the config file) is required";
+            tracing::error!("{}", message);
+            return Err(message.into());
+        }
+    };
+
+    let service = RublClient::new(api_key, &config)
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to build HTTP client"))?;
+
+    if !serving {
+        let format = config.defaults.format.unwrap_or_default();
+        return Ok(cli::run(command, service, format).await);
+    }
+
+    if diagnostics::startup_probe_enabled() {
+        diagnostics::check_key(service.api_client())
+            .await
+            .inspect_err(|e| tracing::error!("{}", e))?;
+    }
+
+    if let Some(addr) = telemetry::init().inspect_err(|e| tracing::error!("{}", e))? {
+        tracing::info!(%addr, "Serving metrics at /metrics");
+    }
 
-    logging::info("Starting MCP server");
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "Starting MCP server");
 
-    let service = RublClient::new(api_key).serve(stdio()).await.map_err(|e| {
-        logging::error(format!("Failed to start MCP server: {}", e));
-        e
-    })?;
+    let service = service
+        .serve(stdio())
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to start MCP server"))?;
 
-    service.waiting().await.map_err(|e| {
-        logging::error(format!("MCP server error: {}", e));
-        e
-    })?;
+    service
+        .waiting()
+        .await
+        .inspect_err(|e| tracing::error!(error = %e, "MCP server error"))?;
 
-    logging::info("MCP server shutting down");
+    tracing::info!("MCP server shutting down");
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
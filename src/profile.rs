@@ -0,0 +1,287 @@
+//! Composite species profiles.
+//!
+//! Taxonomy, recent sightings in a region, the nearest sighting to a point,
+//! and counts by subregion are fetched concurrently. Each part fails on its
+//! own: a profile carries whatever succeeded plus an error per failed part.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::throttle::{RateLimiter, fan_out};
+use crate::ids::{RegionCode, SpeciesCode};
+use crate::tools::observations::{
+    FetchSpeciesNearestRequest, FetchSpeciesRecentRequest, Observation,
+};
+use crate::tools::region::{ListRegionsRequest, RegionType, SubRegion};
+use crate::tools::species::{FetchTaxonomyRequest, TaxonomyEntry};
+
+/// Subregion lookups are one request each; larger fan-outs are refused.
+pub const MAX_SUBREGIONS: usize = 120;
+const SUBREGION_CONCURRENCY: usize = 4;
+const RECENT_LIMIT: usize = 20;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubregionCount {
+    pub code: String,
+    pub name: String,
+    /// Locations with a recent report (eBird returns the latest per location).
+    pub locations: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<u32>,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeciesProfile {
+    pub species_code: SpeciesCode,
+    pub region_code: RegionCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taxonomy: Option<TaxonomyEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_locations: Option<usize>,
+    /// Most recent sightings in the region, newest first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent: Option<Vec<Observation>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest: Option<Observation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subregions: Option<Vec<SubregionCount>>,
+    /// Parts that could not be fetched, by part name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors: BTreeMap<&'static str, String>,
+}
+
+pub struct ProfileRequest {
+    pub species_code: SpeciesCode,
+    pub region_code: RegionCode,
+    pub point: Option<(f64, f64)>,
+    pub back: u32,
+}
+
+/// Rolls per-subregion sightings up into counts, busiest first. Subregions
+/// without reports are dropped.
+pub fn count_by_subregion(results: Vec<(SubRegion, Vec<Observation>)>) -> Vec<SubregionCount> {
+    let mut counts: Vec<SubregionCount> = results
+        .into_iter()
+        .filter(|(_, observations)| !observations.is_empty())
+        .map(|(region, observations)| SubregionCount {
+            code: region.code,
+            name: region.name,
+            locations: observations.len() as u32,
+            max_count: observations.iter().filter_map(|o| o.count).max(),
+            last_seen: observations
+                .iter()
+                .map(|o| o.observation_date.clone())
+                .max()
+                .unwrap_or_default(),
+        })
+        .collect();
+    counts.sort_by(|a, b| {
+        b.locations
+            .cmp(&a.locations)
+            .then_with(|| b.last_seen.cmp(&a.last_seen))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    counts
+}
+
+async fn subregion_counts(
+    client: &ApiClient,
+    limiter: &RateLimiter,
+    req: &ProfileRequest,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<Option<Vec<SubregionCount>>, ApiError> {
+    let Some(region_type) = RegionType::children_of(req.region_code.as_str()) else {
+        return Ok(None);
+    };
+    let subregions = client
+        .send(&ListRegionsRequest {
+            region_type,
+            parent_code: req.region_code.clone(),
+        })
+        .await?;
+    if subregions.len() > MAX_SUBREGIONS {
+        return Err(ApiError::InvalidRequest(format!(
+            "{} has {} subregions; counts are limited to {}. Use a smaller region.",
+            req.region_code,
+            subregions.len(),
+            MAX_SUBREGIONS
+        )));
+    }
+
+    let results = fan_out(
+        subregions,
+        SUBREGION_CONCURRENCY,
+        |region| {
+            let client = client.clone();
+            let limiter = limiter.clone();
+            let region_code = region.code.parse::<RegionCode>();
+            let species_code = req.species_code.clone();
+            let back = Some(req.back);
+            async move {
+                let request = FetchSpeciesRecentRequest {
+                    region_code: region_code?,
+                    species_code,
+                    back,
+                };
+                limiter.wait().await;
+                let observations = client.send(&request).await?;
+                Ok::<_, ApiError>((region, observations))
+            }
+        },
+        on_progress,
+    )
+    .await?;
+    Ok(Some(count_by_subregion(results)))
+}
+
+/// Fetches every part of the profile concurrently.
+///
+/// Fails only when every attempted part failed.
+pub async fn build(
+    client: &ApiClient,
+    limiter: &RateLimiter,
+    req: &ProfileRequest,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<SpeciesProfile, ApiError> {
+    let taxonomy_request = FetchTaxonomyRequest::for_species(req.species_code.clone());
+    let recent_request = FetchSpeciesRecentRequest {
+        region_code: req.region_code.clone(),
+        species_code: req.species_code.clone(),
+        back: Some(req.back),
+    };
+    let taxonomy = client.send(&taxonomy_request);
+    let recent = client.send(&recent_request);
+    let nearest = async {
+        match req.point {
+            Some((lat, lng)) => client
+                .send(&FetchSpeciesNearestRequest {
+                    species_code: req.species_code.clone(),
+                    lat,
+                    lng,
+                    dist: Some(50),
+                    back: Some(req.back),
+                })
+                .await
+                .map(|mut observations| {
+                    (!observations.is_empty()).then(|| observations.swap_remove(0))
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    };
+    let subregions = subregion_counts(client, limiter, req, on_progress);
+    let (taxonomy, recent, nearest, subregions) =
+        tokio::join!(taxonomy, recent, nearest, subregions);
+
+    let mut profile = SpeciesProfile {
+        species_code: req.species_code.clone(),
+        region_code: req.region_code.clone(),
+        taxonomy: None,
+        recent_locations: None,
+        recent: None,
+        nearest: None,
+        subregions: None,
+        errors: BTreeMap::new(),
+    };
+    let mut failures = Vec::new();
+    let mut attempted = 2;
+
+    match taxonomy {
+        Ok(entries) => match entries.into_iter().next() {
+            Some(entry) => profile.taxonomy = Some(entry),
+            None => {
+                profile.errors.insert(
+                    "taxonomy",
+                    format!("Unknown species code {}", req.species_code),
+                );
+            }
+        },
+        Err(e) => failures.push(("taxonomy", e)),
+    }
+
+    match recent {
+        Ok(mut observations) => {
+            observations.sort_by_key(|o| std::cmp::Reverse(o.observed_at()));
+            profile.recent_locations = Some(observations.len());
+            observations.truncate(RECENT_LIMIT);
+            profile.recent = Some(observations);
+        }
+        Err(e) => failures.push(("recent", e)),
+    }
+
+    if req.point.is_some() {
+        attempted += 1;
+    }
+    match nearest {
+        Ok(observation) => profile.nearest = observation.flatten(),
+        Err(e) => failures.push(("nearest", e)),
+    }
+
+    if RegionType::children_of(req.region_code.as_str()).is_some() {
+        attempted += 1;
+    }
+    match subregions {
+        Ok(counts) => profile.subregions = counts,
+        Err(e) => failures.push(("subregions", e)),
+    }
+
+    if failures.len() == attempted {
+        let (_, error) = failures.swap_remove(0);
+        return Err(error);
+    }
+    for (part, error) in failures {
+        profile.errors.insert(part, error.to_string());
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obs(location: &str, date: &str, count: Option<u32>) -> Observation {
+        Observation {
+            scientific_name: "Hirundo rustica".into(),
+            count,
+            species_code: Some("barswa".into()),
+            ..Observation::test("Barn Swallow", location, date)
+        }
+    }
+
+    fn region(code: &str) -> SubRegion {
+        SubRegion {
+            code: code.into(),
+            name: code.into(),
+        }
+    }
+
+    #[test]
+    fn counts_rank_busiest_subregions_first() {
+        let counts = count_by_subregion(vec![
+            (
+                region("US-NC-001"),
+                vec![obs("A", "2023-05-01 08:00", Some(3))],
+            ),
+            (region("US-NC-003"), vec![]),
+            (
+                region("US-NC-005"),
+                vec![
+                    obs("B", "2023-05-02 09:00", Some(12)),
+                    obs("C", "2023-05-03 07:30", None),
+                ],
+            ),
+        ]);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].code, "US-NC-005");
+        assert_eq!(counts[0].locations, 2);
+        assert_eq!(counts[0].max_count, Some(12));
+        assert_eq!(counts[0].last_seen, "2023-05-03 07:30");
+        assert_eq!(counts[1].code, "US-NC-001");
+    }
+}
@@ -0,0 +1,40 @@
+//! MCP progress notifications for long-running tool calls.
+
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::RequestContext;
+use rmcp::{Peer, RoleServer};
+
+/// Reports progress to the client if it asked for it with a progress token.
+pub struct Progress {
+    target: Option<(Peer<RoleServer>, ProgressToken)>,
+}
+
+impl Progress {
+    pub fn from_context(context: &RequestContext<RoleServer>) -> Self {
+        Self {
+            target: context
+                .meta
+                .get_progress_token()
+                .map(|token| (context.peer.clone(), token)),
+        }
+    }
+
+    /// Sends an update without waiting for it to be delivered.
+    pub fn report(&self, done: usize, total: usize, message: impl Into<String>) {
+        let Some((peer, token)) = &self.target else {
+            return;
+        };
+        let peer = peer.clone();
+        let param = ProgressNotificationParam {
+            progress_token: token.clone(),
+            progress: done as f64,
+            total: Some(total as f64),
+            message: Some(message.into()),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = peer.notify_progress(param).await {
+                tracing::warn!(error = %e, "Failed to send progress");
+            }
+        });
+    }
+}
@@ -0,0 +1,448 @@
+//! Hotspot scoring and recommendations.
+//!
+//! Nearby hotspots birded recently are the candidates. Each is scored on
+//! recent species richness, notable sightings, how recently it was birded,
+//! all-time richness, closeness, and (optionally) how many target species
+//! it has had lately. Every factor is scaled to 0-1 against the best
+//! candidate, so scores compare hotspots with each other rather than
+//! against any absolute standard.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::Serialize;
+
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::api::throttle::{RateLimiter, fan_out};
+use crate::geo;
+use crate::ids::{RegionCode, SpeciesCode};
+use crate::tools::hotspot::{FetchNearbyHotspotsRequest, Hotspot, ScoreWeightsInput};
+use crate::tools::observations::{
+    FetchGeoNotableRequest, FetchRegionRecentRequest, Observation, RarityView,
+};
+
+/// Hotspots whose recent observations are fetched; one request each.
+pub const MAX_CANDIDATES: usize = 15;
+const CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub richness: f64,
+    pub notable: f64,
+    pub activity: f64,
+    pub all_time: f64,
+    pub distance: f64,
+    pub targets: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            richness: 1.0,
+            notable: 1.0,
+            activity: 0.5,
+            all_time: 0.5,
+            distance: 1.0,
+            targets: 2.0,
+        }
+    }
+}
+
+impl From<&ScoreWeightsInput> for Weights {
+    fn from(input: &ScoreWeightsInput) -> Self {
+        let defaults = Self::default();
+        let pick = |value: Option<f64>, default: f64| value.unwrap_or(default).max(0.0);
+        Self {
+            richness: pick(input.richness, defaults.richness),
+            notable: pick(input.notable, defaults.notable),
+            activity: pick(input.activity, defaults.activity),
+            all_time: pick(input.all_time, defaults.all_time),
+            distance: pick(input.distance, defaults.distance),
+            targets: pick(input.targets, defaults.targets),
+        }
+    }
+}
+
+/// A hotspot with what was reported there recently.
+pub struct Candidate {
+    pub hotspot: Hotspot,
+    pub distance_km: f64,
+    pub recent: Vec<Observation>,
+    pub notable: Vec<Observation>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreFactor {
+    pub factor: &'static str,
+    /// 0-1, relative to the best candidate.
+    pub value: f64,
+    pub weight: f64,
+    pub note: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub location_id: String,
+    pub name: String,
+    pub lat: f64,
+    pub lng: f64,
+    /// 0-100.
+    pub score: f64,
+    pub distance_km: f64,
+    pub recent_species: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notable_species: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub targets_present: Vec<SpeciesCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_observation: Option<String>,
+    pub factors: Vec<ScoreFactor>,
+}
+
+fn parse_latest(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })
+}
+
+fn ratio(value: f64, best: f64) -> f64 {
+    if best > 0.0 { value / best } else { 0.0 }
+}
+
+fn round(value: f64, places: i32) -> f64 {
+    let scale = 10f64.powi(places);
+    (value * scale).round() / scale
+}
+
+/// Scores and ranks candidates, best first.
+pub fn score(
+    candidates: Vec<Candidate>,
+    targets: &[SpeciesCode],
+    weights: Weights,
+    radius_km: f64,
+    back_days: u32,
+    now: NaiveDateTime,
+) -> Vec<Recommendation> {
+    let notable_names = |c: &Candidate| -> BTreeSet<String> {
+        c.notable.iter().map(|o| o.common_name.clone()).collect()
+    };
+    let best_recent = candidates.iter().map(|c| c.recent.len()).max().unwrap_or(0) as f64;
+    let best_notable = candidates
+        .iter()
+        .map(|c| notable_names(c).len())
+        .max()
+        .unwrap_or(0) as f64;
+    let best_all_time = candidates
+        .iter()
+        .filter_map(|c| c.hotspot.num_species_all_time)
+        .max()
+        .unwrap_or(0) as f64;
+    let window_hours = back_days as f64 * 24.0;
+
+    let mut recommendations: Vec<Recommendation> = candidates
+        .into_iter()
+        .map(|c| {
+            let notable = notable_names(&c);
+            let present: Vec<SpeciesCode> = targets
+                .iter()
+                .filter(|t| {
+                    c.recent
+                        .iter()
+                        .any(|o| o.species_code.as_deref() == Some(t.as_str()))
+                })
+                .cloned()
+                .collect();
+            let hours_since = c
+                .hotspot
+                .latest_observation_date
+                .as_deref()
+                .and_then(parse_latest)
+                .map(|at| (now - at).num_minutes().max(0) as f64 / 60.0);
+            let all_time = c.hotspot.num_species_all_time.unwrap_or(0);
+
+            let mut factors = vec![
+                ScoreFactor {
+                    factor: "richness",
+                    value: ratio(c.recent.len() as f64, best_recent),
+                    weight: weights.richness,
+                    note: format!(
+                        "{} species in the last {} days (best nearby: {})",
+                        c.recent.len(),
+                        back_days,
+                        best_recent
+                    ),
+                },
+                ScoreFactor {
+                    factor: "notable",
+                    value: ratio(notable.len() as f64, best_notable),
+                    weight: weights.notable,
+                    note: match notable.len() {
+                        0 => "no notable sightings".to_string(),
+                        n => format!("{} notable species", n),
+                    },
+                },
+                ScoreFactor {
+                    factor: "activity",
+                    value: hours_since
+                        .map(|h| (1.0 - h / window_hours).clamp(0.0, 1.0))
+                        .unwrap_or(0.0),
+                    weight: weights.activity,
+                    note: match hours_since {
+                        Some(h) if h < 24.0 => "birded within the last day".to_string(),
+                        Some(h) => format!("last birded {:.0} days ago", h / 24.0),
+                        None => "no recent checklist date".to_string(),
+                    },
+                },
+                ScoreFactor {
+                    factor: "allTime",
+                    value: ratio(all_time as f64, best_all_time),
+                    weight: weights.all_time,
+                    note: format!("{} species all time", all_time),
+                },
+                ScoreFactor {
+                    factor: "distance",
+                    value: (1.0 - c.distance_km / radius_km.max(1.0)).clamp(0.0, 1.0),
+                    weight: weights.distance,
+                    note: format!("{:.1} km away", c.distance_km),
+                },
+            ];
+            if !targets.is_empty() {
+                factors.push(ScoreFactor {
+                    factor: "targets",
+                    value: present.len() as f64 / targets.len() as f64,
+                    weight: weights.targets,
+                    note: format!(
+                        "{} of {} target species reported recently",
+                        present.len(),
+                        targets.len()
+                    ),
+                });
+            }
+
+            let total_weight: f64 = factors.iter().map(|f| f.weight).sum();
+            let weighted: f64 = factors.iter().map(|f| f.value * f.weight).sum();
+            for factor in &mut factors {
+                factor.value = round(factor.value, 2);
+            }
+            Recommendation {
+                location_id: c.hotspot.location_id,
+                name: c.hotspot.name,
+                lat: c.hotspot.latitude,
+                lng: c.hotspot.longitude,
+                score: round(100.0 * ratio(weighted, total_weight), 1),
+                distance_km: round(c.distance_km, 1),
+                recent_species: c.recent.len(),
+                notable_species: notable.into_iter().collect(),
+                targets_present: present,
+                latest_observation: c.hotspot.latest_observation_date,
+                factors,
+            }
+        })
+        .collect();
+    recommendations.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.distance_km.total_cmp(&b.distance_km))
+    });
+    recommendations
+}
+
+pub struct RecommendRequest {
+    pub lat: f64,
+    pub lng: f64,
+    pub dist: u32,
+    pub back: u32,
+    pub targets: Vec<SpeciesCode>,
+    pub weights: Weights,
+    pub limit: usize,
+}
+
+/// Fetches candidates and their recent sightings, then ranks them.
+pub async fn recommend(
+    client: &ApiClient,
+    limiter: &RateLimiter,
+    req: &RecommendRequest,
+    now: NaiveDateTime,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<Vec<Recommendation>, ApiError> {
+    let hotspots_request = FetchNearbyHotspotsRequest {
+        lat: req.lat,
+        lng: req.lng,
+        dist: Some(req.dist as f64),
+        back: Some(req.back),
+    };
+    let notable_request = FetchGeoNotableRequest {
+        lat: req.lat,
+        lng: req.lng,
+        dist: Some(req.dist),
+        back: Some(req.back),
+        view: RarityView::Reports,
+    };
+    let (hotspots, notable) = tokio::join!(
+        client.send(&hotspots_request),
+        client.send(&notable_request)
+    );
+
+    let mut notable_by_location: HashMap<String, Vec<Observation>> = HashMap::new();
+    for report in notable? {
+        if let Some(loc_id) = report.location_id.clone() {
+            notable_by_location.entry(loc_id).or_default().push(report);
+        }
+    }
+
+    // Closest first, so the cap drops the far fringe of the radius.
+    let mut hotspots: Vec<(f64, Hotspot)> = hotspots?
+        .into_iter()
+        .map(|h| {
+            (
+                geo::haversine_km((req.lat, req.lng), (h.latitude, h.longitude)),
+                h,
+            )
+        })
+        .collect();
+    hotspots.sort_by(|a, b| a.0.total_cmp(&b.0));
+    hotspots.truncate(MAX_CANDIDATES);
+
+    let candidates = fan_out(
+        hotspots,
+        CONCURRENCY,
+        |(distance_km, hotspot)| {
+            let client = client.clone();
+            let limiter = limiter.clone();
+            let notable = notable_by_location
+                .remove(&hotspot.location_id)
+                .unwrap_or_default();
+            let region_code = hotspot.location_id.parse::<RegionCode>();
+            let back = Some(req.back);
+            async move {
+                let request = FetchRegionRecentRequest {
+                    region_code: region_code?,
+                    back,
+                };
+                limiter.wait().await;
+                let recent = client.send(&request).await?;
+                Ok::<_, ApiError>(Candidate {
+                    hotspot,
+                    distance_km,
+                    recent,
+                    notable,
+                })
+            }
+        },
+        on_progress,
+    )
+    .await?;
+
+    let mut ranked = score(
+        candidates,
+        &req.targets,
+        req.weights,
+        req.dist as f64,
+        req.back,
+        now,
+    );
+    ranked.truncate(req.limit);
+    Ok(ranked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hotspot(id: &str, all_time: u32, latest: &str) -> Hotspot {
+        serde_json::from_value(serde_json::json!({
+            "locId": id,
+            "locName": id,
+            "lat": 35.0,
+            "lng": -78.0,
+            "countryCode": "US",
+            "subnational1Code": "US-NC",
+            "latestObsDt": latest,
+            "numSpeciesAllTime": all_time
+        }))
+        .unwrap()
+    }
+
+    fn obs(code: &str) -> Observation {
+        Observation {
+            common_name: code.to_uppercase(),
+            species_code: Some(code.into()),
+            ..Observation::test(code, "", "2023-05-09 07:00")
+        }
+    }
+
+    fn now() -> NaiveDateTime {
+        parse_latest("2023-05-10 12:00").unwrap()
+    }
+
+    #[test]
+    fn richer_closer_hotspot_ranks_first() {
+        let ranked = score(
+            vec![
+                Candidate {
+                    hotspot: hotspot("L1", 200, "2023-05-10 07:00"),
+                    distance_km: 20.0,
+                    recent: vec![obs("a")],
+                    notable: vec![],
+                },
+                Candidate {
+                    hotspot: hotspot("L2", 250, "2023-05-10 08:00"),
+                    distance_km: 5.0,
+                    recent: vec![obs("a"), obs("b"), obs("c")],
+                    notable: vec![obs("c")],
+                },
+            ],
+            &[],
+            Weights::default(),
+            25.0,
+            7,
+            now(),
+        );
+        assert_eq!(ranked[0].location_id, "L2");
+        assert_eq!(ranked[0].notable_species, vec!["C"]);
+        assert_eq!(ranked[0].factors.len(), 5);
+        assert!(ranked[0].score > ranked[1].score);
+        assert!(ranked[0].score <= 100.0);
+    }
+
+    #[test]
+    fn targets_and_weights_change_the_ranking() {
+        let candidates = || {
+            vec![
+                Candidate {
+                    hotspot: hotspot("near", 100, "2023-05-10 07:00"),
+                    distance_km: 1.0,
+                    recent: vec![obs("a"), obs("b")],
+                    notable: vec![],
+                },
+                Candidate {
+                    hotspot: hotspot("far", 100, "2023-05-10 07:00"),
+                    distance_km: 24.0,
+                    recent: vec![obs("a"), obs("paibun")],
+                    notable: vec![],
+                },
+            ]
+        };
+        let ranked = score(candidates(), &[], Weights::default(), 25.0, 7, now());
+        assert_eq!(ranked[0].location_id, "near");
+
+        let targets: Vec<SpeciesCode> = vec!["paibun".parse().unwrap()];
+        let ranked = score(candidates(), &targets, Weights::default(), 25.0, 7, now());
+        assert_eq!(ranked[0].location_id, "far");
+        assert_eq!(ranked[0].targets_present, targets);
+
+        let weights = Weights::from(&ScoreWeightsInput {
+            targets: Some(0.0),
+            ..Default::default()
+        });
+        let ranked = score(candidates(), &targets, weights, 25.0, 7, now());
+        assert_eq!(ranked[0].location_id, "near");
+    }
+}
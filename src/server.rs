@@ -1,39 +1,113 @@
+use std::sync::Arc;
+use std::time::Instant;
+
 use rmcp::{
-    ErrorData as McpError, ServerHandler,
-    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    ErrorData as McpError, Peer, RoleServer, ServerHandler,
+    handler::server::{
+        tool::{ToolCallContext, ToolRouter},
+        wrapper::Parameters,
+    },
     model::*,
-    tool, tool_handler, tool_router,
+    service::{NotificationContext, RequestContext},
+    tool, tool_router,
 };
+use serde::Serialize;
+use tracing::Instrument;
 
 use crate::{
+    alerts::AlertDispatcher,
     api::client::ApiClient,
     api::error::ApiError,
+    api::throttle::RateLimiter,
+    arrivals::{self, ArrivalReport},
+    cache::Cache,
+    chase,
+    config::{Config, OutputFormat, Transport, Units},
     content::ToContent,
+    defaults::ParameterDefaults,
+    diagnostics::{self, Diagnostics},
+    dossier::{self, DossierRequest},
+    ebd::{EbdError, EbdStore},
+    events, export,
+    frequency::{self, FrequencyError},
+    historic::{self, HistoricFetcher},
+    lifelist::{self, LifeList, LifeListImport},
     logging,
+    profile::{self, ProfileRequest},
+    progress::Progress,
+    recommend::{self, RecommendRequest, Weights},
+    telemetry::{self, InFlight},
+    tools::arrivals::FindArrivalsRequest,
+    tools::chase::PlanChaseRequest,
+    tools::ebd::{ImportEbdRequest, QueryEbdRequest},
+    tools::export::{ExportHotspotsRequest, ExportItineraryRequest, ExportObservationsRequest},
+    tools::frequency::GetFrequenciesRequest,
+    tools::historic::{FetchHistoricRangeRequest, HistoricRangeView},
     tools::hotspot::{
         FetchHotspotInfoRequest, FetchNearbyHotspotsRequest, FetchRegionHotspotsRequest,
+        HotspotDossierRequest, RecommendHotspotsRequest,
     },
+    tools::lifelist::{FindLifersRequest, ImportLifeListRequest},
     tools::observations::{
-        FetchGeoRecentRequest, FetchHistoricRequest, FetchNotableRecentRequest,
-        FetchRegionRecentRequest, FetchSpeciesNearestRequest, FetchSpeciesRecentRequest,
+        FetchGeoNotableRequest, FetchGeoRecentRequest, FetchGeoSpeciesRequest,
+        FetchHistoricRequest, FetchNotableRecentRequest, FetchRegionRecentRequest,
+        FetchSpeciesNearestRequest, FetchSpeciesRecentRequest, RareBird, RarityView,
     },
     tools::region::{GetRegionInfoRequest, GetSubRegionsRequest},
+    tools::species::SpeciesProfileRequest,
     tools::trip,
+    tools::watch::{UnwatchRequest, WatchNotableRequest},
+    watch::{WatchManager, WatchSummary},
 };
 
+const INSTRUCTIONS: &str = "eBird API tools: region hierarchy (get_region_info, get_subregions), recent and notable sightings (fetch_notable_recent, fetch_geo_notable), and birding locations (fetch_nearby_hotspots, hotspot_dossier, recommend_hotspots). \
+     Region codes are like US, US-NC, US-NC-067. Tools only read eBird data, except watch_notable, unwatch, import_life_list, and import_ebd, which save state in rubl's data directory.";
+
+/// Pace for fanned-out eBird calls, well under what the API tolerates.
+const API_REQUESTS_PER_SECOND: u32 = 5;
+
 #[derive(Clone)]
 pub struct RublClient {
     tool_router: ToolRouter<Self>,
     client: ApiClient,
+    historic: HistoricFetcher,
+    watches: WatchManager,
+    /// Whether connecting a client starts the background watch poller.
+    poll_watches: bool,
+    defaults: Arc<ParameterDefaults>,
+    format: OutputFormat,
+    units: Units,
+    transport: Transport,
 }
 
 /// Converts an `ApiError` to an appropriate MCP error.
 ///
-/// Maps 4xx client errors to `invalid_params` and 5xx/network errors to
-/// `internal_error`. Logs to stderr for MCP client debugging.
+/// Problems the caller can fix (bad parameters, unknown regions, other 4xx)
+/// map to `invalid_params`; the rest map to `internal_error`. Either way the
+/// error carries a `data` object with the error `kind`, whether it is
+/// `retryable`, the HTTP `status` when there was one, and a `suggestion`.
+/// Logs a warning with the error kind.
 fn api_error_to_mcp(error: ApiError) -> McpError {
-    // Log to stderr for debugging (will appear in MCP client logs)
-    logging::error(format!("API error: {}", error));
+    tracing::warn!(kind = ?error.kind(), %error, "API error");
+
+    let mut data = serde_json::json!({
+        "kind": error.kind(),
+        "retryable": error.is_retryable(),
+    });
+    if let Some(status) = error.status() {
+        data["status"] = status.as_u16().into();
+    }
+    if let Some(suggestion) = error.suggestion() {
+        data["suggestion"] = suggestion.into();
+    }
+    if let ApiError::RateLimited {
+        retry_after: Some(wait),
+        ..
+    } = &error
+    {
+        data["retryAfterSeconds"] = wait.as_secs().into();
+    }
+    let data = Some(data);
 
     match error {
         ApiError::HttpError { status, body } => {
@@ -41,63 +115,186 @@ fn api_error_to_mcp(error: ApiError) -> McpError {
                 // 4xx = client mistakes (bad region code, invalid params, etc.)
                 McpError::invalid_params(
                     format!("eBird API request failed ({}): {}", status, body),
-                    None,
+                    data,
                 )
             } else {
                 McpError::internal_error(
                     format!("eBird API server error ({}): {}", status, body),
-                    None,
+                    data,
                 )
             }
         }
+        ApiError::UnknownRegion { .. } | ApiError::NotFound { .. } => {
+            McpError::invalid_params(error.to_string(), data)
+        }
+        ApiError::Unauthorized { .. }
+        | ApiError::RateLimited { .. }
+        | ApiError::Unavailable { .. } => McpError::internal_error(error.to_string(), data),
         ApiError::Network(e) => {
-            McpError::internal_error(format!("Failed to connect to eBird API: {}", e), None)
+            McpError::internal_error(format!("Failed to connect to eBird API: {}", e), data)
         }
         ApiError::Deserialization(e) => {
-            McpError::internal_error(format!("Failed to parse eBird API response: {}", e), None)
+            McpError::internal_error(format!("Failed to parse eBird API response: {}", e), data)
         }
         ApiError::Serialization(e) => {
-            McpError::invalid_params(format!("Invalid request parameters: {}", e), None)
+            McpError::invalid_params(format!("Invalid request parameters: {}", e), data)
         }
+        ApiError::InvalidRequest(reason) => McpError::invalid_params(reason, data),
+        ApiError::NotRecorded { .. } => McpError::internal_error(error.to_string(), data),
     }
 }
 
+fn lifelist_error_to_mcp(error: lifelist::LifeListError) -> McpError {
+    tracing::error!(%error, "Life list error");
+    McpError::internal_error(error.to_string(), None)
+}
+
+fn ebd_error_to_mcp(error: EbdError) -> McpError {
+    tracing::error!(%error, "EBD error");
+    match error {
+        EbdError::MissingColumn(_) | EbdError::InvalidQuery(_) | EbdError::Io(_) => {
+            McpError::invalid_params(error.to_string(), None)
+        }
+        _ => McpError::internal_error(error.to_string(), None),
+    }
+}
+
+fn frequency_error_to_mcp(error: FrequencyError) -> McpError {
+    match error {
+        FrequencyError::Api(e) => api_error_to_mcp(e),
+        FrequencyError::Ebd(e) => ebd_error_to_mcp(e),
+        FrequencyError::TooManyRequests(_) => McpError::invalid_params(error.to_string(), None),
+    }
+}
+
+/// Runs a blocking EBD store operation off the async runtime.
+async fn with_ebd_store<T, F>(operation: F) -> Result<T, McpError>
+where
+    T: Send + 'static,
+    F: FnOnce(EbdStore) -> Result<T, EbdError> + Send + 'static,
+{
+    let store = EbdStore::open_default().map_err(ebd_error_to_mcp)?;
+    tokio::task::spawn_blocking(move || operation(store))
+        .await
+        .map_err(|e| McpError::internal_error(format!("EBD task failed: {}", e), None))?
+        .map_err(ebd_error_to_mcp)
+}
+
 #[tool_router]
 impl RublClient {
-    pub fn new(api_key: String) -> Self {
-        Self {
-            tool_router: Self::tool_router(),
-            client: ApiClient::new(api_key),
+    /// Builds the server from the resolved configuration: disabled tools are
+    /// dropped and configured defaults are attached to the rest.
+    pub fn new(api_key: String, config: &Config) -> Result<Self, reqwest::Error> {
+        let mut client = ApiClient::with_timeouts(
+            api_key,
+            config.http.timeout(),
+            config.http.connect_timeout(),
+        )?
+        .with_max_concurrent_requests(config.http.max_concurrent_requests);
+        if let Some(base_url) = &config.http.base_url {
+            client = client.with_base_url(base_url);
         }
+        // The response cache would hide requests from the cassette.
+        let cache = match &config.cassette {
+            Some(cassette) => {
+                tracing::info!(mode = ?cassette.mode(), dir = %cassette.dir().display(), "Using cassette");
+                client = client.with_cassette(cassette.clone());
+                Cache::disabled()
+            }
+            None => Cache::open(&config.cache),
+        };
+        let mut tool_router = Self::tool_router();
+        tool_router
+            .map
+            .retain(|name, _| config.defaults.is_tool_enabled(name));
+        let defaults = ParameterDefaults::prepare(
+            &config.defaults,
+            tool_router.map.values_mut().map(|route| &mut route.attr),
+        );
+        Ok(Self {
+            tool_router,
+            watches: WatchManager::load(client.clone(), AlertDispatcher::load()),
+            poll_watches: true,
+            historic: HistoricFetcher {
+                client: client.clone(),
+                cache,
+                limiter: RateLimiter::per_second(API_REQUESTS_PER_SECOND),
+            },
+            client,
+            defaults: Arc::new(defaults),
+            format: config.defaults.format.unwrap_or_default(),
+            units: config.defaults.units.unwrap_or_default(),
+            transport: config.transport.kind,
+        })
+    }
+
+    /// Leaves persisted watches alone: no polling, state updates, or alerts.
+    /// For one-off runs that would otherwise race a long-running server.
+    pub fn without_watch_poller(mut self) -> Self {
+        self.poll_watches = false;
+        self
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_watches(mut self, watches: WatchManager) -> Self {
+        self.watches = watches;
+        self
+    }
+
+    pub fn api_client(&self) -> &ApiClient {
+        &self.client
+    }
+
+    async fn fetch<E>(&self, req: &E) -> Result<E::Response, McpError>
+    where
+        E: crate::api::endpoint::Endpoint,
+    {
+        self.client.send(req).await.map_err(api_error_to_mcp)
     }
 
     async fn handle_request<E>(&self, req: E) -> Result<CallToolResult, McpError>
     where
         E: crate::api::endpoint::Endpoint,
-        E::Response: ToContent,
+        E::Response: Serialize,
     {
-        let response = self
-            .client
-            .send(&req)
-            .await
-            .map_err(api_error_to_mcp)?
-            .to_content()
-            .map_err(|e| {
-                logging::error(format!("Content conversion error: {}", e));
-                McpError::internal_error(e.to_string(), None)
-            })?;
-        Ok(CallToolResult::success(vec![response]))
+        let response = self.fetch(&req).await?;
+        self.to_result(&response)
+    }
+
+    /// Presents notable sightings either as clustered events or raw reports.
+    fn rarity_result(
+        &self,
+        reports: Vec<RareBird>,
+        view: RarityView,
+    ) -> Result<CallToolResult, McpError> {
+        match view {
+            RarityView::Events => self.to_result(&events::cluster(reports)),
+            RarityView::Reports => self.to_result(&reports),
+        }
+    }
+
+    fn to_result<T: Serialize>(&self, value: &T) -> Result<CallToolResult, McpError> {
+        let content = match self.format {
+            OutputFormat::Toon => value.to_content(),
+            OutputFormat::Json => serde_json::to_string(value).map(Content::text),
+        };
+        let content = content.map_err(|e| {
+            tracing::error!(error = %e, "Content conversion error");
+            McpError::internal_error(e.to_string(), None)
+        })?;
+        Ok(CallToolResult::success(vec![content]))
     }
 
     #[tool(
-        description = "Fetch recently reported notable/rare bird sightings for an eBird region. Returns species, location, date, and count. Use for rarity alerts or recent notable observations.",
+        description = "Fetch recently reported notable/rare bird sightings for an eBird region. By default repeat reports of the same bird are grouped into events with first/last seen, report count, max count, centroid, and locations; set view to 'reports' for every report. Use for rarity alerts or recent notable observations.",
         annotations(title = "Rare birds", read_only_hint = true)
     )]
     async fn fetch_notable_recent(
         &self,
         Parameters(req): Parameters<FetchNotableRecentRequest>,
     ) -> Result<CallToolResult, McpError> {
-        self.handle_request(req).await
+        let reports = self.fetch(&req).await?;
+        self.rarity_result(reports, req.view)
     }
 
     #[tool(
@@ -122,6 +319,29 @@ impl RublClient {
         self.handle_request(req).await
     }
 
+    #[tool(
+        description = "Fetch recently reported notable/rare bird sightings within a radius of geographic coordinates. By default repeat reports of the same bird are grouped into events with first/last seen, report count, max count, centroid, and locations; set view to 'reports' for every report. Use for rarity searches near a specific place instead of querying a whole region.",
+        annotations(title = "Nearby rare birds", read_only_hint = true)
+    )]
+    async fn fetch_geo_notable(
+        &self,
+        Parameters(req): Parameters<FetchGeoNotableRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let reports = self.fetch(&req).await?;
+        self.rarity_result(reports, req.view)
+    }
+
+    #[tool(
+        description = "Fetch all recent reports of a specific species within a radius of geographic coordinates. Returns observations with location, date, and count. Use for tracking where a species has been seen around a place.",
+        annotations(title = "Nearby species reports", read_only_hint = true)
+    )]
+    async fn fetch_geo_species(
+        &self,
+        Parameters(req): Parameters<FetchGeoSpeciesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        self.handle_request(req).await
+    }
+
     #[tool(
         description = "Fetch birding hotspots for an eBird region. Returns hotspot names, coordinates, and recent activity statistics. Use for finding birding locations or exploring birding areas.",
         annotations(title = "Region hotspots", read_only_hint = true)
@@ -155,6 +375,63 @@ impl RublClient {
         self.handle_request(req).await
     }
 
+    #[tool(
+        description = "Everything to read before visiting one eBird hotspot: the info record, recent observations there, notable observations there, the all-time species list, the latest checklists, and sunrise and sunset (UTC) for today's date at the hotspot. Parts other than the info record are reported under errors if they fail.",
+        annotations(title = "Hotspot dossier", read_only_hint = true)
+    )]
+    async fn hotspot_dossier(
+        &self,
+        Parameters(req): Parameters<HotspotDossierRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let request = DossierRequest {
+            back: req.back(),
+            checklists: req.checklists(),
+            loc_id: req.loc_id,
+            now: chrono::Utc::now(),
+        };
+        let dossier = dossier::build(&self.client, &request)
+            .await
+            .map_err(api_error_to_mcp)?;
+        self.to_result(&dossier)
+    }
+
+    #[tool(
+        description = "Recommend where to go birding near a point. Scores nearby hotspots birded recently on recent species richness, notable sightings, how recently they were birded, all-time richness, distance, and optionally how many of your target species were reported there. Weights are adjustable. Each result explains its score factor by factor.",
+        annotations(title = "Recommend hotspots", read_only_hint = true)
+    )]
+    async fn recommend_hotspots(
+        &self,
+        Parameters(req): Parameters<RecommendHotspotsRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::from_context(&context);
+        let request = RecommendRequest {
+            lat: req.lat,
+            lng: req.lng,
+            dist: req.dist(),
+            back: req.back(),
+            weights: Weights::from(&req.weights),
+            limit: req.limit(),
+            targets: req.target_species,
+        };
+        let ranked = recommend::recommend(
+            &self.client,
+            &self.historic.limiter,
+            &request,
+            chrono::Local::now().naive_local(),
+            |done, total| {
+                progress.report(
+                    done,
+                    total,
+                    format!("Checked {} of {} hotspots", done, total),
+                )
+            },
+        )
+        .await
+        .map_err(api_error_to_mcp)?;
+        self.to_result(&ranked)
+    }
+
     #[tool(
         description = "Look up eBird region metadata (name, type, bounds, parent). Use when you need to resolve or validate a region code (e.g. US-NC) or get geographic bounds.",
         annotations(title = "Region info", read_only_hint = true)
@@ -200,7 +477,7 @@ impl RublClient {
     }
 
     #[tool(
-        description = "Fetch historic observations on a specific date in a region. Returns observations from exactly that date. Use for exploring what was seen on a particular day in the past.",
+        description = "Fetch historic observations on a specific date in a region or hotspot. By default returns the most recent report of each species per location that day; use rank, detail, categories, hotspot, include_provisional, max_results, locations, and spp_locale to change what is returned. Impossible or future dates are rejected. Use for exploring what was seen on a particular day in the past.",
         annotations(title = "Historic observations", read_only_hint = true)
     )]
     async fn fetch_historic(
@@ -210,6 +487,502 @@ impl RublClient {
         self.handle_request(req).await
     }
 
+    #[tool(
+        description = "Fetch historic observations for every day in a date range (up to 366 days) in a region or hotspot. Returns a per-species summary (first/last date, days detected, max count) or all observations merged. Days are fetched in parallel, rate limited, cached, and reported as progress.",
+        annotations(title = "Historic range", read_only_hint = true)
+    )]
+    async fn fetch_historic_range(
+        &self,
+        Parameters(req): Parameters<FetchHistoricRangeRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let (start, end) = req
+            .dates(chrono::Utc::now().date_naive())
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let progress = Progress::from_context(&context);
+        let days = self
+            .historic
+            .fetch_days(
+                &req.region_code,
+                historic::date_range(start, end),
+                req.concurrency(),
+                |done, total| {
+                    progress.report(done, total, format!("Fetched {} of {} days", done, total))
+                },
+            )
+            .await
+            .map_err(api_error_to_mcp)?;
+        match req.view {
+            HistoricRangeView::Summary => self.to_result(&historic::summarize(&days)),
+            HistoricRangeView::Observations => self.to_result(&historic::merge(days)),
+        }
+    }
+
+    #[tool(
+        description = "Export birding hotspots as GeoJSON, GPX waypoints, or KML placemarks for GIS tools and GPS units. Provide either an eBird region code or coordinates. Returns an embedded file resource.",
+        annotations(title = "Export hotspots", read_only_hint = true)
+    )]
+    async fn export_hotspots(
+        &self,
+        Parameters(req): Parameters<ExportHotspotsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let (slug, hotspots) = match (req.region_code, req.lat.zip(req.lng)) {
+            (Some(region_code), _) => {
+                let slug = format!("hotspots/{}", region_code);
+                let hotspots = self
+                    .fetch(&FetchRegionHotspotsRequest {
+                        region_code,
+                        back: req.back,
+                    })
+                    .await?;
+                (slug, hotspots)
+            }
+            (None, Some((lat, lng))) => {
+                let hotspots = self
+                    .fetch(&FetchNearbyHotspotsRequest {
+                        lat,
+                        lng,
+                        dist: req.dist,
+                        back: req.back,
+                    })
+                    .await?;
+                ("hotspots/nearby".to_string(), hotspots)
+            }
+            (None, None) => {
+                return Err(McpError::invalid_params(
+                    "Provide either region_code or both lat and lng",
+                    None,
+                ));
+            }
+        };
+        let body = export::export_features("eBird hotspots", &hotspots, req.format);
+        Ok(CallToolResult::success(vec![export::to_resource(
+            &slug, body, req.format,
+        )]))
+    }
+
+    #[tool(
+        description = "Export recent bird observations as GeoJSON, GPX waypoints, or KML placemarks for GIS tools and GPS units. Provide either an eBird region code or coordinates; set notable to export only rarities. Returns an embedded file resource.",
+        annotations(title = "Export observations", read_only_hint = true)
+    )]
+    async fn export_observations(
+        &self,
+        Parameters(req): Parameters<ExportObservationsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let (slug, observations) = match (req.region_code, req.lat.zip(req.lng)) {
+            (Some(region_code), _) if req.notable => {
+                let slug = format!("observations/{}/notable", region_code);
+                let observations = self
+                    .fetch(&FetchNotableRecentRequest {
+                        region_code,
+                        back: req.back,
+                        view: RarityView::Reports,
+                    })
+                    .await?;
+                (slug, observations)
+            }
+            (Some(region_code), _) => {
+                let slug = format!("observations/{}", region_code);
+                let observations = self
+                    .fetch(&FetchRegionRecentRequest {
+                        region_code,
+                        back: req.back,
+                    })
+                    .await?;
+                (slug, observations)
+            }
+            (None, Some((lat, lng))) if req.notable => {
+                let observations = self
+                    .fetch(&FetchGeoNotableRequest {
+                        lat,
+                        lng,
+                        dist: req.dist,
+                        back: req.back,
+                        view: RarityView::Reports,
+                    })
+                    .await?;
+                ("observations/nearby/notable".to_string(), observations)
+            }
+            (None, Some((lat, lng))) => {
+                let observations = self
+                    .fetch(&FetchGeoRecentRequest {
+                        lat,
+                        lng,
+                        dist: req.dist,
+                        back: req.back,
+                    })
+                    .await?;
+                ("observations/nearby".to_string(), observations)
+            }
+            (None, None) => {
+                return Err(McpError::invalid_params(
+                    "Provide either region_code or both lat and lng",
+                    None,
+                ));
+            }
+        };
+        let body = export::export_features("eBird observations", &observations, req.format);
+        Ok(CallToolResult::success(vec![export::to_resource(
+            &slug, body, req.format,
+        )]))
+    }
+
+    #[tool(
+        description = "Export a birding itinerary (ordered list of stops) as a GPX route, GeoJSON line, or KML path for GPS units and mapping apps. Use after planning a trip to hand the route to navigation tools.",
+        annotations(title = "Export itinerary", read_only_hint = true)
+    )]
+    async fn export_itinerary(
+        &self,
+        Parameters(req): Parameters<ExportItineraryRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if req.stops.is_empty() {
+            return Err(McpError::invalid_params(
+                "Itinerary must contain at least one stop",
+                None,
+            ));
+        }
+        let body = export::export_route(&req.name, &req.stops, req.format);
+        Ok(CallToolResult::success(vec![export::to_resource(
+            "itinerary",
+            body,
+            req.format,
+        )]))
+    }
+
+    #[tool(
+        description = "Watch a region or a radius around a point for new notable/rare bird sightings. rubl polls eBird in the background and notifies the client (resources/updated on rubl://watches/{id}, or a log message) when a new rarity appears. Watches persist across restarts.",
+        annotations(title = "Watch for rarities", read_only_hint = false)
+    )]
+    async fn watch_notable(
+        &self,
+        peer: Peer<RoleServer>,
+        Parameters(req): Parameters<WatchNotableRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(target) = req.target() else {
+            return Err(McpError::invalid_params(
+                "Provide either region_code or both lat and lng",
+                None,
+            ));
+        };
+        self.watches.attach(peer);
+        let summary = self
+            .watches
+            .add(target, req.back(), req.interval_minutes())
+            .await
+            .map_err(api_error_to_mcp)?;
+        self.to_result(&summary)
+    }
+
+    #[tool(
+        description = "List active rare-bird watches with their resource URIs, poll schedule, and number of new sightings found.",
+        annotations(title = "List watches", read_only_hint = true)
+    )]
+    async fn list_watches(&self) -> Result<CallToolResult, McpError> {
+        self.to_result(&self.watches.list())
+    }
+
+    #[tool(
+        description = "Stop a rare-bird watch by ID.",
+        annotations(title = "Remove watch", read_only_hint = false, idempotent_hint = true)
+    )]
+    async fn unwatch(
+        &self,
+        Parameters(req): Parameters<UnwatchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.watches.remove(&req.id).await {
+            return Err(McpError::invalid_params(
+                format!("No watch with ID {}", req.id),
+                None,
+            ));
+        }
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Removed watch {}",
+            req.id
+        ))]))
+    }
+
+    #[tool(
+        description = "Import the user's personal eBird life list so lifer searches know what they have already seen. Accepts an eBird 'Download My Data' CSV (by file path or contents) and/or a list of species codes. Merges into the stored list unless replace is set.",
+        annotations(title = "Import life list", read_only_hint = false)
+    )]
+    async fn import_life_list(
+        &self,
+        Parameters(req): Parameters<ImportLifeListRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let csv = match (req.csv, req.csv_path) {
+            (Some(csv), _) => Some(csv),
+            (None, Some(path)) => Some(tokio::fs::read_to_string(&path).await.map_err(|e| {
+                McpError::invalid_params(format!("Failed to read {}: {}", path, e), None)
+            })?),
+            (None, None) => None,
+        };
+        if csv.is_none() && req.species_codes.is_none() {
+            return Err(McpError::invalid_params(
+                "Provide csv_path, csv, or species_codes",
+                None,
+            ));
+        }
+
+        let mut life_list = LifeList::load().map_err(lifelist_error_to_mcp)?;
+        if req.replace {
+            life_list.clear();
+        }
+        let mut added = 0;
+        if let Some(csv) = csv {
+            added += life_list
+                .import_csv(&csv)
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        }
+        if let Some(codes) = req.species_codes {
+            added += life_list.import_codes(codes);
+        }
+        life_list.save().map_err(lifelist_error_to_mcp)?;
+
+        self.to_result(&LifeListImport {
+            species_added: added,
+            life_list_size: life_list.len(),
+        })
+    }
+
+    #[tool(
+        description = "Find potential lifers nearby: recent eBird sightings of species not on the user's imported life list, grouped by location and ranked by how many needed species each location has. Provide a region code or coordinates. Requires import_life_list first.",
+        annotations(title = "Find lifers", read_only_hint = true)
+    )]
+    async fn find_lifers(
+        &self,
+        Parameters(req): Parameters<FindLifersRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let life_list = LifeList::load().map_err(lifelist_error_to_mcp)?;
+        if life_list.is_empty() {
+            return Err(McpError::invalid_params(
+                "No life list imported yet; call import_life_list first",
+                None,
+            ));
+        }
+        let limit = req.limit();
+        let observations = match (req.region_code, req.lat.zip(req.lng)) {
+            (Some(region_code), _) => {
+                self.fetch(&FetchRegionRecentRequest {
+                    region_code,
+                    back: req.back,
+                })
+                .await?
+            }
+            (None, Some((lat, lng))) => {
+                self.fetch(&FetchGeoRecentRequest {
+                    lat,
+                    lng,
+                    dist: req.dist,
+                    back: req.back,
+                })
+                .await?
+            }
+            (None, None) => {
+                return Err(McpError::invalid_params(
+                    "Provide either region_code or both lat and lng",
+                    None,
+                ));
+            }
+        };
+        self.to_result(&lifelist::find_lifers(&life_list, observations, limit))
+    }
+
+    #[tool(
+        description = "Import eBird Basic Dataset (EBD) extracts into the local offline store. Takes paths to an observation extract and/or a sampling-event extract (tab-separated, optionally gzipped). Files can be many GB; rows already imported are replaced, so re-running an import is safe.",
+        annotations(title = "Import EBD extract", read_only_hint = false)
+    )]
+    async fn import_ebd(
+        &self,
+        Parameters(req): Parameters<ImportEbdRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if req.observations_path.is_none() && req.sampling_path.is_none() {
+            return Err(McpError::invalid_params(
+                "Provide observations_path and/or sampling_path",
+                None,
+            ));
+        }
+        let progress = Progress::from_context(&context);
+        let summaries = with_ebd_store(move |store| {
+            let report = |name: &str| {
+                let progress = &progress;
+                let name = name.to_string();
+                move |done: usize, total: usize| {
+                    progress.report(
+                        done,
+                        total,
+                        format!("Read {} of {} MB of {}", done >> 20, total >> 20, name),
+                    )
+                }
+            };
+            let mut summaries = Vec::new();
+            if let Some(path) = req.observations_path {
+                summaries.push(store.import_observations(path.as_ref(), report(&path))?);
+            }
+            if let Some(path) = req.sampling_path {
+                summaries.push(store.import_sampling(path.as_ref(), report(&path))?);
+            }
+            Ok(summaries)
+        })
+        .await?;
+        self.to_result(&summaries)
+    }
+
+    #[tool(
+        description = "Query the local offline EBD store for observations over any date range, optionally filtered by region or location code and species name. Returns the same observation shape as the live tools with no network access. Requires import_ebd first.",
+        annotations(title = "Query offline EBD", read_only_hint = true)
+    )]
+    async fn query_ebd(
+        &self,
+        Parameters(req): Parameters<QueryEbdRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let filter = req.filter();
+        let observations = with_ebd_store(move |store| {
+            if !store.exists() {
+                return Err(EbdError::InvalidQuery(
+                    "No EBD data imported yet; call import_ebd first".into(),
+                ));
+            }
+            store.query(&filter)
+        })
+        .await?;
+        self.to_result(&observations)
+    }
+
+    #[tool(
+        description = "Show what the local offline EBD store contains: where it lives, how many observations and checklists have been imported, and the date range they cover.",
+        annotations(title = "EBD store status", read_only_hint = true)
+    )]
+    async fn ebd_status(&self) -> Result<CallToolResult, McpError> {
+        let stats = with_ebd_store(|store| store.stats()).await?;
+        self.to_result(&stats)
+    }
+
+    #[tool(
+        description = "Check the server's health: whether the eBird API key is accepted, whether eBird is reachable and how long a request takes, the eBird taxonomy version in use, the response cache location and size, the server version, and the transport. Use when tool calls fail unexpectedly.",
+        annotations(title = "Server diagnostics", read_only_hint = true)
+    )]
+    async fn rubl_diagnostics(&self) -> Result<CallToolResult, McpError> {
+        let report = Diagnostics {
+            version: env!("CARGO_PKG_VERSION"),
+            transport: self.transport.as_str(),
+            api: diagnostics::probe(&self.client).await,
+            cache: self.historic.cache.status(),
+        };
+        self.to_result(&report)
+    }
+
+    #[tool(
+        description = "Find first-of-season arrivals and departures in a region or hotspot: species reported in the last few days that were not reported in the preceding weeks, and species reported regularly in those weeks but not recently. Returns species, first (or last) date, location, and count. Use during migration to see what just arrived.",
+        annotations(title = "Arrivals and departures", read_only_hint = true)
+    )]
+    async fn find_arrivals(
+        &self,
+        Parameters(req): Parameters<FindArrivalsRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let today = chrono::Utc::now().date_naive();
+        let window_start = today - chrono::Days::new(req.recent_days() as u64 - 1);
+        let baseline_start = window_start - chrono::Days::new(req.baseline_days() as u64);
+        let progress = Progress::from_context(&context);
+        let mut days = self
+            .historic
+            .fetch_days(
+                &req.region_code,
+                historic::date_range(baseline_start, today),
+                historic::DEFAULT_CONCURRENCY,
+                |done, total| {
+                    progress.report(done, total, format!("Fetched {} of {} days", done, total))
+                },
+            )
+            .await
+            .map_err(api_error_to_mcp)?;
+        let recent = days.split_off(req.baseline_days() as usize);
+
+        let (arrivals, mut departures) = arrivals::detect(&recent, &days, req.min_baseline_days());
+        if !req.include_departures {
+            departures.clear();
+        }
+        let day_before = window_start - chrono::Days::new(1);
+        self.to_result(&ArrivalReport {
+            region_code: req.region_code,
+            window: format!("{} to {}", window_start, today),
+            baseline: format!("{} to {}", baseline_start, day_before),
+            arrivals,
+            departures,
+        })
+    }
+
+    #[tool(
+        description = "Everything about one species in one call: taxonomy (common and scientific names, family, order, category, banding codes), its most recent sightings in a region, the nearest sighting to a point, and how many locations reported it in each subregion (state or county). Parts are fetched concurrently; if one fails, the others are still returned with an error noted for the failed part.",
+        annotations(title = "Species profile", read_only_hint = true)
+    )]
+    async fn species_profile(
+        &self,
+        Parameters(req): Parameters<SpeciesProfileRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = Progress::from_context(&context);
+        let request = ProfileRequest {
+            point: req.point(),
+            back: req.back(),
+            species_code: req.species_code,
+            region_code: req.region_code,
+        };
+        let profile = profile::build(
+            &self.client,
+            &self.historic.limiter,
+            &request,
+            |done, total| {
+                progress.report(
+                    done,
+                    total,
+                    format!("Counted {} of {} subregions", done, total),
+                )
+            },
+        )
+        .await
+        .map_err(api_error_to_mcp)?;
+        self.to_result(&profile)
+    }
+
+    #[tool(
+        description = "Plan a chase for a rare bird: finds the nearest recent sighting (or the location you give, e.g. from a rarity event), when it was last reported, the distance and drive time from you, and whether checklists submitted there since then found or missed it. Returns a 0-100 reliability score with a go / possible / unlikely recommendation, plus other recent locations.",
+        annotations(title = "Plan a rarity chase", read_only_hint = true)
+    )]
+    async fn plan_chase(
+        &self,
+        Parameters(req): Parameters<PlanChaseRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let req = req.into_chase();
+        let now = chrono::Local::now().naive_local();
+        match chase::plan(&self.client, &req, now)
+            .await
+            .map_err(api_error_to_mcp)?
+        {
+            Some(plan) => self.to_result(&plan),
+            None => Ok(CallToolResult::success(vec![Content::text(format!(
+                "No reports of {} in the last {} days near the given location.",
+                req.species_code, req.back
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "Seasonal occurrence frequencies in eBird's 48-week bar-chart layout (four weeks per month) for a region or hotspot: the share of samples each species was reported on. Use for questions like 'what should I expect here in the second week of May'. Built from sampled historic days, sampled complete checklists, or the imported EBD; results are cached. Narrow with month/week to keep API sources fast.",
+        annotations(title = "Seasonal frequencies", read_only_hint = true)
+    )]
+    async fn get_frequencies(
+        &self,
+        Parameters(req): Parameters<GetFrequenciesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let query = req.query().map_err(|e| McpError::invalid_params(e, None))?;
+        let table = frequency::build(&self.historic, &query)
+            .await
+            .map_err(frequency_error_to_mcp)?;
+        self.to_result(&table.report(&query, &req.report_options()))
+    }
+
     #[tool(
         description = "Get guidelines and best practices for planning birding trips/itineraries. Returns structured 
 recommendations on optimal timing, location sequencing, breaks, pacing, and 
@@ -231,21 +1004,168 @@ optimal stop sequencing, break timing, observation methodology, and pacing strat
     }
 }
 
-#[tool_handler]
 impl ServerHandler for RublClient {
+    /// Runs each tool call in a `tool_call` span, logs how it went, and
+    /// records it in the tool metrics.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let mut request = request;
+        self.defaults.apply(&request.name, &mut request.arguments);
+        let tool = request.name.clone();
+        let span = tracing::info_span!("tool_call", tool = %tool);
+        let in_flight = InFlight::start("rubl_tool_calls_in_flight");
+        let started = Instant::now();
+        let context = ToolCallContext::new(self, request, context);
+        let result = self
+            .tool_router
+            .call(context)
+            .instrument(span.clone())
+            .await;
+        let elapsed = started.elapsed();
+        drop(in_flight);
+        let outcome = match &result {
+            Ok(output) if output.is_error == Some(true) => "tool_error",
+            Ok(_) => "ok",
+            Err(_) => "error",
+        };
+        // Unknown names share one series so clients can't mint new ones.
+        let label = if self.tool_router.has_route(&tool) {
+            &tool
+        } else {
+            "unknown"
+        };
+        telemetry::tool_call(label, outcome, elapsed);
+        let latency_ms = elapsed.as_millis() as u64;
+        span.in_scope(|| match &result {
+            Ok(output) if output.is_error == Some(true) => {
+                tracing::warn!(latency_ms, "Tool call returned an error")
+            }
+            Ok(_) => tracing::info!(latency_ms, "Tool call finished"),
+            Err(error) => {
+                tracing::warn!(latency_ms, code = error.code.0, error = %error.message, "Tool call failed")
+            }
+        });
+        result
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        logging::set_client_level(request.level);
+        Ok(())
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            instructions: Some(
-                "eBird API tools: region hierarchy (get_region_info, list_subregions), notable sightings (fetch_rare), and nearby birding locations (get_nearby_hotspots). \
-                 Region codes are like US, US-NC, US-NC-067. All tools are read-only."
-                    .into(),
-            ),
+            instructions: Some(match self.units {
+                Units::Km => INSTRUCTIONS.to_string(),
+                Units::Mi => format!(
+                    "{} Tool distances are in kilometers, but the user prefers miles; convert before presenting them.",
+                    INSTRUCTIONS
+                ),
+            }),
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_resources_list_changed()
+                .enable_logging()
                 .build(),
             ..Default::default()
         }
     }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        logging::attach_client(context.peer.clone());
+        if self.poll_watches {
+            self.watches.attach(context.peer);
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let resources = self
+            .watches
+            .list()
+            .into_iter()
+            .map(|watch| {
+                let mut resource = RawResource::new(watch.uri, format!("Watch {}", watch.id));
+                resource.description = Some(format!("New notable sightings for {}", watch.target));
+                resource.mime_type = Some("application/json".into());
+                resource.no_annotation()
+            })
+            .collect();
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let watch = self.watches.get_by_uri(&request.uri).ok_or_else(|| {
+            McpError::resource_not_found(format!("Unknown resource: {}", request.uri), None)
+        })?;
+        let body = serde_json::json!({
+            "watch": WatchSummary::from(&watch),
+            "recent": watch.recent,
+        });
+        let text = serde_json::to_string_pretty(&body)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: request.uri,
+                mime_type: Some("application/json".into()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        if self.watches.get_by_uri(&request.uri).is_none() {
+            return Err(McpError::resource_not_found(
+                format!("Unknown resource: {}", request.uri),
+                None,
+            ));
+        }
+        self.watches.attach(context.peer);
+        self.watches.subscribe(request.uri);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.watches.unsubscribe(&request.uri);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -321,11 +1241,63 @@ mod tests {
                 .await
                 .unwrap_err();
 
-            let error = ApiError::Network(reqwest_error);
+            let error = ApiError::Network(Arc::new(reqwest_error));
             let mcp_error = api_error_to_mcp(error);
             assert!(mcp_error.message.contains("Failed to connect to eBird API"));
         }
 
+        #[test]
+        fn unknown_region_carries_kind_and_suggestion() {
+            let error = ApiError::from_response(
+                StatusCode::BAD_REQUEST,
+                r#"{"errors":[{"title":"Field regionCode: invalid region code"}]}"#.into(),
+                None,
+            );
+            let mcp_error = api_error_to_mcp(error);
+            assert_eq!(mcp_error.code, ErrorCode::INVALID_PARAMS);
+            assert!(mcp_error.message.contains("invalid region code"));
+            let data = mcp_error.data.unwrap();
+            assert_eq!(data["kind"], "unknown_region");
+            assert_eq!(data["retryable"], false);
+            assert_eq!(data["status"], 400);
+            assert!(
+                data["suggestion"]
+                    .as_str()
+                    .unwrap()
+                    .contains("get_region_info")
+            );
+        }
+
+        #[test]
+        fn rate_limit_is_retryable_internal_error() {
+            let error = ApiError::from_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                String::new(),
+                Some(std::time::Duration::from_secs(30)),
+            );
+            let mcp_error = api_error_to_mcp(error);
+            assert_eq!(mcp_error.code, ErrorCode::INTERNAL_ERROR);
+            let data = mcp_error.data.unwrap();
+            assert_eq!(data["kind"], "rate_limited");
+            assert_eq!(data["retryable"], true);
+            assert_eq!(data["retryAfterSeconds"], 30);
+        }
+
+        #[test]
+        fn invalid_key_is_not_retryable() {
+            let error =
+                ApiError::from_response(StatusCode::UNAUTHORIZED, "Unauthorized".into(), None);
+            let data = api_error_to_mcp(error).data.unwrap();
+            assert_eq!(data["kind"], "unauthorized");
+            assert_eq!(data["retryable"], false);
+            assert!(
+                data["suggestion"]
+                    .as_str()
+                    .unwrap()
+                    .contains("EBIRD_API_KEY")
+            );
+        }
+
         #[test]
         fn serialization_error_becomes_invalid_params() {
             // Create a serialization error by parsing invalid JSON
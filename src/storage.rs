@@ -0,0 +1,63 @@
+//! Local persistence for state that must survive restarts.
+//!
+//! Files live under `$RUBL_DATA_DIR`, falling back to the platform data
+//! directory (e.g. `~/.local/share/rubl`).
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Returns the directory rubl stores its state in, if one can be determined.
+pub fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("RUBL_DATA_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_dir().map(|dir| dir.join("rubl")))
+}
+
+/// Returns the path of a named state file inside the data directory.
+pub fn data_file(name: &str) -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(name))
+}
+
+/// Loads a JSON state file, returning `None` if it does not exist yet.
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> io::Result<Option<T>> {
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes a JSON state file atomically (write to a temp file, then rename).
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let bytes = serde_json::to_vec_pretty(value)?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_json_and_reports_missing_files() {
+        let dir = std::env::temp_dir().join(format!("rubl-storage-{}", std::process::id()));
+        let path = dir.join("nested").join("state.json");
+
+        let missing: Option<Vec<u32>> = load_json(&path).unwrap();
+        assert!(missing.is_none());
+
+        save_json(&path, &vec![1u32, 2, 3]).unwrap();
+        let loaded: Option<Vec<u32>> = load_json(&path).unwrap();
+        assert_eq!(loaded, Some(vec![1, 2, 3]));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
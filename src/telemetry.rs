@@ -0,0 +1,187 @@
+//! Prometheus metrics.
+//!
+//! Nothing is recorded unless `RUBL_METRICS_ADDR` is set (for example
+//! `127.0.0.1:9464`); rubl then serves the metrics at `/metrics` on that
+//! address alongside the MCP transport. The hooks are cheap no-ops otherwise.
+//!
+//! eBird endpoints are labelled by request type (`FetchRegionRecent`) rather
+//! than path, so region and species codes don't multiply the series.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder};
+
+use crate::api::error::ApiError;
+
+const DURATION_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+fn builder() -> Result<PrometheusBuilder, BuildError> {
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Suffix("_seconds".into()), DURATION_BUCKETS)
+}
+
+/// Starts the `/metrics` listener if `RUBL_METRICS_ADDR` asks for one.
+/// Must be called from within the Tokio runtime.
+pub fn init() -> Result<Option<SocketAddr>, String> {
+    let Ok(addr) = std::env::var("RUBL_METRICS_ADDR") else {
+        return Ok(None);
+    };
+    let addr: SocketAddr = addr
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid RUBL_METRICS_ADDR '{}': {}", addr, e))?;
+    builder()
+        .and_then(|builder| builder.with_http_listener(addr).install())
+        .map_err(|e| format!("Failed to start metrics listener on {}: {}", addr, e))?;
+    describe();
+    Ok(Some(addr))
+}
+
+fn describe() {
+    metrics::describe_counter!("rubl_tool_calls_total", "Tool calls by tool and outcome");
+    metrics::describe_histogram!(
+        "rubl_tool_call_duration_seconds",
+        metrics::Unit::Seconds,
+        "Tool call latency"
+    );
+    metrics::describe_gauge!("rubl_tool_calls_in_flight", "Tool calls being handled");
+    metrics::describe_counter!(
+        "rubl_ebird_requests_total",
+        "eBird API requests by endpoint and status"
+    );
+    metrics::describe_histogram!(
+        "rubl_ebird_request_duration_seconds",
+        metrics::Unit::Seconds,
+        "eBird API request latency"
+    );
+    metrics::describe_gauge!(
+        "rubl_ebird_requests_in_flight",
+        "eBird API requests awaiting a response"
+    );
+    metrics::describe_counter!("rubl_ebird_retries_total", "eBird API requests retried");
+    metrics::describe_counter!(
+        "rubl_ebird_requests_coalesced_total",
+        "eBird API requests answered by an identical one already in flight"
+    );
+    metrics::describe_counter!(
+        "rubl_cache_lookups_total",
+        "Response cache lookups by result"
+    );
+}
+
+/// Short, stable label for an endpoint type: `FetchRegionRecent`.
+pub fn endpoint_name<E>() -> &'static str {
+    let name = std::any::type_name::<E>();
+    let name = name.rsplit("::").next().unwrap_or(name);
+    name.strip_suffix("Request").unwrap_or(name)
+}
+
+/// Counts something in flight until dropped.
+pub struct InFlight(&'static str);
+
+impl InFlight {
+    pub fn start(gauge_name: &'static str) -> Self {
+        gauge!(gauge_name).increment(1.0);
+        Self(gauge_name)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        gauge!(self.0).decrement(1.0);
+    }
+}
+
+pub fn tool_call(tool: &str, outcome: &'static str, elapsed: Duration) {
+    counter!("rubl_tool_calls_total", "tool" => tool.to_string(), "outcome" => outcome)
+        .increment(1);
+    histogram!("rubl_tool_call_duration_seconds", "tool" => tool.to_string())
+        .record(elapsed.as_secs_f64());
+}
+
+/// Records one eBird request; `status` is the HTTP status when eBird
+/// answered and the error kind when it didn't.
+pub fn ebird_request(endpoint: &'static str, result: Result<u16, &ApiError>, elapsed: Duration) {
+    let status = match result {
+        Ok(status) => status.to_string(),
+        Err(error) => match error.status() {
+            Some(status) => status.as_u16().to_string(),
+            None => error.kind().as_str().to_string(),
+        },
+    };
+    counter!("rubl_ebird_requests_total", "endpoint" => endpoint, "status" => status).increment(1);
+    histogram!("rubl_ebird_request_duration_seconds", "endpoint" => endpoint)
+        .record(elapsed.as_secs_f64());
+}
+
+pub fn retry(endpoint: &'static str) {
+    counter!("rubl_ebird_retries_total", "endpoint" => endpoint).increment(1);
+}
+
+pub fn coalesced(endpoint: &'static str) {
+    counter!("rubl_ebird_requests_coalesced_total", "endpoint" => endpoint).increment(1);
+}
+
+pub fn cache_lookup(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    counter!("rubl_cache_lookups_total", "result" => result).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::observations::FetchRegionRecentRequest;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn endpoint_names_drop_module_and_suffix() {
+        assert_eq!(
+            endpoint_name::<FetchRegionRecentRequest>(),
+            "FetchRegionRecent"
+        );
+    }
+
+    #[test]
+    fn renders_hooks_as_prometheus_text() {
+        let recorder = builder().unwrap().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            let guard = InFlight::start("rubl_tool_calls_in_flight");
+            tool_call("fetch_rare", "ok", Duration::from_millis(120));
+            drop(guard);
+            ebird_request("FetchRegionRecent", Ok(200), Duration::from_millis(80));
+            let error = ApiError::from_response(StatusCode::NOT_FOUND, String::new(), None);
+            ebird_request("FetchRegionRecent", Err(&error), Duration::from_millis(40));
+            let error = ApiError::InvalidRequest("bad".into());
+            ebird_request("FetchRegionRecent", Err(&error), Duration::ZERO);
+            retry("FetchHistoric");
+            coalesced("FetchHotspotInfo");
+            cache_lookup(true);
+            cache_lookup(false);
+            cache_lookup(true);
+        });
+        let text = handle.render();
+        assert!(text.contains(r#"rubl_tool_calls_total{tool="fetch_rare",outcome="ok"} 1"#));
+        assert!(
+            text.contains(
+                r#"rubl_tool_call_duration_seconds_bucket{tool="fetch_rare",le="0.25"} 1"#
+            )
+        );
+        assert!(text.contains("rubl_tool_calls_in_flight 0"));
+        assert!(
+            text.contains(
+                r#"rubl_ebird_requests_total{endpoint="FetchRegionRecent",status="404"} 1"#
+            )
+        );
+        assert!(text.contains(
+            r#"rubl_ebird_requests_total{endpoint="FetchRegionRecent",status="invalid_request"} 1"#
+        ));
+        assert!(text.contains(r#"rubl_ebird_retries_total{endpoint="FetchHistoric"} 1"#));
+        assert!(
+            text.contains(r#"rubl_ebird_requests_coalesced_total{endpoint="FetchHotspotInfo"} 1"#)
+        );
+        assert!(text.contains(r#"rubl_cache_lookups_total{result="hit"} 2"#));
+    }
+}
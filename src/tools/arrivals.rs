@@ -0,0 +1,79 @@
+use crate::ids::RegionCode;
+use serde::Deserialize;
+
+const DEFAULT_RECENT_DAYS: u32 = 7;
+const DEFAULT_BASELINE_WEEKS: u32 = 3;
+const DEFAULT_MIN_BASELINE_DAYS: u32 = 3;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FindArrivalsRequest {
+    #[schemars(description = "eBird region code (e.g., US-NC) or hotspot location ID")]
+    pub region_code: RegionCode,
+    #[schemars(
+        description = "Length of the recent window in days, ending today (default 7)",
+        range(min = 1, max = 14)
+    )]
+    pub recent_days: Option<u32>,
+    #[schemars(
+        description = "Weeks before the window to compare against (default 3)",
+        range(min = 1, max = 8)
+    )]
+    pub baseline_weeks: Option<u32>,
+    #[schemars(
+        description = "Days a species must be reported in the baseline to count as departed when missing from the window (default 3)",
+        range(min = 1, max = 56)
+    )]
+    pub min_baseline_days: Option<u32>,
+    #[serde(default = "default_true")]
+    #[schemars(description = "Also report departures (default true)")]
+    pub include_departures: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl FindArrivalsRequest {
+    pub fn recent_days(&self) -> u32 {
+        self.recent_days.unwrap_or(DEFAULT_RECENT_DAYS).clamp(1, 14)
+    }
+
+    pub fn baseline_days(&self) -> u32 {
+        self.baseline_weeks
+            .unwrap_or(DEFAULT_BASELINE_WEEKS)
+            .clamp(1, 8)
+            * 7
+    }
+
+    pub fn min_baseline_days(&self) -> u32 {
+        self.min_baseline_days
+            .unwrap_or(DEFAULT_MIN_BASELINE_DAYS)
+            .max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_defaults_and_bounds() {
+        let req: FindArrivalsRequest =
+            serde_json::from_value(serde_json::json!({ "region_code": "US-NC" })).unwrap();
+        assert_eq!(req.recent_days(), 7);
+        assert_eq!(req.baseline_days(), 21);
+        assert_eq!(req.min_baseline_days(), 3);
+        assert!(req.include_departures);
+
+        let req: FindArrivalsRequest = serde_json::from_value(serde_json::json!({
+            "region_code": "US-NC",
+            "recent_days": 60,
+            "baseline_weeks": 0,
+            "include_departures": false
+        }))
+        .unwrap();
+        assert_eq!(req.recent_days(), 14);
+        assert_eq!(req.baseline_days(), 7);
+        assert!(!req.include_departures);
+    }
+}
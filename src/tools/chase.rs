@@ -0,0 +1,80 @@
+use serde::Deserialize;
+
+use crate::chase::ChaseRequest;
+use crate::ids::{LocId, SpeciesCode};
+
+const DEFAULT_BACK_DAYS: u32 = 7;
+const DEFAULT_SPEED_KMH: f64 = 60.0;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PlanChaseRequest {
+    #[schemars(description = "Species code of the target bird (e.g., rosgul for Ross's Gull)")]
+    pub species_code: SpeciesCode,
+    #[schemars(description = "Your latitude")]
+    pub lat: f64,
+    #[schemars(description = "Your longitude")]
+    pub lng: f64,
+    #[schemars(
+        description = "Hotspot or location ID to chase (e.g. from a rarity event); defaults to the nearest recent sighting"
+    )]
+    pub location_id: Option<LocId>,
+    #[schemars(
+        description = "Number of days back to look for reports (default 7)",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[schemars(
+        description = "Average travel speed in km/h for the ETA (default 60)",
+        range(min = 5, max = 130)
+    )]
+    pub speed_kmh: Option<f64>,
+}
+
+impl PlanChaseRequest {
+    pub(crate) fn into_chase(self) -> ChaseRequest {
+        ChaseRequest {
+            species_code: self.species_code,
+            location_id: self.location_id,
+            lat: self.lat,
+            lng: self.lng,
+            back: self.back.unwrap_or(DEFAULT_BACK_DAYS).clamp(1, 30),
+            speed_kmh: self
+                .speed_kmh
+                .unwrap_or(DEFAULT_SPEED_KMH)
+                .clamp(5.0, 130.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_defaults_and_bounds() {
+        let req: PlanChaseRequest = serde_json::from_value(serde_json::json!({
+            "species_code": "rosgul",
+            "lat": 35.0,
+            "lng": -76.0
+        }))
+        .unwrap();
+        let chase = req.into_chase();
+        assert_eq!(chase.back, 7);
+        assert_eq!(chase.speed_kmh, 60.0);
+        assert!(chase.location_id.is_none());
+
+        let req: PlanChaseRequest = serde_json::from_value(serde_json::json!({
+            "species_code": "rosgul",
+            "lat": 35.0,
+            "lng": -76.0,
+            "location_id": "L123",
+            "back": 90,
+            "speed_kmh": 1.0
+        }))
+        .unwrap();
+        let chase = req.into_chase();
+        assert_eq!(chase.back, 30);
+        assert_eq!(chase.speed_kmh, 5.0);
+        assert_eq!(chase.location_id.unwrap(), "L123");
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::api::endpoint::Endpoint;
+use crate::ids::{ObsDate, RegionCode, SubId};
+
+/// A checklist as listed in the daily checklist feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistSummary {
+    pub sub_id: String,
+    #[serde(default)]
+    pub loc_id: Option<String>,
+    pub obs_dt: String,
+    #[serde(default)]
+    pub obs_time: Option<String>,
+    #[serde(default)]
+    pub num_species: Option<u32>,
+}
+
+impl ChecklistSummary {
+    /// Parses the feed's "9 May 2023" date and optional "07:15" time.
+    pub fn observed_at(&self) -> Option<NaiveDateTime> {
+        let date = NaiveDate::parse_from_str(&self.obs_dt, "%d %b %Y").ok()?;
+        let time = self
+            .obs_time
+            .as_deref()
+            .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())
+            .unwrap_or(NaiveTime::MIN);
+        Some(date.and_time(time))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistObservation {
+    pub species_code: String,
+    #[serde(default)]
+    pub how_many_str: Option<String>,
+}
+
+/// A full checklist with every species reported on it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checklist {
+    pub sub_id: String,
+    #[serde(default)]
+    pub loc_id: Option<String>,
+    pub obs_dt: String,
+    /// Whether the observer reported every species they identified.
+    #[serde(default)]
+    pub all_obs_reported: bool,
+    #[serde(default)]
+    pub obs: Vec<ChecklistObservation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChecklistFeedQuery {
+    #[serde(rename = "maxResults", skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<u32>,
+}
+
+/// Checklists submitted on a date in a region or at a hotspot.
+#[derive(Debug)]
+pub struct FetchChecklistFeedRequest {
+    pub region_code: RegionCode,
+    pub date: ObsDate,
+    pub query: ChecklistFeedQuery,
+}
+
+impl Endpoint for FetchChecklistFeedRequest {
+    type Query = ChecklistFeedQuery;
+    type Response = Vec<ChecklistSummary>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        let date = self.date.date();
+        format!(
+            "product/lists/{}/{}/{}/{}",
+            self.region_code,
+            date.year(),
+            date.month(),
+            date.day()
+        )
+    }
+
+    fn query(&self) -> &Self::Query {
+        &self.query
+    }
+}
+
+/// The most recently submitted checklists in a region or at a hotspot.
+#[derive(Debug)]
+pub struct FetchRecentChecklistsRequest {
+    pub region_code: RegionCode,
+    pub query: ChecklistFeedQuery,
+}
+
+impl Endpoint for FetchRecentChecklistsRequest {
+    type Query = ChecklistFeedQuery;
+    type Response = Vec<ChecklistSummary>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        format!("product/lists/{}", self.region_code)
+    }
+
+    fn query(&self) -> &Self::Query {
+        &self.query
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchChecklistRequest {
+    pub sub_id: SubId,
+}
+
+impl Endpoint for FetchChecklistRequest {
+    type Query = ();
+    type Response = Checklist;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        format!("product/checklist/view/{}", self.sub_id)
+    }
+
+    fn query(&self) -> &Self::Query {
+        &()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod fetch_checklist_feed {
+        use super::*;
+
+        #[test]
+        fn path_includes_region_and_date() {
+            let req = FetchChecklistFeedRequest {
+                region_code: "L123456".parse().unwrap(),
+                date: "2023-05-09".parse().unwrap(),
+                query: ChecklistFeedQuery {
+                    max_results: Some(10),
+                },
+            };
+            assert_eq!(req.path(), "product/lists/L123456/2023/5/9");
+            assert_eq!(
+                serde_json::to_value(req.query()).unwrap(),
+                serde_json::json!({ "maxResults": 10 })
+            );
+        }
+    }
+
+    mod fetch_recent_checklists {
+        use super::*;
+
+        #[test]
+        fn path_has_no_date() {
+            let req = FetchRecentChecklistsRequest {
+                region_code: "L123456".parse().unwrap(),
+                query: ChecklistFeedQuery { max_results: None },
+            };
+            assert_eq!(req.path(), "product/lists/L123456");
+            assert_eq!(
+                serde_json::to_value(req.query()).unwrap(),
+                serde_json::json!({})
+            );
+        }
+
+        #[test]
+        fn deserializes_feed_entries() {
+            let feed: Vec<ChecklistSummary> = serde_json::from_value(serde_json::json!([{
+                "locId": "L123456",
+                "subId": "S1",
+                "userDisplayName": "A Birder",
+                "numSpecies": 31,
+                "obsDt": "9 May 2023",
+                "obsTime": "07:15"
+            }]))
+            .unwrap();
+            assert_eq!(feed[0].sub_id, "S1");
+            assert_eq!(
+                feed[0].observed_at().unwrap().to_string(),
+                "2023-05-09 07:15:00"
+            );
+        }
+    }
+
+    mod fetch_checklist {
+        use super::*;
+
+        #[test]
+        fn path_includes_submission_id() {
+            let req = FetchChecklistRequest {
+                sub_id: "S12345".parse().unwrap(),
+            };
+            assert_eq!(req.path(), "product/checklist/view/S12345");
+        }
+
+        #[test]
+        fn deserializes_checklist_view() {
+            let checklist: Checklist = serde_json::from_value(serde_json::json!({
+                "subId": "S12345",
+                "locId": "L1",
+                "obsDt": "2023-05-09 07:15",
+                "allObsReported": true,
+                "numSpecies": 2,
+                "obs": [
+                    { "speciesCode": "barswa", "howManyStr": "3" },
+                    { "speciesCode": "whimbr", "howManyStr": "X" }
+                ]
+            }))
+            .unwrap();
+            assert!(checklist.all_obs_reported);
+            assert_eq!(checklist.obs.len(), 2);
+            assert_eq!(checklist.obs[1].species_code, "whimbr");
+        }
+    }
+}
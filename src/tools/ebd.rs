@@ -0,0 +1,100 @@
+use serde::Deserialize;
+
+use crate::ebd::EbdFilter;
+use crate::ids::{ObsDate, RegionCode};
+
+const DEFAULT_LIMIT: usize = 1000;
+const MAX_LIMIT: usize = 10_000;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ImportEbdRequest {
+    #[schemars(
+        description = "Path to an EBD observation extract (ebd_*.txt or .txt.gz) on this machine"
+    )]
+    pub observations_path: Option<String>,
+    #[schemars(
+        description = "Path to a sampling-event extract (ebd_sampling_*.txt or .txt.gz) on this machine"
+    )]
+    pub sampling_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct QueryEbdRequest {
+    #[schemars(
+        description = "Country, subnational, or location code (e.g., US, US-NC, US-NC-063, L123456); omit or use world for everything imported"
+    )]
+    pub region_code: Option<RegionCode>,
+    #[schemars(description = "Common or scientific name (case-insensitive, exact match)")]
+    pub species: Option<String>,
+    #[schemars(description = "First date to include (YYYY-MM-DD)")]
+    pub start_date: ObsDate,
+    #[schemars(description = "Last date to include (YYYY-MM-DD, defaults to start_date)")]
+    pub end_date: Option<ObsDate>,
+    #[schemars(
+        description = "Maximum number of observations to return (default 1000)",
+        range(min = 1, max = 10000)
+    )]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    #[schemars(description = "Include records that have not been approved by eBird reviewers")]
+    pub include_unapproved: bool,
+}
+
+impl QueryEbdRequest {
+    pub(crate) fn filter(self) -> EbdFilter {
+        let start_date = self.start_date.date();
+        let end_date = self.end_date.map_or(start_date, |end| end.date());
+        EbdFilter {
+            // The store only holds what was imported, so world is no filter.
+            region_code: self
+                .region_code
+                .filter(|code| code.as_str() != "world")
+                .map(String::from),
+            species: self.species,
+            start_date,
+            end_date,
+            limit: self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            include_unapproved: self.include_unapproved,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(value: serde_json::Value) -> QueryEbdRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn end_date_defaults_to_start_date() {
+        let filter = request(serde_json::json!({ "start_date": "2023-05-15" })).filter();
+        assert_eq!(filter.start_date, filter.end_date);
+        assert_eq!(filter.limit, DEFAULT_LIMIT);
+        assert_eq!(filter.region_code, None);
+
+        let filter =
+            request(serde_json::json!({ "start_date": "2023-05-15", "region_code": "world" }))
+                .filter();
+        assert_eq!(filter.region_code, None);
+        assert!(!filter.include_unapproved);
+    }
+
+    #[test]
+    fn rejects_invalid_dates_and_clamps_limit() {
+        assert!(
+            serde_json::from_value::<QueryEbdRequest>(
+                serde_json::json!({ "start_date": "2023-02-30" })
+            )
+            .is_err()
+        );
+        let filter = request(serde_json::json!({
+            "start_date": "2023-01-01",
+            "end_date": "2023-12-31",
+            "limit": 50000
+        }))
+        .filter();
+        assert_eq!(filter.limit, MAX_LIMIT);
+    }
+}
@@ -0,0 +1,98 @@
+use serde::Deserialize;
+
+pub use crate::export::{ExportFormat, RoutePoint};
+use crate::ids::RegionCode;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ExportHotspotsRequest {
+    #[schemars(description = "eBird region code (e.g., US-NC). Use this or lat/lng.")]
+    pub region_code: Option<RegionCode>,
+    #[schemars(description = "Latitude (used when region_code is omitted)")]
+    pub lat: Option<f64>,
+    #[schemars(description = "Longitude (used when region_code is omitted)")]
+    pub lng: Option<f64>,
+    #[schemars(
+        description = "Radius in kilometers for coordinate searches",
+        range(min = 0, max = 500)
+    )]
+    pub dist: Option<f64>,
+    #[schemars(
+        description = "Only export hotspots visited up to back days ago",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Export format: geojson (default), gpx, or kml")]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ExportObservationsRequest {
+    #[schemars(description = "eBird region code (e.g., US-NC). Use this or lat/lng.")]
+    pub region_code: Option<RegionCode>,
+    #[schemars(description = "Latitude (used when region_code is omitted)")]
+    pub lat: Option<f64>,
+    #[schemars(description = "Longitude (used when region_code is omitted)")]
+    pub lng: Option<f64>,
+    #[schemars(
+        description = "Radius in kilometers for coordinate searches (max 50)",
+        range(min = 0, max = 50)
+    )]
+    pub dist: Option<u32>,
+    #[schemars(
+        description = "Number of days back to fetch observations",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[serde(default)]
+    #[schemars(description = "Export only notable/rare observations")]
+    pub notable: bool,
+    #[serde(default)]
+    #[schemars(description = "Export format: geojson (default), gpx, or kml")]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ExportItineraryRequest {
+    #[schemars(description = "Itinerary name (e.g., Saturday Jordan Lake loop)")]
+    pub name: String,
+    #[schemars(description = "Stops in visiting order")]
+    pub stops: Vec<RoutePoint>,
+    #[serde(default = "default_itinerary_format")]
+    #[schemars(description = "Export format: gpx (default), geojson, or kml")]
+    pub format: ExportFormat,
+}
+
+fn default_itinerary_format() -> ExportFormat {
+    ExportFormat::Gpx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hotspots_default_to_geojson() {
+        let req: ExportHotspotsRequest =
+            serde_json::from_value(serde_json::json!({ "region_code": "US-NC" })).unwrap();
+        assert_eq!(req.format, ExportFormat::GeoJson);
+        assert!(req.lat.is_none());
+    }
+
+    #[test]
+    fn itinerary_defaults_to_gpx() {
+        let req: ExportItineraryRequest = serde_json::from_value(serde_json::json!({
+            "name": "Saturday",
+            "stops": [{ "name": "Jordan Lake", "lat": 35.73, "lng": -79.01 }]
+        }))
+        .unwrap();
+        assert_eq!(req.format, ExportFormat::Gpx);
+        assert_eq!(req.stops.len(), 1);
+    }
+
+    #[test]
+    fn format_parses_lowercase() {
+        let format: ExportFormat = serde_json::from_str("\"kml\"").unwrap();
+        assert_eq!(format, ExportFormat::Kml);
+    }
+}
@@ -0,0 +1,209 @@
+use chrono::Datelike;
+use serde::Deserialize;
+
+pub use crate::frequency::FrequencySource;
+use crate::frequency::{FrequencyQuery, ReportOptions};
+use crate::ids::RegionCode;
+
+const DEFAULT_LIMIT: usize = 50;
+const DEFAULT_DAYS_PER_WEEK: u32 = 1;
+const DEFAULT_CHECKLISTS_PER_DAY: u32 = 5;
+/// Earliest year accepted; older records are too sparse to chart.
+const MIN_YEAR: i32 = 1800;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetFrequenciesRequest {
+    #[schemars(
+        description = "eBird region code (e.g., US-NC-063) or hotspot location ID (e.g., L123456)"
+    )]
+    pub region_code: RegionCode,
+    #[serde(default)]
+    #[schemars(
+        description = "Where samples come from: 'historic' (sampled days, default), 'checklists' (sampled complete checklists), or 'ebd' (every complete checklist in the imported EBD, offline)"
+    )]
+    pub source: FrequencySource,
+    #[schemars(
+        description = "First year to sample (default: a few years before end_year)",
+        range(min = 1800)
+    )]
+    pub start_year: Option<i32>,
+    #[schemars(
+        description = "Last year to sample (default: last year)",
+        range(min = 1800)
+    )]
+    pub end_year: Option<i32>,
+    #[schemars(
+        description = "Only compute this month (1-12); much cheaper for API sources",
+        range(min = 1, max = 12)
+    )]
+    pub month: Option<u32>,
+    #[schemars(
+        description = "Only compute this week of the month (1-4, where 4 runs from the 22nd to month end); requires month",
+        range(min = 1, max = 4)
+    )]
+    pub week: Option<u32>,
+    #[schemars(description = "Only report this species (common name, scientific name, or code)")]
+    pub species: Option<String>,
+    #[schemars(
+        description = "Hide species whose peak frequency is below this (0-1)",
+        range(min = 0.0, max = 1.0)
+    )]
+    pub min_frequency: Option<f64>,
+    #[schemars(
+        description = "Maximum number of species to return (default 50)",
+        range(min = 1, max = 1000)
+    )]
+    pub limit: Option<usize>,
+    #[schemars(
+        description = "Days sampled per week per year for API sources (default 1)",
+        range(min = 1, max = 7)
+    )]
+    pub days_per_week: Option<u32>,
+    #[schemars(
+        description = "Complete checklists read per sampled day for the checklists source (default 5)",
+        range(min = 1, max = 20)
+    )]
+    pub checklists_per_day: Option<u32>,
+}
+
+impl GetFrequenciesRequest {
+    pub(crate) fn query(&self) -> Result<FrequencyQuery, String> {
+        let weeks = match (self.month, self.week) {
+            (None, None) => 0..=47,
+            (Some(month @ 1..=12), None) => {
+                let first = (month as usize - 1) * 4;
+                first..=first + 3
+            }
+            (Some(month @ 1..=12), Some(week @ 1..=4)) => {
+                let week = (month as usize - 1) * 4 + week as usize - 1;
+                week..=week
+            }
+            (None, Some(_)) => return Err("week requires month".into()),
+            _ => return Err("month must be 1-12 and week 1-4".into()),
+        };
+
+        let span = match self.source {
+            FrequencySource::Historic => 3,
+            FrequencySource::Checklists => 1,
+            FrequencySource::Ebd => 0,
+        };
+        let this_year = chrono::Utc::now().year();
+        for year in [self.start_year, self.end_year].into_iter().flatten() {
+            if !(MIN_YEAR..=this_year).contains(&year) {
+                return Err(format!(
+                    "Years must be between {} and {}",
+                    MIN_YEAR, this_year
+                ));
+            }
+        }
+        let years = match (self.source, self.start_year, self.end_year) {
+            (FrequencySource::Ebd, None, None) => None,
+            (_, start, end) => {
+                let end = end.unwrap_or(this_year - 1);
+                let start = start.unwrap_or(end - span.max(1) + 1);
+                if start > end {
+                    return Err("start_year is after end_year".into());
+                }
+                Some(start..=end)
+            }
+        };
+
+        Ok(FrequencyQuery {
+            region_code: self.region_code.clone(),
+            source: self.source,
+            years,
+            weeks,
+            days_per_week: self
+                .days_per_week
+                .unwrap_or(DEFAULT_DAYS_PER_WEEK)
+                .clamp(1, 7),
+            checklists_per_day: self
+                .checklists_per_day
+                .unwrap_or(DEFAULT_CHECKLISTS_PER_DAY)
+                .clamp(1, 20),
+        })
+    }
+
+    pub(crate) fn report_options(&self) -> ReportOptions {
+        ReportOptions {
+            species: self.species.clone(),
+            min_frequency: self.min_frequency.unwrap_or(0.0),
+            limit: self.limit.unwrap_or(DEFAULT_LIMIT).max(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(value: serde_json::Value) -> GetFrequenciesRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn month_and_week_select_buckets() {
+        let query = request(serde_json::json!({ "region_code": "L1", "month": 5, "week": 2 }))
+            .query()
+            .unwrap();
+        assert_eq!(query.weeks, 17..=17);
+        assert_eq!(query.source, FrequencySource::Historic);
+
+        let query = request(serde_json::json!({ "region_code": "L1", "month": 12 }))
+            .query()
+            .unwrap();
+        assert_eq!(query.weeks, 44..=47);
+
+        assert!(
+            request(serde_json::json!({ "region_code": "L1", "week": 2 }))
+                .query()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn years_default_per_source() {
+        let query = request(serde_json::json!({ "region_code": "L1", "end_year": 2023 }))
+            .query()
+            .unwrap();
+        assert_eq!(query.years, Some(2021..=2023));
+
+        let query = request(serde_json::json!({ "region_code": "L1", "source": "ebd" }))
+            .query()
+            .unwrap();
+        assert_eq!(query.years, None);
+
+        assert!(
+            request(
+                serde_json::json!({ "region_code": "L1", "start_year": 2024, "end_year": 2023 })
+            )
+            .query()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn years_out_of_range_are_rejected() {
+        for (start, end) in [
+            (-2_000_000_000, 2023),
+            (2020, i32::MIN),
+            (1799, 2023),
+            (2020, 9999),
+        ] {
+            assert!(
+                request(serde_json::json!({
+                    "region_code": "L1",
+                    "start_year": start,
+                    "end_year": end,
+                }))
+                .query()
+                .is_err()
+            );
+        }
+        assert!(
+            request(serde_json::json!({ "region_code": "L1", "end_year": i32::MIN }))
+                .query()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::historic::DEFAULT_CONCURRENCY;
+use crate::ids::{ObsDate, RegionCode};
+
+const MAX_DAYS: i64 = 366;
+const MAX_CONCURRENCY: usize = 8;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoricRangeView {
+    /// One row per species with first/last date, days detected, and max count.
+    #[default]
+    Summary,
+    /// Every observation from every day, newest first.
+    Observations,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FetchHistoricRangeRequest {
+    #[schemars(description = "eBird region code (e.g., US-NC) or hotspot location ID")]
+    pub region_code: RegionCode,
+    #[schemars(description = "First date to fetch (YYYY-MM-DD)")]
+    pub start_date: ObsDate,
+    #[schemars(description = "Last date to fetch (YYYY-MM-DD, at most 366 days after start_date)")]
+    pub end_date: ObsDate,
+    #[serde(default)]
+    #[schemars(
+        description = "'summary' (default) for one row per species with first/last date, days detected, and max count; 'observations' for every observation merged"
+    )]
+    pub view: HistoricRangeView,
+    #[schemars(
+        description = "Days fetched in parallel (default 4)",
+        range(min = 1, max = 8)
+    )]
+    pub concurrency: Option<usize>,
+}
+
+impl FetchHistoricRangeRequest {
+    /// Validated (start, end) dates; the range may not reach past `today`.
+    pub fn dates(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let (start, end) = (self.start_date.date(), self.end_date.date());
+        if end < start {
+            return Err("end_date is before start_date".into());
+        }
+        if end > today {
+            return Err("end_date is in the future".into());
+        }
+        if (end - start).num_days() >= MAX_DAYS {
+            return Err(format!("Ranges are limited to {} days", MAX_DAYS));
+        }
+        Ok((start, end))
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+            .unwrap_or(DEFAULT_CONCURRENCY)
+            .clamp(1, MAX_CONCURRENCY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(start: &str, end: &str) -> serde_json::Result<FetchHistoricRangeRequest> {
+        serde_json::from_value(serde_json::json!({
+            "region_code": "US-NC",
+            "start_date": start,
+            "end_date": end,
+        }))
+    }
+
+    fn request(start: &str, end: &str) -> FetchHistoricRangeRequest {
+        parse(start, end).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        "2024-06-01".parse().unwrap()
+    }
+
+    #[test]
+    fn defaults_to_summary_view() {
+        let req = request("2023-05-01", "2023-05-31");
+        assert_eq!(req.view, HistoricRangeView::Summary);
+        assert_eq!(req.concurrency(), DEFAULT_CONCURRENCY);
+        assert!(req.dates(today()).is_ok());
+    }
+
+    #[test]
+    fn rejects_bad_ranges() {
+        assert!(parse("2023-02-30", "2023-03-01").is_err());
+        assert!(request("2023-05-02", "2023-05-01").dates(today()).is_err());
+        assert!(request("2024-05-01", "2024-06-02").dates(today()).is_err());
+        assert!(request("2022-01-01", "2023-01-02").dates(today()).is_err());
+        assert!(request("2023-01-01", "2023-12-31").dates(today()).is_ok());
+    }
+}
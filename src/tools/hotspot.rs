@@ -2,6 +2,7 @@ use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 use crate::api::endpoint::Endpoint;
+use crate::ids::{LocId, RegionCode, SpeciesCode};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Hotspot {
@@ -41,7 +42,7 @@ pub struct Hotspot {
 pub struct FetchRegionHotspotsRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
+    pub region_code: RegionCode,
     #[schemars(
         description = "Only fetch hotspots visited up to back days ago",
         range(min = 1, max = 30)
@@ -106,7 +107,7 @@ impl Endpoint for FetchNearbyHotspotsRequest {
 pub struct FetchHotspotInfoRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird hotspot location ID (e.g., L123456)")]
-    pub loc_id: String,
+    pub loc_id: LocId,
 }
 
 impl Endpoint for FetchHotspotInfoRequest {
@@ -128,6 +129,116 @@ impl Endpoint for FetchHotspotInfoRequest {
     }
 }
 
+/// Every species ever reported in a region or at a hotspot, as species codes.
+#[derive(Debug)]
+pub struct FetchSpeciesListRequest {
+    pub region_code: RegionCode,
+}
+
+impl Endpoint for FetchSpeciesListRequest {
+    type Query = ();
+    type Response = Vec<String>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        format!("product/spplist/{}", self.region_code)
+    }
+
+    fn query(&self) -> &Self::Query {
+        &()
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct HotspotDossierRequest {
+    #[schemars(description = "eBird hotspot location ID (e.g., L123456)")]
+    pub loc_id: LocId,
+    #[schemars(
+        description = "Number of days back for recent and notable observations (default 7)",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[schemars(
+        description = "Number of latest checklists to include (default 10)",
+        range(min = 1, max = 50)
+    )]
+    pub checklists: Option<u32>,
+}
+
+impl HotspotDossierRequest {
+    pub fn back(&self) -> u32 {
+        self.back.unwrap_or(7).clamp(1, 30)
+    }
+
+    pub fn checklists(&self) -> u32 {
+        self.checklists.unwrap_or(10).clamp(1, 50)
+    }
+}
+
+/// Relative weights for the hotspot score factors. Omitted factors keep
+/// their defaults; set one to 0 to ignore it.
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct ScoreWeightsInput {
+    #[schemars(description = "Species reported in the last `back` days (default 1.0)")]
+    pub richness: Option<f64>,
+    #[schemars(description = "Notable/rare species reported recently (default 1.0)")]
+    pub notable: Option<f64>,
+    #[schemars(description = "How recently the hotspot was last birded (default 0.5)")]
+    pub activity: Option<f64>,
+    #[schemars(description = "All-time species total (default 0.5)")]
+    pub all_time: Option<f64>,
+    #[schemars(description = "Closeness to the search point (default 1.0)")]
+    pub distance: Option<f64>,
+    #[schemars(description = "Share of target species reported recently (default 2.0)")]
+    pub targets: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RecommendHotspotsRequest {
+    #[schemars(description = "Latitude")]
+    pub lat: f64,
+    #[schemars(description = "Longitude")]
+    pub lng: f64,
+    #[schemars(
+        description = "Search radius in kilometers (default 25, max 50)",
+        range(min = 1, max = 50)
+    )]
+    pub dist: Option<u32>,
+    #[schemars(
+        description = "Number of days back for recent activity (default 7)",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[serde(default)]
+    #[schemars(
+        description = "Species codes you most want to see (e.g., [\"paibun\", \"swawar\"])"
+    )]
+    pub target_species: Vec<SpeciesCode>,
+    #[serde(default)]
+    #[schemars(description = "Relative factor weights")]
+    pub weights: ScoreWeightsInput,
+    #[schemars(
+        description = "Number of hotspots to return (default 5)",
+        range(min = 1, max = 20)
+    )]
+    pub limit: Option<u32>,
+}
+
+impl RecommendHotspotsRequest {
+    pub fn dist(&self) -> u32 {
+        self.dist.unwrap_or(25).clamp(1, 50)
+    }
+
+    pub fn back(&self) -> u32 {
+        self.back.unwrap_or(7).clamp(1, 30)
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(5).clamp(1, 20) as usize
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -138,7 +249,7 @@ mod tests {
         #[test]
         fn path_includes_region_code() {
             let req = FetchRegionHotspotsRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: None,
             };
             assert_eq!(req.path(), "ref/hotspot/US-NC");
@@ -147,7 +258,7 @@ mod tests {
         #[test]
         fn path_with_different_region() {
             let req = FetchRegionHotspotsRequest {
-                region_code: "US-CA".into(),
+                region_code: "US-CA".parse().unwrap(),
                 back: Some(7),
             };
             assert_eq!(req.path(), "ref/hotspot/US-CA");
@@ -156,7 +267,7 @@ mod tests {
         #[test]
         fn query_includes_back_parameter_when_present() {
             let req = FetchRegionHotspotsRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: Some(14),
             };
             let serialized = serde_json::to_value(req.query()).unwrap();
@@ -166,7 +277,7 @@ mod tests {
         #[test]
         fn format_returns_json() {
             let req = FetchRegionHotspotsRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: None,
             };
             assert_eq!(req.format(), Some("json"));
@@ -231,7 +342,7 @@ mod tests {
         #[test]
         fn path_includes_location_id() {
             let req = FetchHotspotInfoRequest {
-                loc_id: "L123456".into(),
+                loc_id: "L123456".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/hotspot/info/L123456");
         }
@@ -239,7 +350,7 @@ mod tests {
         #[test]
         fn path_with_different_location() {
             let req = FetchHotspotInfoRequest {
-                loc_id: "L789012".into(),
+                loc_id: "L789012".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/hotspot/info/L789012");
         }
@@ -247,7 +358,7 @@ mod tests {
         #[test]
         fn query_returns_self() {
             let req = FetchHotspotInfoRequest {
-                loc_id: "L123456".into(),
+                loc_id: "L123456".parse().unwrap(),
             };
             // FetchHotspotInfoRequest uses itself as query but with skip_serializing on loc_id
             let _query = req.query();
@@ -257,9 +368,54 @@ mod tests {
         #[test]
         fn format_returns_json() {
             let req = FetchHotspotInfoRequest {
-                loc_id: "L123456".into(),
+                loc_id: "L123456".parse().unwrap(),
             };
             assert_eq!(req.format(), Some("json"));
         }
     }
+
+    mod fetch_species_list {
+        use super::*;
+
+        #[test]
+        fn path_includes_location() {
+            let req = FetchSpeciesListRequest {
+                region_code: "L123456".parse().unwrap(),
+            };
+            assert_eq!(req.path(), "product/spplist/L123456");
+        }
+    }
+
+    mod hotspot_dossier {
+        use super::*;
+
+        #[test]
+        fn applies_defaults_and_bounds() {
+            let req: HotspotDossierRequest = serde_json::from_value(
+                serde_json::json!({ "loc_id": "L123456", "checklists": 500 }),
+            )
+            .unwrap();
+            assert_eq!(req.back(), 7);
+            assert_eq!(req.checklists(), 50);
+        }
+    }
+
+    mod recommend_hotspots {
+        use super::*;
+
+        #[test]
+        fn weights_are_optional() {
+            let req: RecommendHotspotsRequest = serde_json::from_value(serde_json::json!({
+                "lat": 35.0,
+                "lng": -78.0,
+                "weights": { "distance": 0.0 }
+            }))
+            .unwrap();
+            assert_eq!(req.dist(), 25);
+            assert_eq!(req.limit(), 5);
+            assert_eq!(req.weights.distance, Some(0.0));
+            assert_eq!(req.weights.richness, None);
+            assert!(req.target_species.is_empty());
+        }
+    }
 }
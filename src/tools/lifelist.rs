@@ -0,0 +1,70 @@
+use crate::ids::{RegionCode, SpeciesCode};
+use serde::Deserialize;
+
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ImportLifeListRequest {
+    #[schemars(
+        description = "Path to an eBird 'Download My Data' CSV export (MyEBirdData.csv) on this machine"
+    )]
+    pub csv_path: Option<String>,
+    #[schemars(description = "Contents of an eBird 'Download My Data' CSV export")]
+    pub csv: Option<String>,
+    #[schemars(description = "eBird species codes already seen (e.g., barswa, norcar)")]
+    pub species_codes: Option<Vec<SpeciesCode>>,
+    #[serde(default)]
+    #[schemars(description = "Replace the stored life list instead of adding to it")]
+    pub replace: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FindLifersRequest {
+    #[schemars(description = "eBird region code (e.g., US-NC). Use this or lat/lng.")]
+    pub region_code: Option<RegionCode>,
+    #[schemars(description = "Latitude (used when region_code is omitted)")]
+    pub lat: Option<f64>,
+    #[schemars(description = "Longitude (used when region_code is omitted)")]
+    pub lng: Option<f64>,
+    #[schemars(
+        description = "Search radius in kilometers (max 50)",
+        range(min = 0, max = 50)
+    )]
+    pub dist: Option<u32>,
+    #[schemars(
+        description = "Number of days back to search",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[schemars(
+        description = "Maximum number of locations to return (default 10)",
+        range(min = 1, max = 100)
+    )]
+    pub limit: Option<usize>,
+}
+
+impl FindLifersRequest {
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_defaults_to_merge() {
+        let req: ImportLifeListRequest =
+            serde_json::from_value(serde_json::json!({ "species_codes": ["barswa"] })).unwrap();
+        assert!(!req.replace);
+        assert!(req.csv_path.is_none());
+    }
+
+    #[test]
+    fn find_lifers_defaults_limit() {
+        let req: FindLifersRequest =
+            serde_json::from_value(serde_json::json!({ "region_code": "US-NC" })).unwrap();
+        assert_eq!(req.limit(), DEFAULT_LIMIT);
+    }
+}
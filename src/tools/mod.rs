@@ -1,4 +1,23 @@
+//! Request and response types, one module per area of the eBird API.
+//!
+//! Requests that map to a single eBird call implement
+//! [`Endpoint`](crate::api::endpoint::Endpoint) and can be sent with
+//! [`ApiClient::send`](crate::api::client::ApiClient::send). The rest
+//! describe rubl's composite tools, which make several calls or work
+//! offline; they are public so their parameters can be built and
+//! deserialized the same way.
+
+pub mod arrivals;
+pub mod chase;
+pub mod checklist;
+pub mod ebd;
+pub mod export;
+pub mod frequency;
+pub mod historic;
 pub mod hotspot;
+pub mod lifelist;
 pub mod observations;
 pub mod region;
+pub mod species;
 pub mod trip;
+pub mod watch;
@@ -1,9 +1,11 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 use crate::api::endpoint::Endpoint;
+use crate::ids::{ObsDate, RegionCode, SpeciesCode};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Observation {
     #[serde(rename = "comName")]
     pub common_name: String,
@@ -21,16 +23,85 @@ pub struct Observation {
     pub latitude: Option<f64>,
     #[serde(rename = "lng", default)]
     pub longitude: Option<f64>,
+    #[serde(rename = "speciesCode", default)]
+    pub species_code: Option<String>,
+    #[serde(rename = "subId", default)]
+    pub submission_id: Option<String>,
+    /// Present only when requested with `detail=full`.
+    #[serde(flatten)]
+    pub details: ObservationDetails,
+}
+
+/// Observer, checklist, and review fields eBird adds with `detail=full`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObservationDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checklist_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obs_reviewed: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub obs_valid: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_comments: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_rich_media: Option<bool>,
+}
+
+impl Observation {
+    /// Parses `obsDt`, which eBird gives as "YYYY-MM-DD HH:MM" or just the
+    /// date when no time was recorded.
+    pub fn observed_at(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.observation_date, "%Y-%m-%d %H:%M")
+            .or_else(|_| {
+                NaiveDate::parse_from_str(&self.observation_date, "%Y-%m-%d")
+                    .map(|d| d.and_time(NaiveTime::MIN))
+            })
+            .ok()
+    }
+
+    /// A minimal observation for tests; set other fields with struct update.
+    #[cfg(test)]
+    pub(crate) fn test(name: &str, location: &str, date: &str) -> Self {
+        Self {
+            common_name: name.into(),
+            scientific_name: name.into(),
+            location: location.into(),
+            observation_date: date.into(),
+            count: None,
+            location_id: None,
+            latitude: None,
+            longitude: None,
+            species_code: None,
+            submission_id: None,
+            details: ObservationDetails::default(),
+        }
+    }
 }
 
 // Type alias for notable/rare birds (same structure, semantically distinct).
 pub type RareBird = Observation;
 
+/// How rarity tools present their results.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RarityView {
+    /// Reports of the same bird grouped into one event each.
+    #[default]
+    Events,
+    /// Every individual report.
+    Reports,
+}
+
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct FetchRegionRecentRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
+    pub region_code: RegionCode,
     #[schemars(
         description = "Number of days back to fetch observations",
         range(min = 1, max = 30)
@@ -59,6 +130,11 @@ pub struct FetchGeoRecentRequest {
     pub lat: f64,
     #[schemars(description = "Longitude")]
     pub lng: f64,
+    #[schemars(
+        description = "Search radius in kilometers (max 50)",
+        range(min = 0, max = 50)
+    )]
+    pub dist: Option<u32>,
     #[schemars(
         description = "Number of days back to fetch observations",
         range(min = 1, max = 30)
@@ -81,16 +157,59 @@ impl Endpoint for FetchGeoRecentRequest {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FetchGeoNotableRequest {
+    #[schemars(description = "Latitude")]
+    pub lat: f64,
+    #[schemars(description = "Longitude")]
+    pub lng: f64,
+    #[schemars(
+        description = "Search radius in kilometers (max 50)",
+        range(min = 0, max = 50)
+    )]
+    pub dist: Option<u32>,
+    #[schemars(
+        description = "Number of days back to fetch notable observations",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[serde(default, skip_serializing)]
+    #[schemars(
+        description = "'events' (default) groups repeat reports of the same bird into one row; 'reports' lists every report"
+    )]
+    pub view: RarityView,
+}
+
+impl Endpoint for FetchGeoNotableRequest {
+    type Query = FetchGeoNotableRequest;
+    type Response = Vec<RareBird>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        "data/obs/geo/recent/notable".into()
+    }
+
+    fn query(&self) -> &Self::Query {
+        self
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct FetchNotableRecentRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
+    pub region_code: RegionCode,
     #[schemars(
         description = "Number of days back to fetch notable observations",
         range(min = 1, max = 30)
     )]
     pub back: Option<u32>,
+    #[serde(default, skip_serializing)]
+    #[schemars(
+        description = "'events' (default) groups repeat reports of the same bird into one row; 'reports' lists every report"
+    )]
+    pub view: RarityView,
 }
 
 impl Endpoint for FetchNotableRecentRequest {
@@ -112,10 +231,10 @@ impl Endpoint for FetchNotableRecentRequest {
 pub struct FetchSpeciesRecentRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
+    pub region_code: RegionCode,
     #[serde(skip_serializing)]
     #[schemars(description = "Species code (e.g., barswa for Barn Swallow)")]
-    pub species_code: String,
+    pub species_code: SpeciesCode,
     #[schemars(
         description = "Number of days back to fetch observations",
         range(min = 1, max = 30)
@@ -142,7 +261,7 @@ impl Endpoint for FetchSpeciesRecentRequest {
 pub struct FetchSpeciesNearestRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "Species code (e.g., barswa for Barn Swallow)")]
-    pub species_code: String,
+    pub species_code: SpeciesCode,
     #[schemars(description = "Latitude")]
     pub lat: f64,
     #[schemars(description = "Longitude")]
@@ -175,36 +294,243 @@ impl Endpoint for FetchSpeciesNearestRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
-pub struct FetchHistoricRequest {
+pub struct FetchGeoSpeciesRequest {
     #[serde(skip_serializing)]
-    #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
-    #[serde(skip_serializing)]
-    #[schemars(description = "Year (e.g., 2023)")]
-    pub year: u32,
+    #[schemars(description = "Species code (e.g., barswa for Barn Swallow)")]
+    pub species_code: SpeciesCode,
+    #[schemars(description = "Latitude")]
+    pub lat: f64,
+    #[schemars(description = "Longitude")]
+    pub lng: f64,
+    #[schemars(
+        description = "Search radius in kilometers (max 50)",
+        range(min = 0, max = 50)
+    )]
+    pub dist: Option<u32>,
+    #[schemars(
+        description = "Number of days back to fetch observations",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+}
+
+impl Endpoint for FetchGeoSpeciesRequest {
+    type Query = FetchGeoSpeciesRequest;
+    type Response = Vec<Observation>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        format!("data/obs/geo/recent/{}", self.species_code)
+    }
+
+    fn query(&self) -> &Self::Query {
+        self
+    }
+}
+
+/// Which observation of a species to report for each location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoricRank {
+    /// The latest observation of the day.
+    Mrec,
+    /// The first observation submitted.
+    Create,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ObservationDetail {
+    Simple,
+    Full,
+}
+
+/// eBird taxonomy categories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxonCategory {
+    Species,
+    Slash,
+    Issf,
+    Spuh,
+    Hybrid,
+    Domestic,
+    Form,
+    Intergrade,
+}
+
+impl AsRef<str> for TaxonCategory {
+    fn as_ref(&self) -> &str {
+        match self {
+            TaxonCategory::Species => "species",
+            TaxonCategory::Slash => "slash",
+            TaxonCategory::Issf => "issf",
+            TaxonCategory::Spuh => "spuh",
+            TaxonCategory::Hybrid => "hybrid",
+            TaxonCategory::Domestic => "domestic",
+            TaxonCategory::Form => "form",
+            TaxonCategory::Intergrade => "intergrade",
+        }
+    }
+}
+
+/// Serializes a list as the comma-separated value eBird expects.
+fn comma_separated<S, T>(values: &Option<Vec<T>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: AsRef<str>,
+{
+    let joined = values
+        .iter()
+        .flatten()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(",");
+    serializer.serialize_str(&joined)
+}
+
+/// Earliest date eBird accepts for historic queries.
+const FIRST_HISTORIC_YEAR: i32 = 1800;
+const MAX_HISTORIC_RESULTS: u32 = 10_000;
+const MAX_HISTORIC_LOCATIONS: usize = 50;
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FetchHistoricRequest {
     #[serde(skip_serializing)]
-    #[schemars(description = "Month (1-12)")]
-    pub month: u32,
+    #[schemars(description = "eBird region code (e.g., US-NC) or hotspot location ID")]
+    pub region_code: RegionCode,
     #[serde(skip_serializing)]
-    #[schemars(description = "Day (1-31)")]
-    pub day: u32,
+    #[schemars(description = "Date (YYYY-MM-DD); must not be in the future")]
+    pub date: ObsDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Which record to return per species and location: 'mrec' (most recent that day, default) or 'create' (first submitted)"
+    )]
+    pub rank: Option<HistoricRank>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "'simple' (default) or 'full' to include observer, checklist, and review details"
+    )]
+    pub detail: Option<ObservationDetail>,
+    #[serde(
+        default,
+        rename(serialize = "cat"),
+        serialize_with = "comma_separated",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(
+        description = "Only include these taxonomic categories (species, slash, issf, spuh, hybrid, domestic, form, intergrade); default all"
+    )]
+    pub categories: Option<Vec<TaxonCategory>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(description = "Only include observations from hotspots (default false)")]
+    pub hotspot: Option<bool>,
+    #[serde(
+        default,
+        rename(serialize = "includeProvisional"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(description = "Include observations not yet reviewed by eBird (default false)")]
+    pub include_provisional: Option<bool>,
+    #[serde(
+        default,
+        rename(serialize = "maxResults"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(
+        description = "Maximum number of observations to return (default all)",
+        range(min = 1, max = 10000)
+    )]
+    pub max_results: Option<u32>,
+    #[serde(
+        default,
+        rename(serialize = "r"),
+        serialize_with = "comma_separated",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(
+        description = "Only include observations from these location IDs or subregion codes (up to 50)"
+    )]
+    pub locations: Option<Vec<RegionCode>>,
+    #[serde(
+        default,
+        rename(serialize = "sppLocale"),
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(description = "Locale for common names (e.g., en, es, fr; default en)")]
+    pub spp_locale: Option<String>,
+}
+
+impl FetchHistoricRequest {
+    /// A request for one day with eBird's default options.
+    pub fn for_date(region_code: RegionCode, date: NaiveDate) -> Self {
+        Self {
+            region_code,
+            date: date.into(),
+            rank: None,
+            detail: None,
+            categories: None,
+            hotspot: None,
+            include_provisional: None,
+            max_results: None,
+            locations: None,
+            spp_locale: None,
+        }
+    }
 }
 
 impl Endpoint for FetchHistoricRequest {
-    type Query = ();
+    type Query = FetchHistoricRequest;
     type Response = Vec<Observation>;
 
     const METHOD: Method = Method::GET;
 
     fn path(&self) -> String {
+        let date = self.date.date();
         format!(
             "data/obs/{}/historic/{}/{}/{}",
-            self.region_code, self.year, self.month, self.day
+            self.region_code,
+            date.year(),
+            date.month(),
+            date.day()
         )
     }
 
     fn query(&self) -> &Self::Query {
-        &()
+        self
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let date = self.date.date();
+        if date.year() < FIRST_HISTORIC_YEAR {
+            return Err(format!("Historic data starts in {}", FIRST_HISTORIC_YEAR));
+        }
+        // Allow a day of slack for regions ahead of UTC.
+        if date > chrono::Utc::now().date_naive() + chrono::Days::new(1) {
+            return Err(format!("{} is in the future", date));
+        }
+        if self
+            .max_results
+            .is_some_and(|n| !(1..=MAX_HISTORIC_RESULTS).contains(&n))
+        {
+            return Err(format!(
+                "max_results must be between 1 and {}",
+                MAX_HISTORIC_RESULTS
+            ));
+        }
+        if let Some(locations) = &self.locations
+            && !(1..=MAX_HISTORIC_LOCATIONS).contains(&locations.len())
+        {
+            return Err(format!(
+                "locations must list between 1 and {} codes",
+                MAX_HISTORIC_LOCATIONS
+            ));
+        }
+        if self.categories.as_ref().is_some_and(Vec::is_empty) {
+            return Err("categories must not be empty".into());
+        }
+        Ok(())
     }
 }
 
@@ -212,13 +538,36 @@ impl Endpoint for FetchHistoricRequest {
 mod tests {
     use super::*;
 
+    mod observed_at {
+        use super::*;
+
+        fn observation(date: &str) -> Observation {
+            serde_json::from_value(serde_json::json!({
+                "comName": "Whimbrel",
+                "sciName": "Numenius phaeopus",
+                "locName": "Beach",
+                "obsDt": date
+            }))
+            .unwrap()
+        }
+
+        #[test]
+        fn parses_with_and_without_time() {
+            let with_time = observation("2023-05-09 07:15").observed_at().unwrap();
+            assert_eq!(with_time.to_string(), "2023-05-09 07:15:00");
+            let date_only = observation("2023-05-09").observed_at().unwrap();
+            assert_eq!(date_only.to_string(), "2023-05-09 00:00:00");
+            assert!(observation("yesterday").observed_at().is_none());
+        }
+    }
+
     mod fetch_region_recent {
         use super::*;
 
         #[test]
         fn path_includes_region_code() {
             let req = FetchRegionRecentRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: None,
             };
             assert_eq!(req.path(), "data/obs/US-NC/recent");
@@ -227,7 +576,7 @@ mod tests {
         #[test]
         fn path_with_different_region_code() {
             let req = FetchRegionRecentRequest {
-                region_code: "US-CA".into(),
+                region_code: "US-CA".parse().unwrap(),
                 back: None,
             };
             assert_eq!(req.path(), "data/obs/US-CA/recent");
@@ -236,7 +585,7 @@ mod tests {
         #[test]
         fn query_includes_back_parameter_when_present() {
             let req = FetchRegionRecentRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: Some(7),
             };
             // Verify the query can be serialized and contains back parameter
@@ -247,7 +596,7 @@ mod tests {
         #[test]
         fn query_omits_back_parameter_when_none() {
             let req = FetchRegionRecentRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: None,
             };
             let serialized = serde_json::to_value(req.query()).unwrap();
@@ -263,6 +612,7 @@ mod tests {
             let req = FetchGeoRecentRequest {
                 lat: 35.9132,
                 lng: -79.0558,
+                dist: None,
                 back: None,
             };
             assert_eq!(req.path(), "data/obs/geo/recent");
@@ -273,6 +623,7 @@ mod tests {
             let req = FetchGeoRecentRequest {
                 lat: 35.9132,
                 lng: -79.0558,
+                dist: None,
                 back: None,
             };
             let serialized = serde_json::to_value(req.query()).unwrap();
@@ -285,11 +636,24 @@ mod tests {
             let req = FetchGeoRecentRequest {
                 lat: 35.9132,
                 lng: -79.0558,
+                dist: None,
                 back: Some(14),
             };
             let serialized = serde_json::to_value(req.query()).unwrap();
             assert_eq!(serialized["back"], 14);
         }
+
+        #[test]
+        fn query_includes_dist_parameter_when_present() {
+            let req = FetchGeoRecentRequest {
+                lat: 35.9132,
+                lng: -79.0558,
+                dist: Some(10),
+                back: None,
+            };
+            let serialized = serde_json::to_value(req.query()).unwrap();
+            assert_eq!(serialized["dist"], 10);
+        }
     }
 
     mod fetch_notable_recent {
@@ -298,8 +662,9 @@ mod tests {
         #[test]
         fn path_includes_region_code_and_notable() {
             let req = FetchNotableRecentRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: None,
+                view: RarityView::Events,
             };
             assert_eq!(req.path(), "data/obs/US-NC/recent/notable");
         }
@@ -307,11 +672,75 @@ mod tests {
         #[test]
         fn query_includes_back_parameter() {
             let req = FetchNotableRecentRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
                 back: Some(3),
+                view: RarityView::Events,
             };
             let serialized = serde_json::to_value(req.query()).unwrap();
             assert_eq!(serialized["back"], 3);
+            assert!(serialized.get("view").is_none());
+        }
+    }
+
+    mod fetch_geo_notable {
+        use super::*;
+
+        #[test]
+        fn path_is_constant() {
+            let req = FetchGeoNotableRequest {
+                lat: 35.9132,
+                lng: -79.0558,
+                dist: None,
+                back: None,
+                view: RarityView::Events,
+            };
+            assert_eq!(req.path(), "data/obs/geo/recent/notable");
+        }
+
+        #[test]
+        fn query_includes_coordinates_and_radius() {
+            let req = FetchGeoNotableRequest {
+                lat: 35.9132,
+                lng: -79.0558,
+                dist: Some(25),
+                back: Some(3),
+                view: RarityView::Events,
+            };
+            let serialized = serde_json::to_value(req.query()).unwrap();
+            assert_eq!(serialized["lat"], 35.9132);
+            assert_eq!(serialized["lng"], -79.0558);
+            assert_eq!(serialized["dist"], 25);
+            assert_eq!(serialized["back"], 3);
+        }
+    }
+
+    mod fetch_geo_species {
+        use super::*;
+
+        #[test]
+        fn path_includes_species_code() {
+            let req = FetchGeoSpeciesRequest {
+                species_code: "whimbr".parse().unwrap(),
+                lat: 35.9132,
+                lng: -79.0558,
+                dist: None,
+                back: None,
+            };
+            assert_eq!(req.path(), "data/obs/geo/recent/whimbr");
+        }
+
+        #[test]
+        fn query_excludes_species_code() {
+            let req = FetchGeoSpeciesRequest {
+                species_code: "whimbr".parse().unwrap(),
+                lat: 35.9132,
+                lng: -79.0558,
+                dist: Some(10),
+                back: None,
+            };
+            let serialized = serde_json::to_value(req.query()).unwrap();
+            assert!(serialized.get("species_code").is_none());
+            assert_eq!(serialized["dist"], 10);
         }
     }
 
@@ -321,8 +750,8 @@ mod tests {
         #[test]
         fn path_includes_region_and_species() {
             let req = FetchSpeciesRecentRequest {
-                region_code: "US-NC".into(),
-                species_code: "barswa".into(),
+                region_code: "US-NC".parse().unwrap(),
+                species_code: "barswa".parse().unwrap(),
                 back: None,
             };
             assert_eq!(req.path(), "data/obs/US-NC/recent/barswa");
@@ -331,8 +760,8 @@ mod tests {
         #[test]
         fn path_with_different_species() {
             let req = FetchSpeciesRecentRequest {
-                region_code: "US-CA".into(),
-                species_code: "caltow".into(),
+                region_code: "US-CA".parse().unwrap(),
+                species_code: "caltow".parse().unwrap(),
                 back: Some(7),
             };
             assert_eq!(req.path(), "data/obs/US-CA/recent/caltow");
@@ -341,8 +770,8 @@ mod tests {
         #[test]
         fn query_serializes_back_parameter() {
             let req = FetchSpeciesRecentRequest {
-                region_code: "US-NC".into(),
-                species_code: "barswa".into(),
+                region_code: "US-NC".parse().unwrap(),
+                species_code: "barswa".parse().unwrap(),
                 back: Some(10),
             };
             let serialized = serde_json::to_value(req.query()).unwrap();
@@ -356,7 +785,7 @@ mod tests {
         #[test]
         fn path_includes_species_code() {
             let req = FetchSpeciesNearestRequest {
-                species_code: "barswa".into(),
+                species_code: "barswa".parse().unwrap(),
                 lat: 35.9132,
                 lng: -79.0558,
                 dist: None,
@@ -368,7 +797,7 @@ mod tests {
         #[test]
         fn query_includes_coordinates() {
             let req = FetchSpeciesNearestRequest {
-                species_code: "barswa".into(),
+                species_code: "barswa".parse().unwrap(),
                 lat: 35.9132,
                 lng: -79.0558,
                 dist: None,
@@ -382,7 +811,7 @@ mod tests {
         #[test]
         fn query_includes_optional_parameters() {
             let req = FetchSpeciesNearestRequest {
-                species_code: "barswa".into(),
+                species_code: "barswa".parse().unwrap(),
                 lat: 35.9132,
                 lng: -79.0558,
                 dist: Some(25),
@@ -397,49 +826,143 @@ mod tests {
     mod fetch_historic {
         use super::*;
 
+        fn region(code: &str) -> RegionCode {
+            code.parse().unwrap()
+        }
+
+        fn date(value: &str) -> NaiveDate {
+            value.parse().unwrap()
+        }
+
         #[test]
         fn path_formats_date_correctly() {
-            let req = FetchHistoricRequest {
-                region_code: "US-NC".into(),
-                year: 2023,
-                month: 1,
-                day: 15,
-            };
+            let req = FetchHistoricRequest::for_date(region("US-NC"), date("2023-01-15"));
             assert_eq!(req.path(), "data/obs/US-NC/historic/2023/1/15");
         }
 
         #[test]
         fn path_formats_single_digit_date() {
-            let req = FetchHistoricRequest {
-                region_code: "US-NC".into(),
-                year: 2023,
-                month: 5,
-                day: 3,
-            };
+            let req = FetchHistoricRequest::for_date(region("US-NC"), date("2023-05-03"));
             assert_eq!(req.path(), "data/obs/US-NC/historic/2023/5/3");
         }
 
         #[test]
         fn path_formats_double_digit_date() {
-            let req = FetchHistoricRequest {
-                region_code: "US-CA".into(),
-                year: 2022,
-                month: 12,
-                day: 31,
-            };
+            let req = FetchHistoricRequest::for_date(region("US-CA"), date("2022-12-31"));
             assert_eq!(req.path(), "data/obs/US-CA/historic/2022/12/31");
         }
 
         #[test]
-        fn query_returns_empty_tuple() {
-            let req = FetchHistoricRequest {
-                region_code: "US-NC".into(),
-                year: 2023,
-                month: 1,
-                day: 15,
-            };
-            // Ensure no query params are serialized (should be unit type)
-            assert_eq!(req.query(), &());
+        fn query_is_empty_by_default() {
+            let req = FetchHistoricRequest::for_date(region("US-NC"), date("2023-01-15"));
+            assert_eq!(req.path(), "data/obs/US-NC/historic/2023/1/15");
+            let serialized = serde_json::to_value(req.query()).unwrap();
+            assert_eq!(serialized, serde_json::json!({}));
+        }
+
+        #[test]
+        fn query_uses_ebird_parameter_names() {
+            let req: FetchHistoricRequest = serde_json::from_value(serde_json::json!({
+                "region_code": "us-nc",
+                "date": "2023-05-09",
+                "rank": "create",
+                "detail": "full",
+                "categories": ["species", "issf"],
+                "hotspot": true,
+                "include_provisional": true,
+                "max_results": 100,
+                "locations": ["L1", "L2"],
+                "spp_locale": "es"
+            }))
+            .unwrap();
+            assert_eq!(
+                serde_json::to_value(req.query()).unwrap(),
+                serde_json::json!({
+                    "rank": "create",
+                    "detail": "full",
+                    "cat": "species,issf",
+                    "hotspot": true,
+                    "includeProvisional": true,
+                    "maxResults": 100,
+                    "r": "L1,L2",
+                    "sppLocale": "es"
+                })
+            );
+        }
+
+        #[test]
+        fn full_detail_fields_come_through() {
+            let observations: Vec<Observation> = serde_json::from_value(serde_json::json!([{
+                "comName": "Whimbrel",
+                "sciName": "Numenius phaeopus",
+                "locName": "Beach",
+                "obsDt": "2023-05-09 07:15",
+                "checklistId": "CL24936",
+                "userDisplayName": "Jo Birder",
+                "obsReviewed": true,
+                "obsValid": true,
+                "hasComments": false,
+                "hasRichMedia": true
+            }]))
+            .unwrap();
+            let details = &observations[0].details;
+            assert_eq!(details.user_display_name.as_deref(), Some("Jo Birder"));
+            assert_eq!(details.obs_valid, Some(true));
+
+            let json = serde_json::to_value(&observations[0]).unwrap();
+            assert_eq!(json["checklistId"], "CL24936");
+            assert_eq!(json["obsReviewed"], true);
+            assert_eq!(json["hasRichMedia"], true);
+            let simple = serde_json::to_value(Observation::test("Whimbrel", "Beach", "")).unwrap();
+            assert!(simple.get("checklistId").is_none());
+        }
+
+        #[test]
+        fn schema_uses_tool_argument_names() {
+            let schema = serde_json::to_value(schemars::schema_for!(FetchHistoricRequest)).unwrap();
+            let properties = schema["properties"].as_object().unwrap();
+            assert!(properties.contains_key("include_provisional"));
+            assert!(properties.contains_key("categories"));
+            assert!(!properties.contains_key("cat"));
+        }
+
+        #[test]
+        fn validate_rejects_out_of_range_dates() {
+            let mut req = FetchHistoricRequest::for_date(region("US-NC"), date("1799-12-01"));
+            assert!(req.validate().is_err());
+
+            req.date = date("9999-12-01").into();
+            assert!(req.validate().unwrap_err().contains("future"));
+
+            req.date = date("2024-02-29").into();
+            assert!(req.validate().is_ok());
+        }
+
+        #[test]
+        fn deserialization_rejects_bad_codes_and_dates() {
+            for (region_code, day) in [("US/NC", "2023-05-09"), ("US-NC", "2023-02-30")] {
+                let result = serde_json::from_value::<FetchHistoricRequest>(serde_json::json!({
+                    "region_code": region_code,
+                    "date": day
+                }));
+                assert!(result.is_err());
+            }
+        }
+
+        #[test]
+        fn validate_checks_option_bounds() {
+            let day = date("2023-05-09");
+            let mut req = FetchHistoricRequest::for_date(region("US-NC"), day);
+            req.max_results = Some(0);
+            assert!(req.validate().is_err());
+
+            let mut req = FetchHistoricRequest::for_date(region("US-NC"), day);
+            req.locations = Some((0..51).map(|i| region(&format!("L{}", i))).collect());
+            assert!(req.validate().is_err());
+
+            let mut req = FetchHistoricRequest::for_date(region("US-NC"), day);
+            req.categories = Some(vec![]);
+            assert!(req.validate().is_err());
         }
     }
 }
@@ -2,6 +2,7 @@ use reqwest::Method;
 use serde::{Deserialize, Serialize};
 
 use crate::api::endpoint::Endpoint;
+use crate::ids::RegionCode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -15,7 +16,7 @@ pub enum RegionType {
 pub struct GetRegionInfoRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
+    pub region_code: RegionCode,
 }
 
 impl Endpoint for GetRegionInfoRequest {
@@ -37,7 +38,7 @@ impl Endpoint for GetRegionInfoRequest {
 pub struct GetSubRegionsRequest {
     #[serde(skip_serializing)]
     #[schemars(description = "eBird region code (e.g., US-NC)")]
-    pub region_code: String,
+    pub region_code: RegionCode,
 }
 
 impl Endpoint for GetSubRegionsRequest {
@@ -55,6 +56,55 @@ impl Endpoint for GetSubRegionsRequest {
     }
 }
 
+impl RegionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegionType::Country => "country",
+            RegionType::Subnational1 => "subnational1",
+            RegionType::Subnational2 => "subnational2",
+        }
+    }
+
+    /// The level directly below a region code ("US" -> states, "US-NC" ->
+    /// counties); `None` for counties and hotspots.
+    pub fn children_of(region_code: &str) -> Option<Self> {
+        if region_code.starts_with('L') {
+            return None;
+        }
+        match region_code.split('-').count() {
+            1 => Some(RegionType::Subnational1),
+            2 => Some(RegionType::Subnational2),
+            _ => None,
+        }
+    }
+}
+
+/// Regions of one type under a parent region.
+#[derive(Debug)]
+pub struct ListRegionsRequest {
+    pub region_type: RegionType,
+    pub parent_code: RegionCode,
+}
+
+impl Endpoint for ListRegionsRequest {
+    type Query = ();
+    type Response = Vec<SubRegion>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        format!(
+            "ref/region/list/{}/{}",
+            self.region_type.as_str(),
+            self.parent_code
+        )
+    }
+
+    fn query(&self) -> &Self::Query {
+        &()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Bounds {
     #[serde(rename = "minX")]
@@ -95,7 +145,7 @@ mod tests {
         #[test]
         fn path_includes_region_code() {
             let req = GetRegionInfoRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/region/info/US-NC");
         }
@@ -103,7 +153,7 @@ mod tests {
         #[test]
         fn path_with_different_region() {
             let req = GetRegionInfoRequest {
-                region_code: "US-CA".into(),
+                region_code: "US-CA".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/region/info/US-CA");
         }
@@ -111,7 +161,7 @@ mod tests {
         #[test]
         fn path_with_country_code() {
             let req = GetRegionInfoRequest {
-                region_code: "US".into(),
+                region_code: "US".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/region/info/US");
         }
@@ -119,7 +169,7 @@ mod tests {
         #[test]
         fn query_returns_empty_tuple() {
             let req = GetRegionInfoRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
             };
             assert_eq!(req.query(), &());
         }
@@ -131,7 +181,7 @@ mod tests {
         #[test]
         fn path_includes_region_code() {
             let req = GetSubRegionsRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/region/list/subnational2/US-NC");
         }
@@ -139,7 +189,7 @@ mod tests {
         #[test]
         fn path_with_different_region() {
             let req = GetSubRegionsRequest {
-                region_code: "US-CA".into(),
+                region_code: "US-CA".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/region/list/subnational2/US-CA");
         }
@@ -147,7 +197,7 @@ mod tests {
         #[test]
         fn path_with_country_code() {
             let req = GetSubRegionsRequest {
-                region_code: "US".into(),
+                region_code: "US".parse().unwrap(),
             };
             assert_eq!(req.path(), "ref/region/list/subnational2/US");
         }
@@ -155,7 +205,7 @@ mod tests {
         #[test]
         fn query_returns_empty_tuple() {
             let req = GetSubRegionsRequest {
-                region_code: "US-NC".into(),
+                region_code: "US-NC".parse().unwrap(),
             };
             assert_eq!(req.query(), &());
         }
@@ -191,4 +241,31 @@ mod tests {
             assert_eq!(subnational2, RegionType::Subnational2);
         }
     }
+
+    mod list_regions {
+        use super::*;
+
+        #[test]
+        fn path_includes_type_and_parent() {
+            let req = ListRegionsRequest {
+                region_type: RegionType::Subnational1,
+                parent_code: "US".parse().unwrap(),
+            };
+            assert_eq!(req.path(), "ref/region/list/subnational1/US");
+        }
+
+        #[test]
+        fn children_follow_region_level() {
+            assert_eq!(
+                RegionType::children_of("US"),
+                Some(RegionType::Subnational1)
+            );
+            assert_eq!(
+                RegionType::children_of("US-NC"),
+                Some(RegionType::Subnational2)
+            );
+            assert_eq!(RegionType::children_of("US-NC-063"), None);
+            assert_eq!(RegionType::children_of("L123456"), None);
+        }
+    }
 }
@@ -0,0 +1,183 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::api::endpoint::Endpoint;
+use crate::ids::{RegionCode, SpeciesCode};
+
+const DEFAULT_BACK_DAYS: u32 = 14;
+
+/// One entry of the eBird taxonomy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonomyEntry {
+    pub species_code: SpeciesCode,
+    pub com_name: String,
+    pub sci_name: String,
+    pub category: String,
+    #[serde(default)]
+    pub taxon_order: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub banding_codes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_com_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_sci_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaxonomyQuery {
+    pub species: SpeciesCode,
+}
+
+/// Taxonomy entries for a comma-separated list of species codes.
+#[derive(Debug)]
+pub struct FetchTaxonomyRequest {
+    pub query: TaxonomyQuery,
+}
+
+impl FetchTaxonomyRequest {
+    pub fn for_species(species_code: SpeciesCode) -> Self {
+        Self {
+            query: TaxonomyQuery {
+                species: species_code,
+            },
+        }
+    }
+}
+
+impl Endpoint for FetchTaxonomyRequest {
+    type Query = TaxonomyQuery;
+    type Response = Vec<TaxonomyEntry>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        "ref/taxonomy/ebird".to_string()
+    }
+
+    fn query(&self) -> &Self::Query {
+        &self.query
+    }
+
+    fn format(&self) -> Option<&'static str> {
+        Some("json")
+    }
+}
+
+/// One published version of the eBird taxonomy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonomyVersion {
+    pub authority_ver: f64,
+    #[serde(default)]
+    pub latest: bool,
+}
+
+/// Every taxonomy version eBird knows about; small and cheap to fetch.
+#[derive(Debug)]
+pub struct FetchTaxonomyVersionsRequest;
+
+impl Endpoint for FetchTaxonomyVersionsRequest {
+    type Query = ();
+    type Response = Vec<TaxonomyVersion>;
+
+    const METHOD: Method = Method::GET;
+
+    fn path(&self) -> String {
+        "ref/taxonomy/versions".to_string()
+    }
+
+    fn query(&self) -> &Self::Query {
+        &()
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SpeciesProfileRequest {
+    #[schemars(description = "Species code (e.g., barswa for Barn Swallow)")]
+    pub species_code: SpeciesCode,
+    #[schemars(
+        description = "eBird region code (e.g., US-NC) for recent sightings and counts by subregion"
+    )]
+    pub region_code: RegionCode,
+    #[schemars(description = "Latitude for the nearest sighting (requires lng)")]
+    pub lat: Option<f64>,
+    #[schemars(description = "Longitude for the nearest sighting (requires lat)")]
+    pub lng: Option<f64>,
+    #[schemars(
+        description = "Number of days back to look for sightings (default 14)",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+}
+
+impl SpeciesProfileRequest {
+    pub fn back(&self) -> u32 {
+        self.back.unwrap_or(DEFAULT_BACK_DAYS).clamp(1, 30)
+    }
+
+    pub fn point(&self) -> Option<(f64, f64)> {
+        self.lat.zip(self.lng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod fetch_taxonomy {
+        use super::*;
+
+        #[test]
+        fn requests_json_for_species() {
+            let req = FetchTaxonomyRequest::for_species("barswa".parse().unwrap());
+            assert_eq!(req.path(), "ref/taxonomy/ebird");
+            assert_eq!(req.format(), Some("json"));
+            assert_eq!(
+                serde_json::to_value(req.query()).unwrap(),
+                serde_json::json!({ "species": "barswa" })
+            );
+        }
+
+        #[test]
+        fn deserializes_taxonomy_entry() {
+            let entries: Vec<TaxonomyEntry> = serde_json::from_value(serde_json::json!([{
+                "sciName": "Hirundo rustica",
+                "comName": "Barn Swallow",
+                "speciesCode": "barswa",
+                "category": "species",
+                "taxonOrder": 17123.0,
+                "bandingCodes": ["BARS"],
+                "comNameCodes": [],
+                "sciNameCodes": ["HIRU"],
+                "order": "Passeriformes",
+                "familyCode": "hirund1",
+                "familyComName": "Swallows",
+                "familySciName": "Hirundinidae"
+            }]))
+            .unwrap();
+            assert_eq!(entries[0].banding_codes, vec!["BARS"]);
+            assert_eq!(entries[0].family_com_name.as_deref(), Some("Swallows"));
+        }
+    }
+
+    mod species_profile {
+        use super::*;
+
+        #[test]
+        fn point_requires_both_coordinates() {
+            let req: SpeciesProfileRequest = serde_json::from_value(serde_json::json!({
+                "species_code": "barswa",
+                "region_code": "US-NC",
+                "lat": 35.0
+            }))
+            .unwrap();
+            assert_eq!(req.point(), None);
+            assert_eq!(req.back(), 14);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use serde::Deserialize;
+
+use crate::ids::RegionCode;
+use crate::watch::WatchTarget;
+
+const DEFAULT_BACK: u32 = 7;
+const DEFAULT_INTERVAL_MINUTES: u32 = 30;
+const MIN_INTERVAL_MINUTES: u32 = 5;
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WatchNotableRequest {
+    #[schemars(description = "eBird region code to watch (e.g., US-NC). Use this or lat/lng.")]
+    pub region_code: Option<RegionCode>,
+    #[schemars(description = "Latitude of the center point (used when region_code is omitted)")]
+    pub lat: Option<f64>,
+    #[schemars(description = "Longitude of the center point (used when region_code is omitted)")]
+    pub lng: Option<f64>,
+    #[schemars(
+        description = "Radius in kilometers around the point (max 50)",
+        range(min = 0, max = 50)
+    )]
+    pub dist: Option<u32>,
+    #[schemars(
+        description = "Number of days back each poll covers (default 7)",
+        range(min = 1, max = 30)
+    )]
+    pub back: Option<u32>,
+    #[schemars(
+        description = "Minutes between polls (default 30, minimum 5)",
+        range(min = 5, max = 1440)
+    )]
+    pub interval_minutes: Option<u32>,
+}
+
+impl WatchNotableRequest {
+    /// Resolves the watch target, preferring a region code over coordinates.
+    pub(crate) fn target(&self) -> Option<WatchTarget> {
+        match (&self.region_code, self.lat.zip(self.lng)) {
+            (Some(region_code), _) => Some(WatchTarget::Region {
+                region_code: region_code.clone(),
+            }),
+            (None, Some((lat, lng))) => Some(WatchTarget::Radius {
+                lat,
+                lng,
+                dist: self.dist,
+            }),
+            (None, None) => None,
+        }
+    }
+
+    pub fn back(&self) -> u32 {
+        self.back.unwrap_or(DEFAULT_BACK)
+    }
+
+    pub fn interval_minutes(&self) -> u32 {
+        self.interval_minutes
+            .unwrap_or(DEFAULT_INTERVAL_MINUTES)
+            .max(MIN_INTERVAL_MINUTES)
+    }
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct UnwatchRequest {
+    #[schemars(description = "Watch ID returned by watch_notable (e.g., w1)")]
+    pub id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(value: serde_json::Value) -> WatchNotableRequest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn region_takes_precedence_over_coordinates() {
+        let req = request(serde_json::json!({ "region_code": "US-NC", "lat": 35.0, "lng": -79.0 }));
+        assert!(matches!(req.target(), Some(WatchTarget::Region { .. })));
+    }
+
+    #[test]
+    fn coordinates_become_radius_target() {
+        let req = request(serde_json::json!({ "lat": 35.0, "lng": -79.0, "dist": 20 }));
+        assert!(matches!(
+            req.target(),
+            Some(WatchTarget::Radius { dist: Some(20), .. })
+        ));
+    }
+
+    #[test]
+    fn missing_location_has_no_target() {
+        assert!(
+            request(serde_json::json!({ "lat": 35.0 }))
+                .target()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn applies_defaults_and_minimum_interval() {
+        let req = request(serde_json::json!({ "region_code": "US-NC", "interval_minutes": 1 }));
+        assert_eq!(req.back(), DEFAULT_BACK);
+        assert_eq!(req.interval_minutes(), MIN_INTERVAL_MINUTES);
+    }
+}
@@ -0,0 +1,611 @@
+//! Background rare-bird watcher.
+//!
+//! Clients register a region or a radius around a point. A poller fetches
+//! notable observations for each watch on its schedule, diffs them against
+//! what has already been seen, and pushes MCP notifications for new
+//! rarities, and hands them to the configured alert sinks. Watches are
+//! persisted to `watches.json` in the data directory.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rmcp::{
+    Peer, RoleServer,
+    model::{LoggingLevel, LoggingMessageNotificationParam, ResourceUpdatedNotificationParam},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::{Alert, AlertDispatcher};
+use crate::api::client::ApiClient;
+use crate::api::error::ApiError;
+use crate::ids::RegionCode;
+use crate::storage;
+use crate::sync::lock;
+use crate::tools::observations::{
+    FetchGeoNotableRequest, FetchNotableRecentRequest, RareBird, RarityView,
+};
+
+const STATE_FILE: &str = "watches.json";
+const URI_PREFIX: &str = "rubl://watches/";
+const TICK: Duration = Duration::from_secs(60);
+/// Seen keys older than this are forgotten; eBird only serves 30 days back.
+const SEEN_RETENTION_DAYS: i64 = 45;
+/// Number of new sightings kept per watch for `resources/read`.
+const MAX_RECENT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum WatchTarget {
+    Region {
+        region_code: RegionCode,
+    },
+    Radius {
+        lat: f64,
+        lng: f64,
+        dist: Option<u32>,
+    },
+}
+
+impl WatchTarget {
+    /// Region code for region watches; radius watches have none.
+    pub fn region_code(&self) -> Option<&str> {
+        match self {
+            WatchTarget::Region { region_code } => Some(region_code.as_str()),
+            WatchTarget::Radius { .. } => None,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            WatchTarget::Region { region_code } => region_code.to_string(),
+            WatchTarget::Radius { lat, lng, dist } => {
+                format!("{:.4},{:.4} ({} km)", lat, lng, dist.unwrap_or(25))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Watch {
+    pub id: String,
+    pub target: WatchTarget,
+    pub back: u32,
+    pub interval_minutes: u32,
+    pub created_at: DateTime<Utc>,
+    pub last_polled: Option<DateTime<Utc>>,
+    /// When a poll was last tried, successful or not; schedules the next one.
+    #[serde(default)]
+    last_attempt: Option<DateTime<Utc>>,
+    /// Newly seen sightings, most recent first.
+    pub recent: Vec<RareBird>,
+    /// Dedup keys of every sighting seen, with when they were last returned.
+    #[serde(default)]
+    seen: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl Watch {
+    pub fn uri(&self) -> String {
+        format!("{}{}", URI_PREFIX, self.id)
+    }
+
+    pub fn label(&self) -> String {
+        self.target.label()
+    }
+
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.last_attempt.or(self.last_polled).is_none_or(|last| {
+            now - last >= chrono::Duration::minutes(i64::from(self.interval_minutes))
+        })
+    }
+
+    /// Records a poll result and returns the sightings not seen before.
+    ///
+    /// The first poll only seeds the seen set so that registering a watch
+    /// does not report every existing rarity as new.
+    fn record(&mut self, sightings: Vec<RareBird>, now: DateTime<Utc>) -> Vec<RareBird> {
+        let seeding = self.last_polled.is_none();
+        self.last_polled = Some(now);
+        self.last_attempt = Some(now);
+
+        let mut fresh = Vec::new();
+        for sighting in sightings {
+            let key = sighting_key(&sighting);
+            if self.seen.insert(key, now).is_none() && !seeding {
+                fresh.push(sighting);
+            }
+        }
+
+        let cutoff = now - chrono::Duration::days(SEEN_RETENTION_DAYS);
+        self.seen.retain(|_, last| *last >= cutoff);
+
+        for sighting in fresh.iter().rev() {
+            self.recent.insert(0, sighting.clone());
+        }
+        self.recent.truncate(MAX_RECENT);
+        fresh
+    }
+}
+
+/// Identifies one report of one species on one checklist.
+pub fn sighting_key(sighting: &RareBird) -> String {
+    match (&sighting.submission_id, &sighting.species_code) {
+        (Some(sub_id), Some(species)) => format!("{}:{}", sub_id, species),
+        _ => format!(
+            "{}|{}|{}",
+            sighting.scientific_name,
+            sighting
+                .location_id
+                .as_deref()
+                .unwrap_or(&sighting.location),
+            sighting.observation_date
+        ),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchState {
+    next_id: u64,
+    watches: Vec<Watch>,
+}
+
+/// Summary of a watch without its seen set.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSummary {
+    pub id: String,
+    pub uri: String,
+    pub target: String,
+    pub back: u32,
+    pub interval_minutes: u32,
+    pub last_polled: Option<DateTime<Utc>>,
+    pub new_sightings: usize,
+}
+
+impl From<&Watch> for WatchSummary {
+    fn from(watch: &Watch) -> Self {
+        Self {
+            id: watch.id.clone(),
+            uri: watch.uri(),
+            target: watch.label(),
+            back: watch.back,
+            interval_minutes: watch.interval_minutes,
+            last_polled: watch.last_polled,
+            new_sightings: watch.recent.len(),
+        }
+    }
+}
+
+/// Shared handle to the watch registry and its poller.
+#[derive(Clone)]
+pub struct WatchManager {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    client: ApiClient,
+    alerts: AlertDispatcher,
+    path: Option<PathBuf>,
+    state: Mutex<WatchState>,
+    peer: Mutex<Option<Peer<RoleServer>>>,
+    subscriptions: Mutex<HashSet<String>>,
+    poller_started: AtomicBool,
+}
+
+impl WatchManager {
+    /// Loads persisted watches from the data directory.
+    pub fn load(client: ApiClient, alerts: AlertDispatcher) -> Self {
+        Self::load_from(client, alerts, storage::data_file(STATE_FILE))
+    }
+
+    /// Loads watches persisted at `path`, saving changes back to it.
+    pub(crate) fn load_from(
+        client: ApiClient,
+        alerts: AlertDispatcher,
+        path: Option<PathBuf>,
+    ) -> Self {
+        let state = path
+            .as_deref()
+            .map(storage::load_json::<WatchState>)
+            .transpose()
+            .unwrap_or_else(|e| {
+                tracing::error!(error = %e, "Failed to load watches");
+                None
+            })
+            .flatten()
+            .unwrap_or_default();
+        Self::with_state(client, alerts, path, state)
+    }
+
+    fn with_state(
+        client: ApiClient,
+        alerts: AlertDispatcher,
+        path: Option<PathBuf>,
+        state: WatchState,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client,
+                alerts,
+                path,
+                state: Mutex::new(state),
+                peer: Mutex::new(None),
+                subscriptions: Mutex::new(HashSet::new()),
+                poller_started: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Registers a watch and seeds it with the current notable sightings.
+    pub async fn add(
+        &self,
+        target: WatchTarget,
+        back: u32,
+        interval_minutes: u32,
+    ) -> Result<WatchSummary, ApiError> {
+        let sightings = self.fetch(&target, back).await?;
+        let now = Utc::now();
+        let summary = {
+            let mut state = self.lock_state();
+            state.next_id += 1;
+            let mut watch = Watch {
+                id: format!("w{}", state.next_id),
+                target,
+                back,
+                interval_minutes,
+                created_at: now,
+                last_polled: None,
+                last_attempt: None,
+                recent: Vec::new(),
+                seen: BTreeMap::new(),
+            };
+            watch.record(sightings, now);
+            let summary = WatchSummary::from(&watch);
+            state.watches.push(watch);
+            summary
+        };
+        self.persist();
+        self.notify_list_changed().await;
+        Ok(summary)
+    }
+
+    pub async fn remove(&self, id: &str) -> bool {
+        let removed = {
+            let mut state = self.lock_state();
+            let before = state.watches.len();
+            state.watches.retain(|w| w.id != id);
+            state.watches.len() != before
+        };
+        if removed {
+            self.persist();
+            self.notify_list_changed().await;
+        }
+        removed
+    }
+
+    pub fn list(&self) -> Vec<WatchSummary> {
+        self.lock_state()
+            .watches
+            .iter()
+            .map(WatchSummary::from)
+            .collect()
+    }
+
+    /// Returns a watch by its resource URI.
+    pub fn get_by_uri(&self, uri: &str) -> Option<Watch> {
+        let id = uri.strip_prefix(URI_PREFIX)?;
+        self.lock_state()
+            .watches
+            .iter()
+            .find(|w| w.id == id)
+            .cloned()
+    }
+
+    pub fn subscribe(&self, uri: String) {
+        lock(&self.inner.subscriptions).insert(uri);
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        lock(&self.inner.subscriptions).remove(uri);
+    }
+
+    /// Remembers the connected client and starts the poller on first call.
+    pub fn attach(&self, peer: Peer<RoleServer>) {
+        *lock(&self.inner.peer) = Some(peer);
+        if !self.inner.poller_started.swap(true, Ordering::SeqCst) {
+            let manager = self.clone();
+            tokio::spawn(async move { manager.run().await });
+        }
+    }
+
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(TICK);
+        loop {
+            ticker.tick().await;
+            self.poll_due().await;
+        }
+    }
+
+    async fn poll_due(&self) {
+        let now = Utc::now();
+        let due: Vec<(String, WatchTarget, u32)> = self
+            .lock_state()
+            .watches
+            .iter()
+            .filter(|w| w.is_due(now))
+            .map(|w| (w.id.clone(), w.target.clone(), w.back))
+            .collect();
+        if due.is_empty() {
+            return;
+        }
+
+        for (id, target, back) in due {
+            let sightings = match self.fetch(&target, back).await {
+                Ok(sightings) => sightings,
+                Err(e) => {
+                    tracing::error!(watch = %id, error = %e, "Watch poll failed");
+                    // Wait a full interval before trying again rather than
+                    // hammering eBird every tick while it (or the key) is down.
+                    if let Some(watch) = self.lock_state().watches.iter_mut().find(|w| w.id == id) {
+                        watch.last_attempt = Some(Utc::now());
+                    }
+                    continue;
+                }
+            };
+            let update = {
+                let mut state = self.lock_state();
+                state
+                    .watches
+                    .iter_mut()
+                    .find(|w| w.id == id)
+                    .map(|w| (w.uri(), w.label(), w.record(sightings, Utc::now())))
+            };
+            if let Some((uri, label, fresh)) = update
+                && !fresh.is_empty()
+            {
+                self.notify(&uri, &label, &fresh).await;
+                self.alert(id, &target, label, fresh);
+            }
+        }
+        self.persist();
+    }
+
+    async fn fetch(&self, target: &WatchTarget, back: u32) -> Result<Vec<RareBird>, ApiError> {
+        match target {
+            WatchTarget::Region { region_code } => {
+                self.inner
+                    .client
+                    .send(&FetchNotableRecentRequest {
+                        region_code: region_code.clone(),
+                        back: Some(back),
+                        view: RarityView::Reports,
+                    })
+                    .await
+            }
+            WatchTarget::Radius { lat, lng, dist } => {
+                self.inner
+                    .client
+                    .send(&FetchGeoNotableRequest {
+                        lat: *lat,
+                        lng: *lng,
+                        dist: *dist,
+                        back: Some(back),
+                        view: RarityView::Reports,
+                    })
+                    .await
+            }
+        }
+    }
+
+    /// Pushes `resources/updated` to subscribers, or a log message otherwise.
+    async fn notify(&self, uri: &str, label: &str, fresh: &[RareBird]) {
+        tracing::info!(watch = %label, new = fresh.len(), "New rarities");
+        let Some(peer) = lock(&self.inner.peer).clone() else {
+            return;
+        };
+        let subscribed = lock(&self.inner.subscriptions).contains(uri);
+        let result = if subscribed {
+            peer.notify_resource_updated(ResourceUpdatedNotificationParam { uri: uri.into() })
+                .await
+        } else {
+            let species: Vec<String> = fresh
+                .iter()
+                .map(|s| {
+                    format!(
+                        "{} at {} ({})",
+                        s.common_name, s.location, s.observation_date
+                    )
+                })
+                .collect();
+            peer.notify_logging_message(LoggingMessageNotificationParam {
+                level: LoggingLevel::Notice,
+                logger: Some("rubl.watch".into()),
+                data: serde_json::json!({
+                    "watch": label,
+                    "uri": uri,
+                    "newSightings": species,
+                }),
+            })
+            .await
+        };
+        if let Err(e) = result {
+            tracing::error!(error = %e, "Failed to notify client");
+        }
+    }
+
+    /// Sends new sightings to alert sinks without blocking the poller on retries.
+    fn alert(&self, watch_id: String, target: &WatchTarget, label: String, fresh: Vec<RareBird>) {
+        if self.inner.alerts.is_empty() {
+            return;
+        }
+        let alerts = self.inner.alerts.clone();
+        let region = target.region_code().map(str::to_string);
+        let alert = Alert {
+            watch_id,
+            target: label,
+            detected_at: Utc::now(),
+            sightings: fresh,
+        };
+        tokio::spawn(async move { alerts.dispatch(region.as_deref(), &alert).await });
+    }
+
+    async fn notify_list_changed(&self) {
+        let peer = lock(&self.inner.peer).clone();
+        if let Some(peer) = peer
+            && let Err(e) = peer.notify_resource_list_changed().await
+        {
+            tracing::error!(error = %e, "Failed to notify client");
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.inner.path else {
+            return;
+        };
+        let state = self.lock_state();
+        if let Err(e) = storage::save_json(path, &*state) {
+            tracing::error!(error = %e, "Failed to save watches");
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, WatchState> {
+        lock(&self.inner.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::cassette::Cassette;
+
+    fn sighting(species: &str, sub_id: &str) -> RareBird {
+        RareBird {
+            count: Some(1),
+            location_id: Some("L123456".into()),
+            latitude: Some(35.84),
+            longitude: Some(-78.79),
+            species_code: Some(species.into()),
+            submission_id: Some(sub_id.into()),
+            ..RareBird::test(species, "Lake Crabtree", "2023-05-15 10:30")
+        }
+    }
+
+    fn watch() -> Watch {
+        Watch {
+            id: "w1".into(),
+            target: WatchTarget::Region {
+                region_code: "US-NC".parse().unwrap(),
+            },
+            back: 7,
+            interval_minutes: 30,
+            created_at: Utc::now(),
+            last_polled: None,
+            last_attempt: None,
+            recent: Vec::new(),
+            seen: BTreeMap::new(),
+        }
+    }
+
+    fn manager(client: ApiClient, watches: Vec<Watch>) -> WatchManager {
+        let state = WatchState {
+            next_id: watches.len() as u64,
+            watches,
+        };
+        WatchManager::with_state(client, AlertDispatcher::new(Vec::new()), None, state)
+    }
+
+    mod record {
+        use super::*;
+
+        #[test]
+        fn first_poll_seeds_without_reporting() {
+            let mut watch = watch();
+            let fresh = watch.record(vec![sighting("whimbr", "S1")], Utc::now());
+            assert!(fresh.is_empty());
+            assert!(watch.recent.is_empty());
+            assert_eq!(watch.seen.len(), 1);
+        }
+
+        #[test]
+        fn reports_only_unseen_sightings() {
+            let mut watch = watch();
+            let now = Utc::now();
+            watch.record(vec![sighting("whimbr", "S1")], now);
+            let fresh = watch.record(
+                vec![sighting("whimbr", "S1"), sighting("whimbr", "S2")],
+                now,
+            );
+            assert_eq!(fresh.len(), 1);
+            assert_eq!(fresh[0].submission_id.as_deref(), Some("S2"));
+            assert_eq!(watch.recent.len(), 1);
+        }
+
+        #[test]
+        fn forgets_keys_past_retention() {
+            let mut watch = watch();
+            let long_ago = Utc::now() - chrono::Duration::days(SEEN_RETENTION_DAYS + 1);
+            watch.record(vec![sighting("whimbr", "S1")], long_ago);
+            watch.record(vec![], Utc::now());
+            assert!(watch.seen.is_empty());
+        }
+    }
+
+    mod schedule {
+        use super::*;
+
+        #[test]
+        fn due_when_never_polled_or_interval_elapsed() {
+            let mut watch = watch();
+            let now = Utc::now();
+            assert!(watch.is_due(now));
+            watch.last_polled = Some(now - chrono::Duration::minutes(10));
+            assert!(!watch.is_due(now));
+            watch.last_polled = Some(now - chrono::Duration::minutes(30));
+            assert!(watch.is_due(now));
+        }
+
+        #[tokio::test]
+        async fn failed_polls_wait_for_the_next_interval() {
+            let dir = std::env::temp_dir().join(format!("rubl-watch-{}", std::process::id()));
+            let client = ApiClient::new("key").with_cassette(Cassette::replay(&dir));
+            let mut stale = watch();
+            stale.last_polled = Some(Utc::now() - chrono::Duration::hours(1));
+            let manager = manager(client, vec![stale]);
+
+            manager.poll_due().await;
+            let watch = manager.get_by_uri("rubl://watches/w1").unwrap();
+            assert!(watch.last_attempt.is_some());
+            assert!(watch.last_polled < watch.last_attempt);
+            assert!(!watch.is_due(Utc::now()));
+            assert!(watch.is_due(Utc::now() + chrono::Duration::minutes(30)));
+        }
+    }
+
+    mod keys {
+        use super::*;
+
+        #[test]
+        fn falls_back_to_name_location_and_date() {
+            let mut s = sighting("whimbr", "S1");
+            s.submission_id = None;
+            assert_eq!(sighting_key(&s), "whimbr|L123456|2023-05-15 10:30");
+        }
+    }
+
+    mod uri {
+        use super::*;
+
+        #[test]
+        fn looks_up_watch_by_uri() {
+            let manager = manager(ApiClient::new("key"), vec![watch()]);
+            assert!(manager.get_by_uri("rubl://watches/w1").is_some());
+            assert!(manager.get_by_uri("rubl://watches/w2").is_none());
+            assert!(manager.get_by_uri("rubl://other/w1").is_none());
+        }
+    }
+}